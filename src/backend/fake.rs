@@ -0,0 +1,404 @@
+use std::{
+    ffi::c_void,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use windows::{
+    core::GUID,
+    Win32::{
+        Foundation::{
+            ERROR_ALREADY_EXISTS, ERROR_INVALID_HANDLE, ERROR_INVALID_PARAMETER, ERROR_NOT_FOUND,
+            HANDLE, WIN32_ERROR,
+        },
+        NetworkManagement::WiFi::{
+            WLAN_API_VERSION_2_0, WLAN_BSS_LIST, WLAN_INTERFACE_INFO_LIST, WLAN_PROFILE_INFO_LIST,
+        },
+    },
+};
+
+use crate::interfaces::WlanInterfaceState;
+
+use super::{
+    lists::{interface_info, profile_info, Allocations},
+    parse_profile_xml, WlanBackend,
+};
+
+/// A wireless interface of a [`FakeBackend`]
+#[derive(Debug, Clone)]
+pub struct FakeInterface {
+    /// GUID of the interface
+    pub guid: GUID,
+
+    /// Description of the interface
+    pub description: String,
+
+    /// State of the interface
+    pub state: WlanInterfaceState,
+
+    /// Saved profiles of the interface
+    pub profiles: Vec<FakeProfile>,
+}
+
+impl FakeInterface {
+    /// Creates a disconnected interface without saved profiles
+    pub fn new(guid: GUID, description: impl Into<String>) -> FakeInterface {
+        FakeInterface {
+            guid,
+            description: description.into(),
+            state: WlanInterfaceState::Disconnected,
+            profiles: Vec::new(),
+        }
+    }
+
+    /// Sets the state of the interface
+    pub fn with_state(mut self, state: WlanInterfaceState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Adds a saved profile to the interface
+    pub fn with_profile(mut self, profile: FakeProfile) -> Self {
+        self.profiles.push(profile);
+        self
+    }
+}
+
+/// A saved profile of a [`FakeInterface`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeProfile {
+    /// Name of the profile
+    pub name: String,
+
+    /// `WLAN_PROFILE_*` flags of the profile
+    pub flags: u32,
+}
+
+impl FakeProfile {
+    /// Creates an all-user profile
+    pub fn new(name: impl Into<String>) -> FakeProfile {
+        FakeProfile {
+            name: name.into(),
+            flags: 0,
+        }
+    }
+
+    /// Sets the `WLAN_PROFILE_*` flags of the profile
+    pub fn with_flags(mut self, flags: u32) -> Self {
+        self.flags = flags;
+        self
+    }
+}
+
+/// Static in-memory backend for testing without the Windows WLAN service. Lists are allocated in
+/// the same layout as wlanapi returns them and the backend keeps track of open handles and memory
+/// which was not freed. Connections complete immediately and scans do not find any networks.
+/// Use a [`WlanSimulator`](super::WlanSimulator) to test connections
+#[derive(Debug, Default)]
+pub struct FakeBackend {
+    state: Mutex<FakeState>,
+}
+
+/// Mutable state of a [`FakeBackend`]
+#[derive(Debug, Default)]
+struct FakeState {
+    /// Wireless interfaces on the fake system
+    interfaces: Vec<FakeInterface>,
+
+    /// Values of the open client handles
+    handles: Vec<isize>,
+
+    /// Value of the last opened client handle
+    last_handle: isize,
+
+    /// Lists which were returned and not freed
+    allocations: Allocations,
+}
+
+impl FakeBackend {
+    /// Creates a backend without any wireless interfaces
+    pub fn new() -> FakeBackend {
+        FakeBackend::default()
+    }
+
+    /// Adds a wireless interface to the backend
+    pub fn with_interface(self, interface: FakeInterface) -> Self {
+        self.add_interface(interface);
+        self
+    }
+
+    /// Adds a wireless interface to a backend which may already be in use
+    pub fn add_interface(&self, interface: FakeInterface) {
+        self.state().interfaces.push(interface);
+    }
+
+    /// Removes a wireless interface from the backend. Returns `false` if there was no interface
+    /// with the GUID
+    pub fn remove_interface(&self, guid: &GUID) -> bool {
+        let mut state = self.state();
+        let count = state.interfaces.len();
+        state.interfaces.retain(|interface| interface.guid != *guid);
+        state.interfaces.len() != count
+    }
+
+    /// Returns the number of client handles which were not closed
+    pub fn open_handles(&self) -> usize {
+        self.state().handles.len()
+    }
+
+    /// Returns the number of returned lists which were not freed
+    pub fn allocations(&self) -> usize {
+        self.state().allocations.len()
+    }
+
+    fn state(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl FakeState {
+    fn check_handle(&self, handle: HANDLE) -> Result<(), WIN32_ERROR> {
+        if self.handles.contains(&handle.0) {
+            Ok(())
+        } else {
+            Err(ERROR_INVALID_HANDLE)
+        }
+    }
+
+    /// Returns the interface with the GUID after checking the handle
+    fn interface(
+        &mut self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<&mut FakeInterface, WIN32_ERROR> {
+        self.check_handle(handle)?;
+        self.interfaces
+            .iter_mut()
+            .find(|interface| interface.guid == *interface_guid)
+            .ok_or(ERROR_NOT_FOUND)
+    }
+}
+
+impl WlanBackend for FakeBackend {
+    fn open_handle(&self, client_version: u32) -> Result<(HANDLE, u32), WIN32_ERROR> {
+        if client_version == 0 {
+            return Err(ERROR_INVALID_PARAMETER);
+        }
+
+        let mut state = self.state();
+        state.last_handle += 1;
+        let handle = state.last_handle;
+        state.handles.push(handle);
+
+        Ok((HANDLE(handle), client_version.min(WLAN_API_VERSION_2_0)))
+    }
+
+    fn close_handle(&self, handle: HANDLE) -> Result<(), WIN32_ERROR> {
+        let mut state = self.state();
+        state.check_handle(handle)?;
+        state.handles.retain(|open| *open != handle.0);
+        Ok(())
+    }
+
+    fn enum_interfaces(
+        &self,
+        handle: HANDLE,
+    ) -> Result<*mut WLAN_INTERFACE_INFO_LIST, WIN32_ERROR> {
+        let mut state = self.state();
+        state.check_handle(handle)?;
+
+        let interfaces = state
+            .interfaces
+            .iter()
+            .map(|interface| {
+                interface_info(interface.guid, &interface.description, interface.state)
+            })
+            .collect();
+
+        Ok(state.allocations.interface_list(interfaces))
+    }
+
+    fn get_profile_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_PROFILE_INFO_LIST, WIN32_ERROR> {
+        let mut state = self.state();
+
+        let profiles = state
+            .interface(handle, interface_guid)?
+            .profiles
+            .iter()
+            .map(|profile| profile_info(&profile.name, profile.flags))
+            .collect();
+
+        Ok(state.allocations.profile_list(profiles))
+    }
+
+    fn scan(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR> {
+        self.state().interface(handle, interface_guid)?;
+        Ok(())
+    }
+
+    fn get_network_bss_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_BSS_LIST, WIN32_ERROR> {
+        let mut state = self.state();
+        state.interface(handle, interface_guid)?;
+        Ok(state.allocations.bss_list(Vec::new()))
+    }
+
+    fn connect(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_name: &str,
+    ) -> Result<(), WIN32_ERROR> {
+        let mut state = self.state();
+        let interface = state.interface(handle, interface_guid)?;
+
+        if !interface
+            .profiles
+            .iter()
+            .any(|profile| profile.name == profile_name)
+        {
+            return Err(ERROR_NOT_FOUND);
+        }
+
+        interface.state = WlanInterfaceState::Connected;
+        Ok(())
+    }
+
+    fn disconnect(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR> {
+        self.state().interface(handle, interface_guid)?.state = WlanInterfaceState::Disconnected;
+        Ok(())
+    }
+
+    fn set_profile(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_xml: &str,
+        overwrite: bool,
+    ) -> Result<(), (WIN32_ERROR, u32)> {
+        let mut state = self.state();
+        let interface = state
+            .interface(handle, interface_guid)
+            .map_err(|code| (code, 0))?;
+        let profile = FakeProfile::new(parse_profile_xml(profile_xml)?.name);
+
+        match interface
+            .profiles
+            .iter_mut()
+            .find(|saved| saved.name == profile.name)
+        {
+            Some(_) if !overwrite => return Err((ERROR_ALREADY_EXISTS, 0)),
+            Some(saved) => *saved = profile,
+            None => interface.profiles.push(profile),
+        }
+        Ok(())
+    }
+
+    unsafe fn free_memory(&self, memory: *mut c_void) {
+        self.state().allocations.free(memory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use windows::Win32::NetworkManagement::WiFi::WLAN_PROFILE_GROUP_POLICY;
+
+    use super::*;
+    use crate::handle::{WlanApiVersion, WlanHandle};
+
+    const TEST_GUID: GUID = GUID::from_values(
+        0x12345678,
+        0x1234,
+        0x1234,
+        [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88],
+    );
+
+    /// Lists the interfaces and profiles of a fake backend through the public api
+    #[test]
+    fn list_interfaces_and_profiles() {
+        let backend = Arc::new(
+            FakeBackend::new()
+                .with_interface(
+                    FakeInterface::new(TEST_GUID, "Fake Wireless Adapter")
+                        .with_state(WlanInterfaceState::Connected)
+                        .with_profile(FakeProfile::new("Home"))
+                        .with_profile(
+                            FakeProfile::new("Corporate").with_flags(WLAN_PROFILE_GROUP_POLICY),
+                        ),
+                )
+                .with_interface(FakeInterface::new(GUID::zeroed(), "Second Adapter")),
+        );
+
+        {
+            let interfaces = WlanHandle::with_backend(backend.clone(), WlanApiVersion::ApiVersion2)
+                .expect("Failed to open handle")
+                .into_interfaces()
+                .expect("Failed to enumerate interfaces");
+            assert_eq!(interfaces.len(), 2);
+
+            let interface = interfaces.iter().next().expect("Missing interface");
+            assert_eq!(interface.guid(), TEST_GUID);
+            assert_eq!(
+                interface.description().as_deref(),
+                Some("Fake Wireless Adapter".as_ref())
+            );
+            assert_eq!(interface.if_state(), WlanInterfaceState::Connected);
+
+            let profiles = interface.profiles().expect("Failed to get profiles");
+            let profiles = profiles
+                .iter()
+                .map(|profile| (profile.name(), profile.group_policy_profile()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                profiles,
+                [
+                    (Some("Home".into()), false),
+                    (Some("Corporate".into()), true)
+                ]
+            );
+
+            assert_eq!(backend.open_handles(), 1);
+        }
+
+        assert_eq!(backend.open_handles(), 0);
+        assert_eq!(backend.allocations(), 0);
+    }
+
+    /// Checks the errors for invalid handles and unknown interfaces
+    #[test]
+    fn backend_errors() {
+        let backend = FakeBackend::new();
+
+        assert_eq!(
+            backend.enum_interfaces(HANDLE(1)).unwrap_err(),
+            ERROR_INVALID_HANDLE
+        );
+
+        let (handle, version) = backend.open_handle(3).expect("Failed to open handle");
+        assert_eq!(version, WLAN_API_VERSION_2_0);
+
+        assert_eq!(
+            backend.get_profile_list(handle, &TEST_GUID).unwrap_err(),
+            ERROR_NOT_FOUND
+        );
+
+        let interfaces = backend
+            .enum_interfaces(handle)
+            .expect("Failed to enumerate");
+        assert_eq!(unsafe { (*interfaces).dwNumberOfItems }, 0);
+        unsafe { backend.free_memory(interfaces.cast()) };
+
+        backend
+            .close_handle(handle)
+            .expect("Failed to close handle");
+        assert_eq!(backend.close_handle(handle), Err(ERROR_INVALID_HANDLE));
+    }
+}
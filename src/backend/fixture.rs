@@ -0,0 +1,397 @@
+use serde::{Deserialize, Serialize};
+use windows::{
+    core::GUID,
+    Win32::{
+        Foundation::BOOLEAN,
+        NetworkManagement::WiFi::{
+            DOT11_BSS_TYPE, DOT11_PHY_TYPE, WLAN_BSS_ENTRY, WLAN_INTERFACE_INFO,
+            WLAN_INTERFACE_STATE, WLAN_PROFILE_INFO, WLAN_RATE_SET,
+        },
+    },
+};
+
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::{lists::to_wide, WlanCall};
+
+/// Recorded wlanapi calls which can be replayed with a [`ReplayBackend`](super::ReplayBackend)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WlanFixture {
+    /// Calls in the order they were made
+    pub calls: Vec<RecordedCall>,
+}
+
+impl WlanFixture {
+    /// Parses a fixture from JSON
+    pub fn from_json(json: &str) -> Result<WlanFixture, WinWifiError> {
+        serde_json::from_str(json).map_err(WinWifiError::FixtureJsonError)
+    }
+
+    /// Serializes the fixture into pretty printed JSON
+    pub fn to_json(&self) -> Result<String, WinWifiError> {
+        serde_json::to_string_pretty(self).map_err(WinWifiError::FixtureJsonError)
+    }
+}
+
+/// A recorded wlanapi call with its result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    /// Function and arguments of the call
+    pub request: WlanRequest,
+
+    /// Result returned by the call
+    pub result: RecordedResult,
+}
+
+/// Function and arguments of a wlanapi call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "call", rename_all = "snake_case")]
+pub enum WlanRequest {
+    /// `WlanOpenHandle`
+    OpenHandle { client_version: u32 },
+
+    /// `WlanCloseHandle`
+    CloseHandle { handle: isize },
+
+    /// `WlanEnumInterfaces`
+    EnumInterfaces { handle: isize },
+
+    /// `WlanGetProfileList`
+    GetProfileList {
+        handle: isize,
+        #[serde(with = "crate::guid::serde_guid")]
+        interface_guid: GUID,
+    },
+
+    /// `WlanScan`
+    Scan {
+        handle: isize,
+        #[serde(with = "crate::guid::serde_guid")]
+        interface_guid: GUID,
+    },
+
+    /// `WlanGetNetworkBssList`
+    GetNetworkBssList {
+        handle: isize,
+        #[serde(with = "crate::guid::serde_guid")]
+        interface_guid: GUID,
+    },
+
+    /// `WlanConnect` with a saved profile
+    Connect {
+        handle: isize,
+        #[serde(with = "crate::guid::serde_guid")]
+        interface_guid: GUID,
+        profile_name: String,
+    },
+
+    /// `WlanDisconnect`
+    Disconnect {
+        handle: isize,
+        #[serde(with = "crate::guid::serde_guid")]
+        interface_guid: GUID,
+    },
+
+    /// `WlanSetProfile` for an all-user profile
+    SetProfile {
+        handle: isize,
+        #[serde(with = "crate::guid::serde_guid")]
+        interface_guid: GUID,
+        profile_xml: String,
+        overwrite: bool,
+    },
+}
+
+impl WlanRequest {
+    /// Returns the called function
+    pub fn call(&self) -> WlanCall {
+        match self {
+            WlanRequest::OpenHandle { .. } => WlanCall::OpenHandle,
+            WlanRequest::CloseHandle { .. } => WlanCall::CloseHandle,
+            WlanRequest::EnumInterfaces { .. } => WlanCall::EnumInterfaces,
+            WlanRequest::GetProfileList { .. } => WlanCall::GetProfileList,
+            WlanRequest::Scan { .. } => WlanCall::Scan,
+            WlanRequest::GetNetworkBssList { .. } => WlanCall::GetNetworkBssList,
+            WlanRequest::Connect { .. } => WlanCall::Connect,
+            WlanRequest::Disconnect { .. } => WlanCall::Disconnect,
+            WlanRequest::SetProfile { .. } => WlanCall::SetProfile,
+        }
+    }
+}
+
+/// Result of a wlanapi call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedResult {
+    /// The call succeeded without returning data
+    Success,
+
+    /// Client handle and negotiated api version of an opened connection
+    Handle {
+        handle: isize,
+        negotiated_version: u32,
+    },
+
+    /// List of wireless interfaces
+    Interfaces(Vec<RecordedInterface>),
+
+    /// List of saved profiles
+    Profiles(Vec<RecordedProfile>),
+
+    /// List of BSS entries
+    BssEntries(Vec<RecordedBss>),
+
+    /// Win32 error code returned by the call
+    Error(u32),
+
+    /// Win32 error code and `WLAN_REASON_CODE` of a rejected profile
+    ProfileError { error: u32, reason_code: u32 },
+}
+
+/// A recorded `WLAN_INTERFACE_INFO`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedInterface {
+    /// GUID of the interface
+    #[serde(with = "crate::guid::serde_guid")]
+    pub guid: GUID,
+
+    /// Description of the interface
+    pub description: String,
+
+    /// Raw `WLAN_INTERFACE_STATE` value
+    pub state: i32,
+}
+
+impl From<&WLAN_INTERFACE_INFO> for RecordedInterface {
+    fn from(value: &WLAN_INTERFACE_INFO) -> Self {
+        RecordedInterface {
+            guid: value.InterfaceGuid,
+            description: from_wide(&value.strInterfaceDescription),
+            state: value.isState.0,
+        }
+    }
+}
+
+impl From<&RecordedInterface> for WLAN_INTERFACE_INFO {
+    fn from(value: &RecordedInterface) -> Self {
+        WLAN_INTERFACE_INFO {
+            InterfaceGuid: value.guid,
+            strInterfaceDescription: to_wide(&value.description),
+            isState: WLAN_INTERFACE_STATE(value.state),
+        }
+    }
+}
+
+/// A recorded `WLAN_PROFILE_INFO`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedProfile {
+    /// Name of the profile
+    pub name: String,
+
+    /// `WLAN_PROFILE_*` flags of the profile
+    pub flags: u32,
+}
+
+impl From<&WLAN_PROFILE_INFO> for RecordedProfile {
+    fn from(value: &WLAN_PROFILE_INFO) -> Self {
+        RecordedProfile {
+            name: from_wide(&value.strProfileName),
+            flags: value.dwFlags,
+        }
+    }
+}
+
+impl From<&RecordedProfile> for WLAN_PROFILE_INFO {
+    fn from(value: &RecordedProfile) -> Self {
+        WLAN_PROFILE_INFO {
+            strProfileName: to_wide(&value.name),
+            dwFlags: value.flags,
+        }
+    }
+}
+
+/// A recorded `WLAN_BSS_ENTRY`. Information elements are not recorded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedBss {
+    /// SSID of the network
+    pub ssid: Ssid,
+
+    /// Identifier of the PHY the entry was received on
+    pub phy_id: u32,
+
+    /// MAC address of the access point
+    pub bssid: [u8; 6],
+
+    /// Raw `DOT11_BSS_TYPE` value
+    pub bss_type: i32,
+
+    /// Raw `DOT11_PHY_TYPE` value
+    pub phy_type: i32,
+
+    /// Received signal strength in dBm
+    pub rssi: i32,
+
+    /// Link quality from 0 to 100
+    pub link_quality: u32,
+
+    /// Whether the access point is in the regulatory domain
+    pub in_reg_domain: bool,
+
+    /// Beacon interval in time units
+    pub beacon_period: u16,
+
+    /// Timestamp from the beacon or probe response
+    pub timestamp: u64,
+
+    /// Host time the beacon or probe response was received
+    pub host_timestamp: u64,
+
+    /// Capability information field of the access point
+    pub capability_information: u16,
+
+    /// Center frequency of the channel in kHz
+    pub channel_frequency: u32,
+
+    /// Supported data rates
+    pub rates: Vec<u16>,
+}
+
+impl From<&WLAN_BSS_ENTRY> for RecordedBss {
+    fn from(value: &WLAN_BSS_ENTRY) -> Self {
+        let rate_count = (value.wlanRateSet.uRateSetLength as usize / std::mem::size_of::<u16>())
+            .min(value.wlanRateSet.usRateSet.len());
+
+        RecordedBss {
+            ssid: Ssid::from(&value.dot11Ssid),
+            phy_id: value.uPhyId,
+            bssid: value.dot11Bssid,
+            bss_type: value.dot11BssType.0,
+            phy_type: value.dot11BssPhyType.0,
+            rssi: value.lRssi,
+            link_quality: value.uLinkQuality,
+            in_reg_domain: value.bInRegDomain.as_bool(),
+            beacon_period: value.usBeaconPeriod,
+            timestamp: value.ullTimestamp,
+            host_timestamp: value.ullHostTimestamp,
+            capability_information: value.usCapabilityInformation,
+            channel_frequency: value.ulChCenterFrequency,
+            rates: value.wlanRateSet.usRateSet[..rate_count].to_vec(),
+        }
+    }
+}
+
+impl From<&RecordedBss> for WLAN_BSS_ENTRY {
+    fn from(value: &RecordedBss) -> Self {
+        let mut rate_set = WLAN_RATE_SET::default();
+        for (rate, recorded) in rate_set.usRateSet.iter_mut().zip(&value.rates) {
+            *rate = *recorded;
+        }
+        rate_set.uRateSetLength =
+            (value.rates.len().min(rate_set.usRateSet.len()) * std::mem::size_of::<u16>()) as u32;
+
+        WLAN_BSS_ENTRY {
+            dot11Ssid: (&value.ssid).into(),
+            uPhyId: value.phy_id,
+            dot11Bssid: value.bssid,
+            dot11BssType: DOT11_BSS_TYPE(value.bss_type),
+            dot11BssPhyType: DOT11_PHY_TYPE(value.phy_type),
+            lRssi: value.rssi,
+            uLinkQuality: value.link_quality,
+            bInRegDomain: BOOLEAN::from(value.in_reg_domain),
+            usBeaconPeriod: value.beacon_period,
+            ullTimestamp: value.timestamp,
+            ullHostTimestamp: value.host_timestamp,
+            usCapabilityInformation: value.capability_information,
+            ulChCenterFrequency: value.channel_frequency,
+            wlanRateSet: rate_set,
+            ulIeOffset: 0,
+            ulIeSize: 0,
+        }
+    }
+}
+
+/// Converts a null terminated UTF-16 string into a string
+fn from_wide(wide: &[u16]) -> String {
+    let length = wide.iter().position(|c| *c == 0).unwrap_or(wide.len());
+    String::from_utf16_lossy(&wide[..length])
+}
+
+#[cfg(test)]
+mod tests {
+    use windows::Win32::NetworkManagement::WiFi::{dot11_BSS_type_infrastructure, WLAN_BSS_ENTRY};
+
+    use super::*;
+
+    /// Checks that a fixture survives a JSON round trip and uses readable GUIDs
+    #[test]
+    fn json_round_trip() {
+        let guid = GUID::from_u128(0x12345678_1234_1234_1122_334455667788);
+        let fixture = WlanFixture {
+            calls: vec![
+                RecordedCall {
+                    request: WlanRequest::OpenHandle { client_version: 2 },
+                    result: RecordedResult::Handle {
+                        handle: 1,
+                        negotiated_version: 2,
+                    },
+                },
+                RecordedCall {
+                    request: WlanRequest::EnumInterfaces { handle: 1 },
+                    result: RecordedResult::Interfaces(vec![RecordedInterface {
+                        guid,
+                        description: "Wireless Adapter".into(),
+                        state: 1,
+                    }]),
+                },
+                RecordedCall {
+                    request: WlanRequest::Connect {
+                        handle: 1,
+                        interface_guid: guid,
+                        profile_name: "Home".into(),
+                    },
+                    result: RecordedResult::Error(1168),
+                },
+            ],
+        };
+
+        let json = fixture.to_json().expect("Failed to serialize fixture");
+        assert!(json.contains(r#""interface_guid": "12345678-1234-1234-1122-334455667788""#));
+        assert!(json.contains(r#""call": "connect""#));
+        assert!(json.contains(r#""error": 1168"#));
+
+        assert_eq!(
+            WlanFixture::from_json(&json).expect("Failed to parse fixture"),
+            fixture
+        );
+
+        assert!(matches!(
+            WlanFixture::from_json(&json.replace("12345678-", "1234567-")),
+            Err(WinWifiError::FixtureJsonError(_))
+        ));
+    }
+
+    /// Checks that BSS entries are converted without losing recorded fields
+    #[test]
+    fn bss_entry_round_trip() {
+        let mut entry = WLAN_BSS_ENTRY {
+            dot11Ssid: (&Ssid::from("Home")).into(),
+            dot11Bssid: [0x02, 0, 0, 0, 0, 1],
+            dot11BssType: dot11_BSS_type_infrastructure,
+            lRssi: -60,
+            uLinkQuality: 80,
+            bInRegDomain: BOOLEAN(1),
+            usBeaconPeriod: 100,
+            ulChCenterFrequency: 5_180_000,
+            ..Default::default()
+        };
+        entry.wlanRateSet.usRateSet[..3].copy_from_slice(&[12, 24, 48]);
+        entry.wlanRateSet.uRateSetLength = 6;
+
+        let recorded = RecordedBss::from(&entry);
+        assert_eq!(recorded.ssid, Ssid::from("Home"));
+        assert_eq!(recorded.rates, [12, 24, 48]);
+
+        let replayed = WLAN_BSS_ENTRY::from(&recorded);
+        assert_eq!(RecordedBss::from(&replayed), recorded);
+    }
+}
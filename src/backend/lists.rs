@@ -0,0 +1,157 @@
+use std::{alloc::Layout, collections::HashMap, ffi::c_void, mem::offset_of};
+
+use windows::{
+    core::GUID,
+    Win32::NetworkManagement::WiFi::{
+        WLAN_BSS_ENTRY, WLAN_BSS_LIST, WLAN_INTERFACE_INFO, WLAN_INTERFACE_INFO_LIST,
+        WLAN_PROFILE_INFO, WLAN_PROFILE_INFO_LIST,
+    },
+};
+
+use crate::interfaces::WlanInterfaceState;
+
+/// Lists allocated by an in-memory backend in the layout used by wlanapi. Keeps track of the
+/// lists which were not freed
+#[derive(Debug, Default)]
+pub(super) struct Allocations(HashMap<usize, Layout>);
+
+impl Allocations {
+    /// Returns the number of lists which were not freed
+    pub(super) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Allocates a list of interfaces
+    pub(super) fn interface_list(
+        &mut self,
+        interfaces: Vec<WLAN_INTERFACE_INFO>,
+    ) -> *mut WLAN_INTERFACE_INFO_LIST {
+        let header = WLAN_INTERFACE_INFO_LIST {
+            dwNumberOfItems: interfaces.len() as u32,
+            dwIndex: 0,
+            InterfaceInfo: Default::default(),
+        };
+
+        self.allocate_list(
+            header,
+            offset_of!(WLAN_INTERFACE_INFO_LIST, InterfaceInfo),
+            interfaces,
+        )
+    }
+
+    /// Allocates a list of saved profiles
+    pub(super) fn profile_list(
+        &mut self,
+        profiles: Vec<WLAN_PROFILE_INFO>,
+    ) -> *mut WLAN_PROFILE_INFO_LIST {
+        let header = WLAN_PROFILE_INFO_LIST {
+            dwNumberOfItems: profiles.len() as u32,
+            dwIndex: 0,
+            ProfileInfo: Default::default(),
+        };
+
+        self.allocate_list(
+            header,
+            offset_of!(WLAN_PROFILE_INFO_LIST, ProfileInfo),
+            profiles,
+        )
+    }
+
+    /// Allocates a list of BSS entries
+    pub(super) fn bss_list(&mut self, entries: Vec<WLAN_BSS_ENTRY>) -> *mut WLAN_BSS_LIST {
+        let items_offset = offset_of!(WLAN_BSS_LIST, wlanBssEntries);
+        let header = WLAN_BSS_LIST {
+            dwTotalSize: list_size::<WLAN_BSS_LIST, WLAN_BSS_ENTRY>(items_offset, entries.len())
+                as u32,
+            dwNumberOfItems: entries.len() as u32,
+            wlanBssEntries: Default::default(),
+        };
+
+        self.allocate_list(header, items_offset, entries)
+    }
+
+    /// Allocates a list which starts with a header followed by an array of items. The items
+    /// overwrite the placeholder item of the header
+    fn allocate_list<L, T>(&mut self, header: L, items_offset: usize, items: Vec<T>) -> *mut L {
+        let layout = Layout::from_size_align(
+            list_size::<L, T>(items_offset, items.len()),
+            std::mem::align_of::<L>(),
+        )
+        .expect("Invalid list layout");
+
+        unsafe {
+            let list = std::alloc::alloc_zeroed(layout);
+            if list.is_null() {
+                std::alloc::handle_alloc_error(layout);
+            }
+
+            list.cast::<L>().write(header);
+
+            let first_item = list.add(items_offset).cast::<T>();
+            for (index, item) in items.into_iter().enumerate() {
+                first_item.add(index).write(item);
+            }
+
+            self.0.insert(list as usize, layout);
+            list.cast()
+        }
+    }
+
+    /// Releases a list
+    ///
+    /// # Safety
+    ///
+    /// The list must not be used afterwards
+    pub(super) unsafe fn free(&mut self, memory: *mut c_void) {
+        let layout = self
+            .0
+            .remove(&(memory as usize))
+            .expect("Memory was not allocated by the backend or was already freed");
+
+        std::alloc::dealloc(memory.cast(), layout);
+    }
+}
+
+/// Returns the size of a list with a header and the number of items
+fn list_size<L, T>(items_offset: usize, count: usize) -> usize {
+    std::mem::size_of::<L>().max(items_offset + std::mem::size_of::<T>() * count)
+}
+
+/// Returns the items of a list returned by wlanapi
+///
+/// # Safety
+///
+/// The first item has to be followed by the rest of the items in memory
+pub(super) unsafe fn list_items<'a, T>(first: *const T, count: u32) -> &'a [T] {
+    std::slice::from_raw_parts(first, count as usize)
+}
+
+/// Creates the wlanapi structure of an interface
+pub(super) fn interface_info(
+    guid: GUID,
+    description: &str,
+    state: WlanInterfaceState,
+) -> WLAN_INTERFACE_INFO {
+    WLAN_INTERFACE_INFO {
+        InterfaceGuid: guid,
+        strInterfaceDescription: to_wide(description),
+        isState: state.into(),
+    }
+}
+
+/// Creates the wlanapi structure of a saved profile
+pub(super) fn profile_info(name: &str, flags: u32) -> WLAN_PROFILE_INFO {
+    WLAN_PROFILE_INFO {
+        strProfileName: to_wide(name),
+        dwFlags: flags,
+    }
+}
+
+/// Converts a string into a null terminated UTF-16 string. Strings which do not fit are truncated
+pub(super) fn to_wide<const N: usize>(value: &str) -> [u16; N] {
+    let mut wide = [0u16; N];
+    for (character, unit) in wide[..N - 1].iter_mut().zip(value.encode_utf16()) {
+        *character = unit;
+    }
+    wide
+}
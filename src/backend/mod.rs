@@ -0,0 +1,161 @@
+use std::ffi::{c_void, OsString};
+
+use ::windows::{
+    core::GUID,
+    Win32::{
+        Foundation::{ERROR_BAD_PROFILE, HANDLE, WIN32_ERROR},
+        NetworkManagement::WiFi::{
+            WLAN_BSS_LIST, WLAN_INTERFACE_INFO_LIST, WLAN_PROFILE_INFO_LIST,
+            WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA,
+        },
+    },
+};
+
+use crate::profiles::info::WlanProfile;
+
+pub mod fake;
+pub mod fixture;
+mod lists;
+pub mod recording;
+pub mod replay;
+pub mod simulator;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(windows)]
+pub use self::windows::WindowsBackend;
+pub use fake::{FakeBackend, FakeInterface, FakeProfile};
+pub use fixture::WlanFixture;
+pub use recording::RecordingBackend;
+pub use replay::ReplayBackend;
+pub use simulator::{ConnectionFailure, SimulatedBss, SimulatedInterface, WlanSimulator};
+
+/// The wlanapi functions of a [`WlanBackend`] which can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WlanCall {
+    /// `WlanOpenHandle`
+    OpenHandle,
+
+    /// `WlanCloseHandle`
+    CloseHandle,
+
+    /// `WlanEnumInterfaces`
+    EnumInterfaces,
+
+    /// `WlanGetProfileList`
+    GetProfileList,
+
+    /// `WlanScan`
+    Scan,
+
+    /// `WlanGetNetworkBssList`
+    GetNetworkBssList,
+
+    /// `WlanConnect`
+    Connect,
+
+    /// `WlanDisconnect`
+    Disconnect,
+
+    /// `WlanSetProfile`
+    SetProfile,
+}
+
+impl std::fmt::Display for WlanCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            WlanCall::OpenHandle => "WlanOpenHandle",
+            WlanCall::CloseHandle => "WlanCloseHandle",
+            WlanCall::EnumInterfaces => "WlanEnumInterfaces",
+            WlanCall::GetProfileList => "WlanGetProfileList",
+            WlanCall::Scan => "WlanScan",
+            WlanCall::GetNetworkBssList => "WlanGetNetworkBssList",
+            WlanCall::Connect => "WlanConnect",
+            WlanCall::Disconnect => "WlanDisconnect",
+            WlanCall::SetProfile => "WlanSetProfile",
+        })
+    }
+}
+
+/// The wlanapi functions used by the crate. Refer to https://learn.microsoft.com/en-us/windows/win32/api/wlanapi/ for more details.
+///
+/// Lists are returned in the memory layout used by wlanapi and have to be released with
+/// [`WlanBackend::free_memory`]. Errors are the Win32 error codes returned by wlanapi
+pub trait WlanBackend: Send + Sync {
+    /// Opens a connection to the WLAN service. Returns the client handle and the negotiated
+    /// api version
+    fn open_handle(&self, client_version: u32) -> Result<(HANDLE, u32), WIN32_ERROR>;
+
+    /// Closes a connection to the WLAN service
+    fn close_handle(&self, handle: HANDLE) -> Result<(), WIN32_ERROR>;
+
+    /// Returns the list of wireless interfaces on the system
+    fn enum_interfaces(&self, handle: HANDLE)
+        -> Result<*mut WLAN_INTERFACE_INFO_LIST, WIN32_ERROR>;
+
+    /// Returns the list of saved profiles for a wireless interface
+    fn get_profile_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_PROFILE_INFO_LIST, WIN32_ERROR>;
+
+    /// Requests a scan for wireless networks on an interface. The scan completes asynchronously
+    fn scan(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR>;
+
+    /// Returns the BSS entries of the networks found by the scans of an interface
+    fn get_network_bss_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_BSS_LIST, WIN32_ERROR>;
+
+    /// Connects an interface to the network of a saved profile. The connection completes
+    /// asynchronously
+    fn connect(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_name: &str,
+    ) -> Result<(), WIN32_ERROR>;
+
+    /// Disconnects an interface from its network
+    fn disconnect(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR>;
+
+    /// Saves an all-user profile for an interface. Errors are returned together with the
+    /// `WLAN_REASON_CODE` explaining why the profile was rejected, which is 0 for other errors
+    fn set_profile(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_xml: &str,
+        overwrite: bool,
+    ) -> Result<(), (WIN32_ERROR, u32)>;
+
+    /// Releases memory returned by the backend
+    ///
+    /// # Safety
+    ///
+    /// The memory has to be returned by the same backend and must not be used afterwards
+    unsafe fn free_memory(&self, memory: *mut c_void);
+}
+
+/// Parses the XML of a profile which is saved by an in-memory backend. Invalid documents are
+/// rejected like wlanapi rejects them
+fn parse_profile_xml(profile_xml: &str) -> Result<WlanProfile, (WIN32_ERROR, u32)> {
+    WlanProfile::from_xml(profile_xml)
+        .map_err(|_| (ERROR_BAD_PROFILE, WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA))
+}
+
+/// Converts a UTF-16 string returned by wlanapi into an OS string
+#[cfg(windows)]
+pub(crate) fn os_string_from_wide(wide: &[u16]) -> OsString {
+    std::os::windows::ffi::OsStringExt::from_wide(wide)
+}
+
+/// Converts a UTF-16 string returned by wlanapi into an OS string. Unpaired surrogates are
+/// replaced since other platforms can not represent them
+#[cfg(not(windows))]
+pub(crate) fn os_string_from_wide(wide: &[u16]) -> OsString {
+    OsString::from(String::from_utf16_lossy(wide))
+}
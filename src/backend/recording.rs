@@ -0,0 +1,299 @@
+use std::{
+    ffi::c_void,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use windows::{
+    core::GUID,
+    Win32::{
+        Foundation::{HANDLE, WIN32_ERROR},
+        NetworkManagement::WiFi::{
+            WLAN_BSS_LIST, WLAN_INTERFACE_INFO_LIST, WLAN_PROFILE_INFO_LIST,
+        },
+    },
+};
+
+use super::{
+    fixture::{RecordedCall, RecordedResult, WlanFixture, WlanRequest},
+    lists::list_items,
+    WlanBackend,
+};
+
+/// Backend which records every call made to another backend. The recorded calls can be saved
+/// as a [`WlanFixture`] and replayed with a [`ReplayBackend`](super::ReplayBackend)
+#[derive(Debug, Default)]
+pub struct RecordingBackend<B> {
+    backend: B,
+    calls: Mutex<Vec<RecordedCall>>,
+}
+
+impl<B: WlanBackend> RecordingBackend<B> {
+    /// Records the calls made to a backend
+    pub fn new(backend: B) -> RecordingBackend<B> {
+        RecordingBackend {
+            backend,
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the recorded backend
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Returns the calls recorded so far
+    pub fn fixture(&self) -> WlanFixture {
+        WlanFixture {
+            calls: self.calls().clone(),
+        }
+    }
+
+    /// Stops recording and returns the recorded calls
+    pub fn into_fixture(self) -> WlanFixture {
+        WlanFixture {
+            calls: self
+                .calls
+                .into_inner()
+                .unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+
+    fn calls(&self) -> MutexGuard<'_, Vec<RecordedCall>> {
+        self.calls.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records a call and its result
+    fn record<T>(
+        &self,
+        request: WlanRequest,
+        result: Result<T, WIN32_ERROR>,
+        recorded: impl FnOnce(&T) -> RecordedResult,
+    ) -> Result<T, WIN32_ERROR> {
+        let recorded = match &result {
+            Ok(value) => recorded(value),
+            Err(code) => RecordedResult::Error(code.0),
+        };
+
+        self.calls().push(RecordedCall {
+            request,
+            result: recorded,
+        });
+        result
+    }
+}
+
+impl<B: WlanBackend> WlanBackend for RecordingBackend<B> {
+    fn open_handle(&self, client_version: u32) -> Result<(HANDLE, u32), WIN32_ERROR> {
+        self.record(
+            WlanRequest::OpenHandle { client_version },
+            self.backend.open_handle(client_version),
+            |(handle, negotiated_version)| RecordedResult::Handle {
+                handle: handle.0,
+                negotiated_version: *negotiated_version,
+            },
+        )
+    }
+
+    fn close_handle(&self, handle: HANDLE) -> Result<(), WIN32_ERROR> {
+        self.record(
+            WlanRequest::CloseHandle { handle: handle.0 },
+            self.backend.close_handle(handle),
+            |_| RecordedResult::Success,
+        )
+    }
+
+    fn enum_interfaces(
+        &self,
+        handle: HANDLE,
+    ) -> Result<*mut WLAN_INTERFACE_INFO_LIST, WIN32_ERROR> {
+        self.record(
+            WlanRequest::EnumInterfaces { handle: handle.0 },
+            self.backend.enum_interfaces(handle),
+            |list| {
+                let interfaces = unsafe { list.as_ref() }
+                    .map(|list| unsafe {
+                        list_items(list.InterfaceInfo.as_ptr(), list.dwNumberOfItems)
+                    })
+                    .unwrap_or_default();
+
+                RecordedResult::Interfaces(interfaces.iter().map(Into::into).collect())
+            },
+        )
+    }
+
+    fn get_profile_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_PROFILE_INFO_LIST, WIN32_ERROR> {
+        self.record(
+            WlanRequest::GetProfileList {
+                handle: handle.0,
+                interface_guid: *interface_guid,
+            },
+            self.backend.get_profile_list(handle, interface_guid),
+            |list| {
+                let profiles = unsafe { list.as_ref() }
+                    .map(|list| unsafe {
+                        list_items(list.ProfileInfo.as_ptr(), list.dwNumberOfItems)
+                    })
+                    .unwrap_or_default();
+
+                RecordedResult::Profiles(profiles.iter().map(Into::into).collect())
+            },
+        )
+    }
+
+    fn scan(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR> {
+        self.record(
+            WlanRequest::Scan {
+                handle: handle.0,
+                interface_guid: *interface_guid,
+            },
+            self.backend.scan(handle, interface_guid),
+            |_| RecordedResult::Success,
+        )
+    }
+
+    fn get_network_bss_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_BSS_LIST, WIN32_ERROR> {
+        self.record(
+            WlanRequest::GetNetworkBssList {
+                handle: handle.0,
+                interface_guid: *interface_guid,
+            },
+            self.backend.get_network_bss_list(handle, interface_guid),
+            |list| {
+                let entries = unsafe { list.as_ref() }
+                    .map(|list| unsafe {
+                        list_items(list.wlanBssEntries.as_ptr(), list.dwNumberOfItems)
+                    })
+                    .unwrap_or_default();
+
+                RecordedResult::BssEntries(entries.iter().map(Into::into).collect())
+            },
+        )
+    }
+
+    fn connect(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_name: &str,
+    ) -> Result<(), WIN32_ERROR> {
+        self.record(
+            WlanRequest::Connect {
+                handle: handle.0,
+                interface_guid: *interface_guid,
+                profile_name: profile_name.to_string(),
+            },
+            self.backend.connect(handle, interface_guid, profile_name),
+            |_| RecordedResult::Success,
+        )
+    }
+
+    fn disconnect(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR> {
+        self.record(
+            WlanRequest::Disconnect {
+                handle: handle.0,
+                interface_guid: *interface_guid,
+            },
+            self.backend.disconnect(handle, interface_guid),
+            |_| RecordedResult::Success,
+        )
+    }
+
+    fn set_profile(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_xml: &str,
+        overwrite: bool,
+    ) -> Result<(), (WIN32_ERROR, u32)> {
+        let result = self
+            .backend
+            .set_profile(handle, interface_guid, profile_xml, overwrite);
+
+        self.calls().push(RecordedCall {
+            request: WlanRequest::SetProfile {
+                handle: handle.0,
+                interface_guid: *interface_guid,
+                profile_xml: profile_xml.to_string(),
+                overwrite,
+            },
+            result: match result {
+                Ok(()) => RecordedResult::Success,
+                Err((code, reason_code)) => RecordedResult::ProfileError {
+                    error: code.0,
+                    reason_code,
+                },
+            },
+        });
+        result
+    }
+
+    unsafe fn free_memory(&self, memory: *mut c_void) {
+        self.backend.free_memory(memory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use windows::Win32::Foundation::ERROR_NOT_FOUND;
+
+    use super::*;
+    use crate::{
+        backend::{fixture::RecordedProfile, FakeBackend, FakeInterface, FakeProfile, WlanCall},
+        handle::{WlanApiVersion, WlanHandle},
+    };
+
+    /// Checks that calls are recorded with their arguments, returned lists and errors
+    #[test]
+    fn record_calls() {
+        let guid = GUID::from_u128(0x12345678_1234_1234_1122_334455667788);
+        let recorder = Arc::new(RecordingBackend::new(FakeBackend::new().with_interface(
+            FakeInterface::new(guid, "Fake Adapter").with_profile(FakeProfile::new("Home")),
+        )));
+
+        {
+            let interfaces =
+                WlanHandle::with_backend(recorder.clone(), WlanApiVersion::ApiVersion2)
+                    .expect("Failed to open handle")
+                    .into_interfaces()
+                    .expect("Failed to enumerate interfaces");
+            let interface = interfaces.iter().next().expect("Missing interface");
+
+            interface.profiles().expect("Failed to get profiles");
+            assert!(interface.connect("Missing").is_err());
+        }
+
+        let calls = recorder.fixture().calls;
+        assert_eq!(
+            calls
+                .iter()
+                .map(|call| call.request.call())
+                .collect::<Vec<_>>(),
+            [
+                WlanCall::OpenHandle,
+                WlanCall::EnumInterfaces,
+                WlanCall::GetProfileList,
+                WlanCall::Connect,
+                WlanCall::CloseHandle,
+            ]
+        );
+        assert_eq!(
+            calls[2].result,
+            RecordedResult::Profiles(vec![RecordedProfile {
+                name: "Home".into(),
+                flags: 0,
+            }])
+        );
+        assert_eq!(calls[3].result, RecordedResult::Error(ERROR_NOT_FOUND.0));
+        assert_eq!(recorder.backend().allocations(), 0);
+    }
+}
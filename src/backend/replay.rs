@@ -0,0 +1,410 @@
+use std::{
+    collections::VecDeque,
+    ffi::c_void,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use windows::{
+    core::GUID,
+    Win32::{
+        Foundation::{ERROR_INVALID_STATE, HANDLE, WIN32_ERROR},
+        NetworkManagement::WiFi::{
+            WLAN_BSS_LIST, WLAN_INTERFACE_INFO_LIST, WLAN_PROFILE_INFO_LIST,
+        },
+    },
+};
+
+use super::{
+    fixture::{RecordedCall, RecordedResult, WlanFixture, WlanRequest},
+    lists::Allocations,
+    WlanBackend,
+};
+
+/// Backend which serves the calls of a [`WlanFixture`] in the recorded order. Calls have to be
+/// made with the recorded arguments, a call which does not match the fixture panics
+#[derive(Debug, Default)]
+pub struct ReplayBackend {
+    state: Mutex<ReplayState>,
+}
+
+/// Mutable state of a [`ReplayBackend`]
+#[derive(Debug, Default)]
+struct ReplayState {
+    /// Calls which were not replayed yet
+    calls: VecDeque<RecordedCall>,
+
+    /// Lists which were returned and not freed
+    allocations: Allocations,
+}
+
+impl ReplayBackend {
+    /// Creates a backend replaying the calls of a fixture
+    pub fn new(fixture: WlanFixture) -> ReplayBackend {
+        ReplayBackend {
+            state: Mutex::new(ReplayState {
+                calls: fixture.calls.into(),
+                allocations: Allocations::default(),
+            }),
+        }
+    }
+
+    /// Returns the number of recorded calls which were not replayed yet
+    pub fn remaining(&self) -> usize {
+        self.state().calls.len()
+    }
+
+    /// Returns the number of returned lists which were not freed
+    pub fn allocations(&self) -> usize {
+        self.state().allocations.len()
+    }
+
+    fn state(&self) -> MutexGuard<'_, ReplayState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replays the next call and converts its result. Calls made while a thread unwinds from a
+    /// mismatch return an error instead of panicking again
+    fn replay<T>(
+        &self,
+        request: WlanRequest,
+        convert: impl FnOnce(&RecordedResult, &mut Allocations) -> Option<T>,
+    ) -> Result<T, WIN32_ERROR> {
+        let mut state = self.state();
+
+        let recorded = match state.calls.pop_front() {
+            _ if std::thread::panicking() => return Err(ERROR_INVALID_STATE),
+            Some(recorded) if recorded.request == request => recorded,
+            Some(recorded) => panic!(
+                "wlanapi call {request:?} does not match the recorded call {:?}",
+                recorded.request
+            ),
+            None => panic!("wlanapi call {request:?} was not recorded"),
+        };
+
+        if let RecordedResult::Error(code) = recorded.result {
+            return Err(WIN32_ERROR(code));
+        }
+
+        match convert(&recorded.result, &mut state.allocations) {
+            Some(value) => Ok(value),
+            None => panic!(
+                "Recorded result {:?} does not fit the call {request:?}",
+                recorded.result
+            ),
+        }
+    }
+
+    /// Replays the next call which does not return data
+    fn replay_success(&self, request: WlanRequest) -> Result<(), WIN32_ERROR> {
+        self.replay(request, |result, _| {
+            matches!(result, RecordedResult::Success).then_some(())
+        })
+    }
+}
+
+impl WlanBackend for ReplayBackend {
+    fn open_handle(&self, client_version: u32) -> Result<(HANDLE, u32), WIN32_ERROR> {
+        self.replay(
+            WlanRequest::OpenHandle { client_version },
+            |result, _| match result {
+                RecordedResult::Handle {
+                    handle,
+                    negotiated_version,
+                } => Some((HANDLE(*handle), *negotiated_version)),
+                _ => None,
+            },
+        )
+    }
+
+    fn close_handle(&self, handle: HANDLE) -> Result<(), WIN32_ERROR> {
+        self.replay_success(WlanRequest::CloseHandle { handle: handle.0 })
+    }
+
+    fn enum_interfaces(
+        &self,
+        handle: HANDLE,
+    ) -> Result<*mut WLAN_INTERFACE_INFO_LIST, WIN32_ERROR> {
+        self.replay(
+            WlanRequest::EnumInterfaces { handle: handle.0 },
+            |result, allocations| match result {
+                RecordedResult::Interfaces(interfaces) => {
+                    Some(allocations.interface_list(interfaces.iter().map(Into::into).collect()))
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn get_profile_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_PROFILE_INFO_LIST, WIN32_ERROR> {
+        self.replay(
+            WlanRequest::GetProfileList {
+                handle: handle.0,
+                interface_guid: *interface_guid,
+            },
+            |result, allocations| match result {
+                RecordedResult::Profiles(profiles) => {
+                    Some(allocations.profile_list(profiles.iter().map(Into::into).collect()))
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn scan(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR> {
+        self.replay_success(WlanRequest::Scan {
+            handle: handle.0,
+            interface_guid: *interface_guid,
+        })
+    }
+
+    fn get_network_bss_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_BSS_LIST, WIN32_ERROR> {
+        self.replay(
+            WlanRequest::GetNetworkBssList {
+                handle: handle.0,
+                interface_guid: *interface_guid,
+            },
+            |result, allocations| match result {
+                RecordedResult::BssEntries(entries) => {
+                    Some(allocations.bss_list(entries.iter().map(Into::into).collect()))
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn connect(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_name: &str,
+    ) -> Result<(), WIN32_ERROR> {
+        self.replay_success(WlanRequest::Connect {
+            handle: handle.0,
+            interface_guid: *interface_guid,
+            profile_name: profile_name.to_string(),
+        })
+    }
+
+    fn disconnect(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR> {
+        self.replay_success(WlanRequest::Disconnect {
+            handle: handle.0,
+            interface_guid: *interface_guid,
+        })
+    }
+
+    fn set_profile(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_xml: &str,
+        overwrite: bool,
+    ) -> Result<(), (WIN32_ERROR, u32)> {
+        self.replay(
+            WlanRequest::SetProfile {
+                handle: handle.0,
+                interface_guid: *interface_guid,
+                profile_xml: profile_xml.to_string(),
+                overwrite,
+            },
+            |result, _| match result {
+                RecordedResult::Success => Some(Ok(())),
+                RecordedResult::ProfileError { error, reason_code } => {
+                    Some(Err((WIN32_ERROR(*error), *reason_code)))
+                }
+                _ => None,
+            },
+        )
+        .map_err(|code| (code, 0))?
+    }
+
+    unsafe fn free_memory(&self, memory: *mut c_void) {
+        self.state().allocations.free(memory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use windows::Win32::{
+        Foundation::ERROR_BAD_PROFILE,
+        NetworkManagement::WiFi::WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA,
+    };
+
+    use super::*;
+    use crate::{
+        backend::{RecordingBackend, SimulatedBss, SimulatedInterface, WlanCall, WlanSimulator},
+        errors::WinWifiError,
+        handle::{WlanApiVersion, WlanHandle},
+        interfaces::WlanInterfaceState,
+        profiles::WlanProfileBuilder,
+        reason::ReasonCode,
+        WlanBssEntry,
+    };
+
+    const TEST_GUID: GUID = GUID::from_values(
+        0x12345678,
+        0x1234,
+        0x1234,
+        [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88],
+    );
+
+    /// Scans and connects through the public api and returns what was observed
+    fn scan_and_connect(backend: Arc<dyn WlanBackend>) -> (String, Vec<WlanBssEntry>, bool) {
+        let interfaces = WlanHandle::with_backend(backend, WlanApiVersion::ApiVersion2)
+            .expect("Failed to open handle")
+            .into_interfaces()
+            .expect("Failed to enumerate interfaces");
+        let interface = interfaces.iter().next().expect("Missing interface");
+
+        interface.scan().expect("Failed to scan");
+        let bss_entries = interface.bss_entries().expect("Failed to get BSS entries");
+        let connected = interface.connect("Missing").is_ok() && interface.connect("Home").is_ok();
+
+        (
+            interface
+                .description()
+                .map(|description| description.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            bss_entries,
+            connected,
+        )
+    }
+
+    /// Records a session against the simulator and replays it from JSON
+    #[test]
+    fn record_and_replay() {
+        let profile = WlanProfileBuilder::new("Home")
+            .wpa2_psk("passphrase")
+            .build()
+            .expect("Failed to build profile");
+        let recorder = Arc::new(RecordingBackend::new(
+            WlanSimulator::new()
+                .with_interface(
+                    SimulatedInterface::new(TEST_GUID, "Simulated Adapter").with_profile(profile),
+                )
+                .with_bss(SimulatedBss::new("Home", [0x02, 0, 0, 0, 0, 1], -60)),
+        ));
+
+        let recorded = scan_and_connect(recorder.clone());
+        assert_eq!(recorded.1.len(), 1);
+        assert!(!recorded.2);
+
+        let json = recorder
+            .fixture()
+            .to_json()
+            .expect("Failed to serialize fixture");
+        let replay = Arc::new(ReplayBackend::new(
+            WlanFixture::from_json(&json).expect("Failed to parse fixture"),
+        ));
+
+        assert_eq!(scan_and_connect(replay.clone()), recorded);
+        assert_eq!(replay.remaining(), 0);
+        assert_eq!(replay.allocations(), 0);
+    }
+
+    /// Checks that a fixture with an interface state unknown to the crate is replayed without
+    /// panicking
+    #[test]
+    fn replay_unknown_state() {
+        let recorder = Arc::new(RecordingBackend::new(
+            WlanSimulator::new()
+                .with_interface(SimulatedInterface::new(TEST_GUID, "Simulated Adapter")),
+        ));
+        drop(
+            WlanHandle::with_backend(recorder.clone(), WlanApiVersion::ApiVersion2)
+                .expect("Failed to open handle")
+                .into_interfaces()
+                .expect("Failed to enumerate interfaces"),
+        );
+
+        let mut fixture = recorder.fixture();
+        for call in &mut fixture.calls {
+            if let RecordedResult::Interfaces(interfaces) = &mut call.result {
+                interfaces
+                    .iter_mut()
+                    .for_each(|interface| interface.state = 42);
+            }
+        }
+
+        let interfaces = WlanHandle::with_backend(
+            Arc::new(ReplayBackend::new(fixture)),
+            WlanApiVersion::ApiVersion2,
+        )
+        .expect("Failed to open handle")
+        .into_interfaces()
+        .expect("Failed to enumerate interfaces");
+        let interface = interfaces.iter().next().expect("Missing interface");
+
+        assert_eq!(interface.if_state(), WlanInterfaceState::Unknown(42));
+        assert_eq!(interface.to_owned().state, WlanInterfaceState::Unknown(42));
+    }
+
+    /// Checks that the reason code of a rejected profile is replayed into the returned error
+    #[test]
+    fn replay_rejected_profile() {
+        let profile = WlanProfileBuilder::new("Home")
+            .wpa2_psk("passphrase")
+            .build()
+            .expect("Failed to build profile");
+        let set_profile = |backend: Arc<dyn WlanBackend>| {
+            let interfaces = WlanHandle::with_backend(backend, WlanApiVersion::ApiVersion2)
+                .expect("Failed to open handle")
+                .into_interfaces()
+                .expect("Failed to enumerate interfaces");
+            let interface = interfaces.iter().next().expect("Missing interface");
+            interface.set_profile(&profile, false)
+        };
+
+        let recorder = Arc::new(RecordingBackend::new(
+            WlanSimulator::new()
+                .with_interface(SimulatedInterface::new(TEST_GUID, "Simulated Adapter")),
+        ));
+        set_profile(recorder.clone()).expect("Failed to set profile");
+
+        let mut fixture = recorder.fixture();
+        for call in &mut fixture.calls {
+            if call.request.call() == WlanCall::SetProfile {
+                call.result = RecordedResult::ProfileError {
+                    error: ERROR_BAD_PROFILE.0,
+                    reason_code: WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA,
+                };
+            }
+        }
+
+        let error = set_profile(Arc::new(ReplayBackend::new(fixture)))
+            .expect_err("Rejected profile was saved");
+        assert!(matches!(
+            error,
+            WinWifiError::BadProfile {
+                reason_code: Some(code),
+                ..
+            } if code == ReasonCode::new(WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA)
+        ));
+    }
+
+    /// Checks that calls which were not recorded are rejected
+    #[test]
+    #[should_panic(expected = "does not match the recorded call")]
+    fn replay_mismatch() {
+        let replay = ReplayBackend::new(WlanFixture {
+            calls: vec![RecordedCall {
+                request: WlanRequest::OpenHandle { client_version: 2 },
+                result: RecordedResult::Handle {
+                    handle: 1,
+                    negotiated_version: 2,
+                },
+            }],
+        });
+
+        let _ = replay.open_handle(1);
+    }
+}
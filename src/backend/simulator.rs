@@ -0,0 +1,848 @@
+use std::{
+    ffi::c_void,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use windows::{
+    core::GUID,
+    Win32::{
+        Foundation::{
+            BOOLEAN, ERROR_ALREADY_EXISTS, ERROR_BAD_PROFILE, ERROR_INVALID_HANDLE,
+            ERROR_INVALID_PARAMETER, ERROR_NOT_FOUND, HANDLE, WIN32_ERROR,
+        },
+        NetworkManagement::WiFi::{
+            dot11_BSS_type_infrastructure, WLAN_API_VERSION_2_0, WLAN_BSS_ENTRY, WLAN_BSS_LIST,
+            WLAN_INTERFACE_INFO_LIST, WLAN_PROFILE_INFO_LIST, WLAN_REASON_CODE_ASSOCIATION_FAILURE,
+            WLAN_REASON_CODE_NETWORK_NOT_AVAILABLE, WLAN_REASON_CODE_SECURITY_FAILURE,
+        },
+    },
+};
+
+use crate::{
+    interfaces::WlanInterfaceState, profiles::info::WlanProfile, reason::ReasonCode, ssid::Ssid,
+};
+
+use super::{
+    lists::{interface_info, profile_info, Allocations},
+    parse_profile_xml, WlanBackend, WlanCall,
+};
+
+/// Maximum number of steps taken by [`WlanSimulator::run_until_idle`]
+const MAX_STEPS: usize = 64;
+
+/// An access point of a [`WlanSimulator`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedBss {
+    /// SSID of the network
+    pub ssid: Ssid,
+
+    /// MAC address of the access point
+    pub bssid: [u8; 6],
+
+    /// Signal strength in dBm
+    pub rssi: i32,
+
+    /// Center frequency of the channel in kHz
+    pub channel_frequency: u32,
+}
+
+impl SimulatedBss {
+    /// Creates an access point on channel 6 of the 2.4 GHz band
+    pub fn new(ssid: impl Into<Ssid>, bssid: [u8; 6], rssi: i32) -> SimulatedBss {
+        SimulatedBss {
+            ssid: ssid.into(),
+            bssid,
+            rssi,
+            channel_frequency: 2_437_000,
+        }
+    }
+
+    /// Sets the center frequency of the channel in kHz
+    pub fn with_channel_frequency(mut self, channel_frequency: u32) -> Self {
+        self.channel_frequency = channel_frequency;
+        self
+    }
+
+    /// Whether the access point is strong enough to be found
+    fn visible(&self) -> bool {
+        self.rssi >= WlanSimulator::SENSITIVITY_RSSI
+    }
+
+    /// Creates the wlanapi structure of the access point
+    fn bss_entry(&self) -> WLAN_BSS_ENTRY {
+        WLAN_BSS_ENTRY {
+            dot11Ssid: (&self.ssid).into(),
+            dot11Bssid: self.bssid,
+            dot11BssType: dot11_BSS_type_infrastructure,
+            lRssi: self.rssi,
+            uLinkQuality: ((self.rssi + 100) * 2).clamp(0, 100) as u32,
+            bInRegDomain: BOOLEAN(1),
+            usBeaconPeriod: 100,
+            ulChCenterFrequency: self.channel_frequency,
+            ..Default::default()
+        }
+    }
+}
+
+/// A wireless interface of a [`WlanSimulator`]
+#[derive(Debug, Clone)]
+pub struct SimulatedInterface {
+    /// GUID of the interface
+    pub guid: GUID,
+
+    /// Description of the interface
+    pub description: String,
+
+    /// Saved profiles of the interface
+    pub profiles: Vec<WlanProfile>,
+}
+
+impl SimulatedInterface {
+    /// Creates an interface without saved profiles
+    pub fn new(guid: GUID, description: impl Into<String>) -> SimulatedInterface {
+        SimulatedInterface {
+            guid,
+            description: description.into(),
+            profiles: Vec::new(),
+        }
+    }
+
+    /// Adds a saved profile to the interface
+    pub fn with_profile(mut self, profile: WlanProfile) -> Self {
+        self.profiles.push(profile);
+        self
+    }
+}
+
+/// A failure injected into the next connection attempt of an interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionFailure {
+    /// The access point rejects the association
+    Association,
+
+    /// The authentication with the network fails
+    Authentication,
+}
+
+impl ConnectionFailure {
+    /// Reason code reported for the failure
+    pub fn reason_code(self) -> ReasonCode {
+        ReasonCode::new(match self {
+            ConnectionFailure::Association => WLAN_REASON_CODE_ASSOCIATION_FAILURE,
+            ConnectionFailure::Authentication => WLAN_REASON_CODE_SECURITY_FAILURE,
+        })
+    }
+}
+
+/// Simulated WLAN service for testing connection logic without a wireless card. Connections walk
+/// through the discovering, associating and authenticating states one [`WlanSimulator::step`] at
+/// a time. Connected interfaces roam to a stronger access point of the network when their signal
+/// gets weak and disconnect when no access point is left
+#[derive(Debug, Default)]
+pub struct WlanSimulator {
+    state: Mutex<SimulatorState>,
+}
+
+/// Mutable state of a [`WlanSimulator`]
+#[derive(Debug, Default)]
+struct SimulatorState {
+    /// Wireless interfaces of the simulated system
+    interfaces: Vec<InterfaceState>,
+
+    /// Access points in range of the simulated system
+    bss_entries: Vec<SimulatedBss>,
+
+    /// Failures injected into the next calls of wlanapi functions
+    call_failures: Vec<(WlanCall, WIN32_ERROR)>,
+
+    /// Values of the open client handles
+    handles: Vec<isize>,
+
+    /// Value of the last opened client handle
+    last_handle: isize,
+
+    /// Lists which were returned and not freed
+    allocations: Allocations,
+}
+
+/// Connection state of a simulated interface
+#[derive(Debug)]
+struct InterfaceState {
+    /// Configuration of the interface
+    interface: SimulatedInterface,
+
+    /// Current state of the interface
+    state: WlanInterfaceState,
+
+    /// SSID of the network which is connected or being connected to
+    ssid: Option<Ssid>,
+
+    /// Access point which is connected or being connected to
+    bssid: Option<[u8; 6]>,
+
+    /// Access points found by the last scan
+    scanned: Vec<SimulatedBss>,
+
+    /// Failure injected into the next connection attempt
+    failure: Option<ConnectionFailure>,
+
+    /// Reason code of the last failed or lost connection
+    reason_code: Option<ReasonCode>,
+
+    /// States the interface went through
+    history: Vec<WlanInterfaceState>,
+}
+
+impl WlanSimulator {
+    /// Weakest signal in dBm of an access point which can be found and connected to
+    pub const SENSITIVITY_RSSI: i32 = -90;
+
+    /// Signal in dBm below which a connected interface roams to a stronger access point
+    pub const ROAMING_RSSI: i32 = -75;
+
+    /// How much stronger in dB another access point has to be for an interface to roam to it
+    pub const ROAMING_HYSTERESIS: i32 = 10;
+
+    /// Creates a simulator without interfaces or access points
+    pub fn new() -> WlanSimulator {
+        WlanSimulator::default()
+    }
+
+    /// Adds a disconnected wireless interface
+    pub fn with_interface(self, interface: SimulatedInterface) -> Self {
+        self.state().interfaces.push(InterfaceState {
+            interface,
+            state: WlanInterfaceState::Disconnected,
+            ssid: None,
+            bssid: None,
+            scanned: Vec::new(),
+            failure: None,
+            reason_code: None,
+            history: Vec::new(),
+        });
+        self
+    }
+
+    /// Adds an access point in range
+    pub fn with_bss(self, bss: SimulatedBss) -> Self {
+        self.add_bss(bss);
+        self
+    }
+
+    /// Adds an access point in range of a simulator which may already be in use
+    pub fn add_bss(&self, bss: SimulatedBss) {
+        self.state().bss_entries.push(bss);
+    }
+
+    /// Removes an access point. Returns `false` if there was no access point with the BSSID
+    pub fn remove_bss(&self, bssid: [u8; 6]) -> bool {
+        let mut state = self.state();
+        let count = state.bss_entries.len();
+        state.bss_entries.retain(|bss| bss.bssid != bssid);
+        state.bss_entries.len() != count
+    }
+
+    /// Changes the signal strength of an access point. Returns `false` if there was no access
+    /// point with the BSSID
+    pub fn set_rssi(&self, bssid: [u8; 6], rssi: i32) -> bool {
+        self.state()
+            .bss_entries
+            .iter_mut()
+            .find(|bss| bss.bssid == bssid)
+            .map(|bss| bss.rssi = rssi)
+            .is_some()
+    }
+
+    /// Makes the next connection attempt of an interface fail
+    pub fn fail_next_connection(&self, interface_guid: &GUID, failure: ConnectionFailure) {
+        if let Some(interface) = self.state().interface_mut(interface_guid) {
+            interface.failure = Some(failure);
+        }
+    }
+
+    /// Makes the next call of a wlanapi function return an error code
+    pub fn fail_next_call(&self, call: WlanCall, error: WIN32_ERROR) {
+        self.state().call_failures.push((call, error));
+    }
+
+    /// Advances the connection of every interface by one state. Returns `false` if no
+    /// interface changed its state
+    pub fn step(&self) -> bool {
+        let mut state = self.state();
+        let SimulatorState {
+            interfaces,
+            bss_entries,
+            ..
+        } = &mut *state;
+
+        let mut changed = false;
+        for interface in interfaces {
+            changed |= interface.step(bss_entries);
+        }
+        changed
+    }
+
+    /// Steps until no interface changes its state. Returns the number of steps which changed
+    /// an interface
+    pub fn run_until_idle(&self) -> usize {
+        (0..MAX_STEPS).take_while(|_| self.step()).count()
+    }
+
+    /// Returns the current state of an interface
+    pub fn interface_state(&self, interface_guid: &GUID) -> Option<WlanInterfaceState> {
+        self.state()
+            .interface(interface_guid)
+            .map(|interface| interface.state)
+    }
+
+    /// Returns the states an interface went through
+    pub fn history(&self, interface_guid: &GUID) -> Vec<WlanInterfaceState> {
+        self.state()
+            .interface(interface_guid)
+            .map(|interface| interface.history.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the access point an interface is connected to
+    pub fn connected_bssid(&self, interface_guid: &GUID) -> Option<[u8; 6]> {
+        self.state()
+            .interface(interface_guid)
+            .filter(|interface| interface.state == WlanInterfaceState::Connected)
+            .and_then(|interface| interface.bssid)
+    }
+
+    /// Returns the reason code of the last failed or lost connection of an interface
+    pub fn reason_code(&self, interface_guid: &GUID) -> Option<ReasonCode> {
+        self.state()
+            .interface(interface_guid)
+            .and_then(|interface| interface.reason_code)
+    }
+
+    /// Returns the number of client handles which were not closed
+    pub fn open_handles(&self) -> usize {
+        self.state().handles.len()
+    }
+
+    /// Returns the number of returned lists which were not freed
+    pub fn allocations(&self) -> usize {
+        self.state().allocations.len()
+    }
+
+    fn state(&self) -> MutexGuard<'_, SimulatorState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl SimulatorState {
+    fn interface(&self, interface_guid: &GUID) -> Option<&InterfaceState> {
+        self.interfaces
+            .iter()
+            .find(|interface| interface.interface.guid == *interface_guid)
+    }
+
+    fn interface_mut(&mut self, interface_guid: &GUID) -> Option<&mut InterfaceState> {
+        self.interfaces
+            .iter_mut()
+            .find(|interface| interface.interface.guid == *interface_guid)
+    }
+
+    /// Returns the injected failure of a call, then checks the handle
+    fn begin_call(&mut self, call: WlanCall, handle: HANDLE) -> Result<(), WIN32_ERROR> {
+        if let Some(index) = self
+            .call_failures
+            .iter()
+            .position(|(failing_call, _)| *failing_call == call)
+        {
+            return Err(self.call_failures.remove(index).1);
+        }
+
+        if call != WlanCall::OpenHandle && !self.handles.contains(&handle.0) {
+            return Err(ERROR_INVALID_HANDLE);
+        }
+
+        Ok(())
+    }
+
+    /// Begins a call for an interface and returns the interface
+    fn begin_interface_call(
+        &mut self,
+        call: WlanCall,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<&mut InterfaceState, WIN32_ERROR> {
+        self.begin_call(call, handle)?;
+        self.interface_mut(interface_guid).ok_or(ERROR_NOT_FOUND)
+    }
+}
+
+impl InterfaceState {
+    /// Moves the interface to a new state
+    fn transition(&mut self, state: WlanInterfaceState) -> bool {
+        self.state = state;
+        self.history.push(state);
+        true
+    }
+
+    /// Ends the connection attempt or connection with a reason code
+    fn fail(&mut self, reason_code: ReasonCode) -> bool {
+        self.ssid = None;
+        self.bssid = None;
+        self.reason_code = Some(reason_code);
+        self.transition(WlanInterfaceState::Disconnected)
+    }
+
+    /// Takes the injected failure if it is for the current stage of the connection
+    fn take_failure(&mut self, failure: ConnectionFailure) -> bool {
+        self.failure
+            .take_if(|injected| *injected == failure)
+            .is_some()
+    }
+
+    /// Advances the connection by one state
+    fn step(&mut self, bss_entries: &[SimulatedBss]) -> bool {
+        if self.state == WlanInterfaceState::Disconnecting {
+            return self.transition(WlanInterfaceState::Disconnected);
+        }
+
+        let Some(ssid) = &self.ssid else {
+            return false;
+        };
+
+        let current = self.bssid.and_then(|bssid| {
+            bss_entries
+                .iter()
+                .find(|bss| bss.bssid == bssid && bss.visible())
+        });
+        let strongest = bss_entries
+            .iter()
+            .filter(|bss| bss.ssid == *ssid && bss.visible() && Some(bss.bssid) != self.bssid)
+            .max_by_key(|bss| bss.rssi);
+
+        match self.state {
+            WlanInterfaceState::Discovering => match strongest {
+                Some(bss) => {
+                    self.bssid = Some(bss.bssid);
+                    self.transition(WlanInterfaceState::Associating)
+                }
+                None => self.fail(ReasonCode::new(WLAN_REASON_CODE_NETWORK_NOT_AVAILABLE)),
+            },
+            WlanInterfaceState::Associating => {
+                if self.take_failure(ConnectionFailure::Association) || current.is_none() {
+                    self.fail(ConnectionFailure::Association.reason_code())
+                } else {
+                    self.transition(WlanInterfaceState::Authenticating)
+                }
+            }
+            WlanInterfaceState::Authenticating => {
+                if self.take_failure(ConnectionFailure::Authentication) {
+                    self.fail(ConnectionFailure::Authentication.reason_code())
+                } else {
+                    self.transition(WlanInterfaceState::Connected)
+                }
+            }
+            WlanInterfaceState::Connected => match (current, strongest) {
+                (None, None) => self.fail(ReasonCode::new(WLAN_REASON_CODE_NETWORK_NOT_AVAILABLE)),
+                (None, Some(bss)) => {
+                    self.bssid = Some(bss.bssid);
+                    self.transition(WlanInterfaceState::Associating)
+                }
+                (Some(current), Some(bss))
+                    if current.rssi < WlanSimulator::ROAMING_RSSI
+                        && bss.rssi >= current.rssi + WlanSimulator::ROAMING_HYSTERESIS =>
+                {
+                    self.bssid = Some(bss.bssid);
+                    self.transition(WlanInterfaceState::Associating)
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl WlanBackend for WlanSimulator {
+    fn open_handle(&self, client_version: u32) -> Result<(HANDLE, u32), WIN32_ERROR> {
+        let mut state = self.state();
+        state.begin_call(WlanCall::OpenHandle, HANDLE::default())?;
+
+        if client_version == 0 {
+            return Err(ERROR_INVALID_PARAMETER);
+        }
+
+        state.last_handle += 1;
+        let handle = state.last_handle;
+        state.handles.push(handle);
+
+        Ok((HANDLE(handle), client_version.min(WLAN_API_VERSION_2_0)))
+    }
+
+    fn close_handle(&self, handle: HANDLE) -> Result<(), WIN32_ERROR> {
+        let mut state = self.state();
+        state.begin_call(WlanCall::CloseHandle, handle)?;
+        state.handles.retain(|open| *open != handle.0);
+        Ok(())
+    }
+
+    fn enum_interfaces(
+        &self,
+        handle: HANDLE,
+    ) -> Result<*mut WLAN_INTERFACE_INFO_LIST, WIN32_ERROR> {
+        let mut state = self.state();
+        state.begin_call(WlanCall::EnumInterfaces, handle)?;
+
+        let interfaces = state
+            .interfaces
+            .iter()
+            .map(|interface| {
+                interface_info(
+                    interface.interface.guid,
+                    &interface.interface.description,
+                    interface.state,
+                )
+            })
+            .collect();
+
+        Ok(state.allocations.interface_list(interfaces))
+    }
+
+    fn get_profile_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_PROFILE_INFO_LIST, WIN32_ERROR> {
+        let mut state = self.state();
+
+        let profiles = state
+            .begin_interface_call(WlanCall::GetProfileList, handle, interface_guid)?
+            .interface
+            .profiles
+            .iter()
+            .map(|profile| profile_info(&profile.name, 0))
+            .collect();
+
+        Ok(state.allocations.profile_list(profiles))
+    }
+
+    fn scan(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR> {
+        let mut state = self.state();
+
+        let visible = state
+            .bss_entries
+            .iter()
+            .filter(|bss| bss.visible())
+            .cloned()
+            .collect();
+
+        state
+            .begin_interface_call(WlanCall::Scan, handle, interface_guid)?
+            .scanned = visible;
+        Ok(())
+    }
+
+    fn get_network_bss_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_BSS_LIST, WIN32_ERROR> {
+        let mut state = self.state();
+
+        let entries = state
+            .begin_interface_call(WlanCall::GetNetworkBssList, handle, interface_guid)?
+            .scanned
+            .iter()
+            .map(SimulatedBss::bss_entry)
+            .collect();
+
+        Ok(state.allocations.bss_list(entries))
+    }
+
+    fn connect(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_name: &str,
+    ) -> Result<(), WIN32_ERROR> {
+        let mut state = self.state();
+        let interface = state.begin_interface_call(WlanCall::Connect, handle, interface_guid)?;
+
+        let ssid = interface
+            .interface
+            .profiles
+            .iter()
+            .find(|profile| profile.name == profile_name)
+            .ok_or(ERROR_NOT_FOUND)?
+            .ssid_config
+            .ssid
+            .ssid()
+            .map_err(|_| ERROR_BAD_PROFILE)?;
+
+        interface.ssid = Some(ssid);
+        interface.bssid = None;
+        interface.reason_code = None;
+        interface.transition(WlanInterfaceState::Discovering);
+        Ok(())
+    }
+
+    fn disconnect(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR> {
+        let mut state = self.state();
+        let interface = state.begin_interface_call(WlanCall::Disconnect, handle, interface_guid)?;
+
+        if interface.state != WlanInterfaceState::Disconnected {
+            interface.ssid = None;
+            interface.bssid = None;
+            interface.transition(WlanInterfaceState::Disconnecting);
+        }
+        Ok(())
+    }
+
+    fn set_profile(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_xml: &str,
+        overwrite: bool,
+    ) -> Result<(), (WIN32_ERROR, u32)> {
+        let mut state = self.state();
+        let interface = state
+            .begin_interface_call(WlanCall::SetProfile, handle, interface_guid)
+            .map_err(|code| (code, 0))?;
+        let profile = parse_profile_xml(profile_xml)?;
+
+        match interface
+            .interface
+            .profiles
+            .iter_mut()
+            .find(|saved| saved.name == profile.name)
+        {
+            Some(_) if !overwrite => return Err((ERROR_ALREADY_EXISTS, 0)),
+            Some(saved) => *saved = profile,
+            None => interface.interface.profiles.push(profile),
+        }
+        Ok(())
+    }
+
+    unsafe fn free_memory(&self, memory: *mut c_void) {
+        self.state().allocations.free(memory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use windows::Win32::{
+        Foundation::ERROR_BUSY, NetworkManagement::WiFi::WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA,
+    };
+
+    use super::*;
+    use crate::{
+        errors::{WinWifiError, WlanErrorContext},
+        handle::{WlanApiVersion, WlanHandle},
+        profiles::WlanProfileBuilder,
+    };
+
+    const TEST_GUID: GUID = GUID::from_values(
+        0x12345678,
+        0x1234,
+        0x1234,
+        [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88],
+    );
+
+    const NEAR_BSSID: [u8; 6] = [0x02, 0, 0, 0, 0, 1];
+    const FAR_BSSID: [u8; 6] = [0x02, 0, 0, 0, 0, 2];
+    const OTHER_BSSID: [u8; 6] = [0x02, 0, 0, 0, 0, 3];
+
+    /// Creates a simulator with one interface, a saved profile for the `Home` network and access
+    /// points of two networks
+    fn simulator() -> Arc<WlanSimulator> {
+        let profile = WlanProfileBuilder::new("Home")
+            .wpa2_psk("passphrase")
+            .build()
+            .expect("Failed to build profile");
+
+        Arc::new(
+            WlanSimulator::new()
+                .with_interface(
+                    SimulatedInterface::new(TEST_GUID, "Simulated Adapter").with_profile(profile),
+                )
+                .with_bss(SimulatedBss::new("Home", NEAR_BSSID, -50))
+                .with_bss(SimulatedBss::new("Home", FAR_BSSID, -70))
+                .with_bss(SimulatedBss::new("Other", OTHER_BSSID, -95)),
+        )
+    }
+
+    /// Opens a handle to the simulator
+    fn open(simulator: &Arc<WlanSimulator>) -> WlanHandle {
+        WlanHandle::with_backend(simulator.clone(), WlanApiVersion::ApiVersion2)
+            .expect("Failed to open handle")
+    }
+
+    /// Checks that a connection walks through the connection states to the strongest access point
+    #[test]
+    fn connect_to_strongest_bss() {
+        let simulator = simulator();
+
+        {
+            let interfaces = open(&simulator)
+                .into_interfaces()
+                .expect("Failed to enumerate interfaces");
+            let interface = interfaces.iter().next().expect("Missing interface");
+
+            assert!(interface.bss_entries().unwrap().is_empty());
+            interface.scan().expect("Failed to scan");
+
+            let bss_entries = interface.bss_entries().expect("Failed to get BSS entries");
+            assert_eq!(
+                bss_entries
+                    .iter()
+                    .map(|bss| (bss.ssid.to_string(), bss.bssid, bss.link_quality))
+                    .collect::<Vec<_>>(),
+                [
+                    ("Home".to_string(), NEAR_BSSID, 100),
+                    ("Home".to_string(), FAR_BSSID, 60)
+                ]
+            );
+
+            interface.connect("Home").expect("Failed to connect");
+            assert!(interface.connect("Missing").is_err());
+        }
+
+        assert_eq!(simulator.run_until_idle(), 3);
+        assert_eq!(
+            simulator.history(&TEST_GUID),
+            [
+                WlanInterfaceState::Discovering,
+                WlanInterfaceState::Associating,
+                WlanInterfaceState::Authenticating,
+                WlanInterfaceState::Connected,
+            ]
+        );
+        assert_eq!(simulator.connected_bssid(&TEST_GUID), Some(NEAR_BSSID));
+
+        let interfaces = open(&simulator).into_interfaces().unwrap();
+        let interface = interfaces.iter().next().unwrap();
+        assert_eq!(interface.if_state(), WlanInterfaceState::Connected);
+
+        interface.disconnect().expect("Failed to disconnect");
+        simulator.run_until_idle();
+        assert_eq!(
+            simulator.interface_state(&TEST_GUID),
+            Some(WlanInterfaceState::Disconnected)
+        );
+
+        drop(interfaces);
+        assert_eq!(simulator.open_handles(), 0);
+        assert_eq!(simulator.allocations(), 0);
+    }
+
+    /// Checks injected connection and call failures
+    #[test]
+    fn injected_failures() {
+        let simulator = simulator();
+        let interfaces = open(&simulator).into_interfaces().unwrap();
+        let interface = interfaces.iter().next().unwrap();
+
+        simulator.fail_next_connection(&TEST_GUID, ConnectionFailure::Authentication);
+        interface.connect("Home").unwrap();
+        simulator.run_until_idle();
+
+        assert_eq!(
+            simulator.interface_state(&TEST_GUID),
+            Some(WlanInterfaceState::Disconnected)
+        );
+        assert_eq!(
+            simulator.reason_code(&TEST_GUID),
+            Some(ReasonCode::new(WLAN_REASON_CODE_SECURITY_FAILURE))
+        );
+
+        simulator.fail_next_call(WlanCall::Connect, ERROR_BUSY);
+        assert!(matches!(
+            interface.connect("Home"),
+            Err(WinWifiError::Busy(WlanErrorContext {
+                operation: WlanCall::Connect,
+                interface_guid: Some(TEST_GUID),
+                ..
+            }))
+        ));
+
+        interface.connect("Home").unwrap();
+        simulator.run_until_idle();
+        assert_eq!(simulator.connected_bssid(&TEST_GUID), Some(NEAR_BSSID));
+        assert_eq!(simulator.reason_code(&TEST_GUID), None);
+
+        simulator.remove_bss(NEAR_BSSID);
+        simulator.remove_bss(FAR_BSSID);
+        interface.connect("Home").unwrap();
+        simulator.run_until_idle();
+        assert_eq!(
+            simulator.reason_code(&TEST_GUID),
+            Some(ReasonCode::new(WLAN_REASON_CODE_NETWORK_NOT_AVAILABLE))
+        );
+    }
+
+    /// Saves profiles and checks the reason code of a rejected profile
+    #[test]
+    fn save_profiles() {
+        let simulator = simulator();
+        let interfaces = open(&simulator).into_interfaces().unwrap();
+        let interface = interfaces.iter().next().unwrap();
+
+        let profile = WlanProfileBuilder::new("Guest")
+            .ssid("Home")
+            .open()
+            .build()
+            .unwrap();
+        interface.set_profile(&profile, false).unwrap();
+        assert!(matches!(
+            interface.set_profile(&profile, false),
+            Err(WinWifiError::Win32Error { code, .. }) if code == ERROR_ALREADY_EXISTS
+        ));
+        interface.set_profile(&profile, true).unwrap();
+
+        interface.connect("Guest").unwrap();
+        simulator.run_until_idle();
+        assert_eq!(simulator.connected_bssid(&TEST_GUID), Some(NEAR_BSSID));
+
+        let (handle, _) = simulator.open_handle(2).unwrap();
+        assert_eq!(
+            simulator.set_profile(handle, &TEST_GUID, "<WLANProfile/>", true),
+            Err((ERROR_BAD_PROFILE, WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA))
+        );
+        simulator.close_handle(handle).unwrap();
+    }
+
+    /// Checks roaming between the access points of a network and losing the connection
+    #[test]
+    fn roaming() {
+        let simulator = simulator();
+        let interfaces = open(&simulator).into_interfaces().unwrap();
+        let interface = interfaces.iter().next().unwrap();
+
+        interface.connect("Home").unwrap();
+        simulator.run_until_idle();
+        assert_eq!(simulator.connected_bssid(&TEST_GUID), Some(NEAR_BSSID));
+
+        // Weak but not enough stronger access point to roam to
+        simulator.set_rssi(NEAR_BSSID, -78);
+        assert!(!simulator.step());
+
+        simulator.set_rssi(NEAR_BSSID, -85);
+        assert_eq!(simulator.run_until_idle(), 3);
+        assert_eq!(simulator.connected_bssid(&TEST_GUID), Some(FAR_BSSID));
+
+        simulator.remove_bss(FAR_BSSID);
+        simulator.run_until_idle();
+        assert_eq!(simulator.connected_bssid(&TEST_GUID), Some(NEAR_BSSID));
+
+        simulator.set_rssi(NEAR_BSSID, -95);
+        simulator.run_until_idle();
+        assert_eq!(
+            simulator.interface_state(&TEST_GUID),
+            Some(WlanInterfaceState::Disconnected)
+        );
+        assert_eq!(
+            simulator.reason_code(&TEST_GUID),
+            Some(ReasonCode::new(WLAN_REASON_CODE_NETWORK_NOT_AVAILABLE))
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::ffi::c_void;
+
+use windows::{
+    core::{GUID, HSTRING, PCWSTR},
+    Win32::{
+        Foundation::{HANDLE, INVALID_HANDLE_VALUE, WIN32_ERROR},
+        NetworkManagement::WiFi::{
+            dot11_BSS_type_any, wlan_connection_mode_profile, WlanCloseHandle, WlanConnect,
+            WlanDisconnect, WlanEnumInterfaces, WlanFreeMemory, WlanGetNetworkBssList,
+            WlanGetProfileList, WlanOpenHandle, WlanScan, WlanSetProfile, WLAN_BSS_LIST,
+            WLAN_CONNECTION_PARAMETERS, WLAN_INTERFACE_INFO_LIST, WLAN_PROFILE_INFO_LIST,
+        },
+    },
+};
+
+use super::WlanBackend;
+
+/// Backend which calls the Windows WLAN api
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsBackend;
+
+impl WlanBackend for WindowsBackend {
+    fn open_handle(&self, client_version: u32) -> Result<(HANDLE, u32), WIN32_ERROR> {
+        let mut negotiated_version = 0;
+        let mut handle = INVALID_HANDLE_VALUE;
+
+        check(unsafe {
+            WlanOpenHandle(client_version, None, &mut negotiated_version, &mut handle)
+        })?;
+
+        Ok((handle, negotiated_version))
+    }
+
+    fn close_handle(&self, handle: HANDLE) -> Result<(), WIN32_ERROR> {
+        check(unsafe { WlanCloseHandle(handle, None) })
+    }
+
+    fn enum_interfaces(
+        &self,
+        handle: HANDLE,
+    ) -> Result<*mut WLAN_INTERFACE_INFO_LIST, WIN32_ERROR> {
+        let mut interface_list_ptr = std::ptr::null_mut();
+        check(unsafe { WlanEnumInterfaces(handle, None, &mut interface_list_ptr) })?;
+
+        Ok(interface_list_ptr)
+    }
+
+    fn get_profile_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_PROFILE_INFO_LIST, WIN32_ERROR> {
+        let mut profile_list_ptr = std::ptr::null_mut();
+        check(unsafe { WlanGetProfileList(handle, interface_guid, None, &mut profile_list_ptr) })?;
+
+        Ok(profile_list_ptr)
+    }
+
+    fn scan(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR> {
+        check(unsafe { WlanScan(handle, interface_guid, None, None, None) })
+    }
+
+    fn get_network_bss_list(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+    ) -> Result<*mut WLAN_BSS_LIST, WIN32_ERROR> {
+        let mut bss_list_ptr = std::ptr::null_mut();
+        check(unsafe {
+            WlanGetNetworkBssList(
+                handle,
+                interface_guid,
+                None,
+                dot11_BSS_type_any,
+                false,
+                None,
+                &mut bss_list_ptr,
+            )
+        })?;
+
+        Ok(bss_list_ptr)
+    }
+
+    fn connect(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_name: &str,
+    ) -> Result<(), WIN32_ERROR> {
+        let profile_name = HSTRING::from(profile_name);
+        let parameters = WLAN_CONNECTION_PARAMETERS {
+            wlanConnectionMode: wlan_connection_mode_profile,
+            strProfile: PCWSTR(profile_name.as_ptr()),
+            pDot11Ssid: std::ptr::null_mut(),
+            pDesiredBssidList: std::ptr::null_mut(),
+            dot11BssType: dot11_BSS_type_any,
+            dwFlags: 0,
+        };
+
+        check(unsafe { WlanConnect(handle, interface_guid, &parameters, None) })
+    }
+
+    fn disconnect(&self, handle: HANDLE, interface_guid: &GUID) -> Result<(), WIN32_ERROR> {
+        check(unsafe { WlanDisconnect(handle, interface_guid, None) })
+    }
+
+    fn set_profile(
+        &self,
+        handle: HANDLE,
+        interface_guid: &GUID,
+        profile_xml: &str,
+        overwrite: bool,
+    ) -> Result<(), (WIN32_ERROR, u32)> {
+        let profile_xml = HSTRING::from(profile_xml);
+        let mut reason_code = 0;
+
+        check(unsafe {
+            WlanSetProfile(
+                handle,
+                interface_guid,
+                0,
+                &profile_xml,
+                PCWSTR::null(),
+                overwrite,
+                None,
+                &mut reason_code,
+            )
+        })
+        .map_err(|code| (code, reason_code))
+    }
+
+    unsafe fn free_memory(&self, memory: *mut c_void) {
+        WlanFreeMemory(memory);
+    }
+}
+
+/// Converts the error code returned by a wlanapi function into a result
+fn check(code: u32) -> Result<(), WIN32_ERROR> {
+    match WIN32_ERROR(code) {
+        code if code.is_ok() => Ok(()),
+        code => Err(code),
+    }
+}
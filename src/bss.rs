@@ -0,0 +1,34 @@
+use windows::Win32::NetworkManagement::WiFi::WLAN_BSS_ENTRY;
+
+use crate::ssid::Ssid;
+
+/// An access point found by the scans of a wireless interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WlanBssEntry {
+    /// SSID of the network
+    pub ssid: Ssid,
+
+    /// MAC address of the access point
+    pub bssid: [u8; 6],
+
+    /// Received signal strength in dBm
+    pub rssi: i32,
+
+    /// Link quality from 0 to 100
+    pub link_quality: u32,
+
+    /// Center frequency of the channel in kHz
+    pub channel_frequency: u32,
+}
+
+impl From<&WLAN_BSS_ENTRY> for WlanBssEntry {
+    fn from(value: &WLAN_BSS_ENTRY) -> Self {
+        WlanBssEntry {
+            ssid: Ssid::from(&value.dot11Ssid),
+            bssid: value.dot11Bssid,
+            rssi: value.lRssi,
+            link_quality: value.uLinkQuality,
+            channel_frequency: value.ulChCenterFrequency,
+        }
+    }
+}
@@ -1,7 +1,291 @@
+use std::fmt::Display;
+
 use thiserror::Error;
+use windows::{
+    core::GUID,
+    Win32::Foundation::{
+        ERROR_ACCESS_DENIED, ERROR_BAD_PROFILE, ERROR_BUSY, ERROR_INVALID_PARAMETER,
+        ERROR_NOT_FOUND, ERROR_NOT_SUPPORTED, ERROR_SERVICE_NOT_ACTIVE, WIN32_ERROR,
+    },
+};
+
+use crate::{backend::WlanCall, guid::GuidRef, reason::ReasonCode};
 
 #[derive(Error, Debug)]
 pub enum WinWifiError {
-    #[error("call to Windows API returned an error code")]
-    Win32Error(#[from] windows::core::Error),
+    #[error("{0}: access denied")]
+    AccessDenied(WlanErrorContext),
+
+    #[error("{0}: interface or profile not found")]
+    NotFound(WlanErrorContext),
+
+    #[error("{0}: invalid parameter")]
+    InvalidParameter(WlanErrorContext),
+
+    #[error("{0}: the WLAN AutoConfig service is not running")]
+    ServiceNotRunning(WlanErrorContext),
+
+    #[error("{0}: not supported")]
+    NotSupported(WlanErrorContext),
+
+    #[error("{0}: the interface is busy")]
+    Busy(WlanErrorContext),
+
+    #[error("{context}: invalid profile{}", reason_code.map(|code| format!(": {code}")).unwrap_or_default())]
+    BadProfile {
+        context: WlanErrorContext,
+        reason_code: Option<ReasonCode>,
+    },
+
+    #[error("{context}: returned error code {}", code.0)]
+    Win32Error {
+        context: WlanErrorContext,
+        code: WIN32_ERROR,
+    },
+
+    #[error("wireless profile XML could not be processed")]
+    ProfileXmlError(#[from] quick_xml::DeError),
+
+    #[error("wireless profiles could not be read")]
+    ProfileReadError(#[from] std::io::Error),
+
+    #[error("invalid wireless profile settings: {0}")]
+    InvalidProfile(String),
+
+    #[error("invalid SSID: {0}")]
+    InvalidSsid(String),
+
+    #[error("invalid GUID: {0}")]
+    InvalidGuid(String),
+
+    #[error("ONC configuration could not be processed")]
+    OncJsonError(#[source] serde_json::Error),
+
+    #[error("WLAN fixture could not be processed")]
+    FixtureJsonError(#[source] serde_json::Error),
+
+    #[cfg(feature = "qrcode")]
+    #[error("QR code could not be created")]
+    QrCodeError(#[from] qrcode::types::QrError),
+
+    #[cfg(feature = "qrcode")]
+    #[error("QR code image could not be encoded")]
+    QrCodeImageError(#[from] png::EncodingError),
+}
+
+impl WinWifiError {
+    /// Maps an error code returned by a wlanapi function to an error
+    pub fn from_win32(code: WIN32_ERROR, context: WlanErrorContext) -> WinWifiError {
+        match code {
+            ERROR_ACCESS_DENIED => WinWifiError::AccessDenied(context),
+            ERROR_NOT_FOUND => WinWifiError::NotFound(context),
+            ERROR_INVALID_PARAMETER => WinWifiError::InvalidParameter(context),
+            ERROR_SERVICE_NOT_ACTIVE => WinWifiError::ServiceNotRunning(context),
+            ERROR_NOT_SUPPORTED => WinWifiError::NotSupported(context),
+            ERROR_BUSY => WinWifiError::Busy(context),
+            ERROR_BAD_PROFILE => WinWifiError::BadProfile {
+                context,
+                reason_code: None,
+            },
+            code => WinWifiError::Win32Error { context, code },
+        }
+    }
+
+    /// Returns the error code returned by the wlanapi function
+    pub fn code(&self) -> Option<WIN32_ERROR> {
+        match self {
+            WinWifiError::AccessDenied(_) => Some(ERROR_ACCESS_DENIED),
+            WinWifiError::NotFound(_) => Some(ERROR_NOT_FOUND),
+            WinWifiError::InvalidParameter(_) => Some(ERROR_INVALID_PARAMETER),
+            WinWifiError::ServiceNotRunning(_) => Some(ERROR_SERVICE_NOT_ACTIVE),
+            WinWifiError::NotSupported(_) => Some(ERROR_NOT_SUPPORTED),
+            WinWifiError::Busy(_) => Some(ERROR_BUSY),
+            WinWifiError::BadProfile { .. } => Some(ERROR_BAD_PROFILE),
+            WinWifiError::Win32Error { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// Returns the call which failed
+    pub fn context(&self) -> Option<&WlanErrorContext> {
+        match self {
+            WinWifiError::AccessDenied(context)
+            | WinWifiError::NotFound(context)
+            | WinWifiError::InvalidParameter(context)
+            | WinWifiError::ServiceNotRunning(context)
+            | WinWifiError::NotSupported(context)
+            | WinWifiError::Busy(context)
+            | WinWifiError::BadProfile { context, .. }
+            | WinWifiError::Win32Error { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+}
+
+/// The wlanapi call which failed, with the interface and profile it was made for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WlanErrorContext {
+    /// Function which returned the error
+    pub operation: WlanCall,
+
+    /// GUID of the interface the call was made for
+    pub interface_guid: Option<GUID>,
+
+    /// Name of the profile the call was made for
+    pub profile_name: Option<String>,
+}
+
+impl WlanErrorContext {
+    /// Creates the context of a call which is not made for an interface
+    pub fn new(operation: WlanCall) -> WlanErrorContext {
+        WlanErrorContext {
+            operation,
+            interface_guid: None,
+            profile_name: None,
+        }
+    }
+
+    /// Sets the interface the call was made for
+    pub fn with_interface(mut self, interface_guid: &GUID) -> Self {
+        self.interface_guid = Some(*interface_guid);
+        self
+    }
+
+    /// Sets the profile the call was made for
+    pub fn with_profile(mut self, profile_name: impl Into<String>) -> Self {
+        self.profile_name = Some(profile_name.into());
+        self
+    }
+
+    /// Creates the error for a code returned by the call
+    pub(crate) fn error(self, code: WIN32_ERROR) -> WinWifiError {
+        WinWifiError::from_win32(code, self)
+    }
+
+    /// Creates the error for a code and the `WLAN_REASON_CODE` returned by the call. The reason
+    /// code explains why a profile was rejected
+    pub(crate) fn error_with_reason(self, code: WIN32_ERROR, reason_code: u32) -> WinWifiError {
+        match WinWifiError::from_win32(code, self) {
+            WinWifiError::BadProfile { context, .. } if reason_code != 0 => {
+                WinWifiError::BadProfile {
+                    context,
+                    reason_code: Some(ReasonCode::new(reason_code)),
+                }
+            }
+            error => error,
+        }
+    }
+}
+
+impl Display for WlanErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.operation)?;
+
+        if let Some(interface_guid) = &self.interface_guid {
+            write!(f, " for interface {}", GuidRef::from(interface_guid))?;
+        }
+
+        if let Some(profile_name) = &self.profile_name {
+            write!(f, " with profile '{profile_name}'")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use windows::Win32::Foundation::{ERROR_INVALID_HANDLE, ERROR_SUCCESS};
+
+    use super::*;
+
+    const TEST_GUID: GUID = GUID::from_values(
+        0x12345678,
+        0x1234,
+        0x1234,
+        [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88],
+    );
+
+    /// Checks that raw codes map to typed errors and back
+    #[test]
+    fn map_win32_codes() {
+        let context = WlanErrorContext::new(WlanCall::Scan).with_interface(&TEST_GUID);
+
+        for (code, expected) in [
+            (ERROR_ACCESS_DENIED, "AccessDenied"),
+            (ERROR_NOT_FOUND, "NotFound"),
+            (ERROR_INVALID_PARAMETER, "InvalidParameter"),
+            (ERROR_SERVICE_NOT_ACTIVE, "ServiceNotRunning"),
+            (ERROR_NOT_SUPPORTED, "NotSupported"),
+            (ERROR_BUSY, "Busy"),
+            (ERROR_BAD_PROFILE, "BadProfile"),
+            (ERROR_INVALID_HANDLE, "Win32Error"),
+            (ERROR_SUCCESS, "Win32Error"),
+        ] {
+            let error = WinWifiError::from_win32(code, context.clone());
+            assert!(
+                format!("{error:?}").starts_with(expected),
+                "Error code {} was mapped to {error:?}",
+                code.0
+            );
+            assert_eq!(error.code(), Some(code));
+            assert_eq!(error.context(), Some(&context));
+        }
+
+        assert!(WinWifiError::InvalidSsid(String::new()).code().is_none());
+    }
+
+    /// Checks that only rejected profiles keep the reason code of the call
+    #[test]
+    fn reason_codes() {
+        let context = WlanErrorContext::new(WlanCall::SetProfile)
+            .with_interface(&TEST_GUID)
+            .with_profile("Home");
+
+        assert_eq!(
+            context
+                .clone()
+                .error_with_reason(ERROR_BAD_PROFILE, 524289)
+                .to_string(),
+            "WlanSetProfile for interface 12345678-1234-1234-1122-334455667788 with profile 'Home': invalid profile: The profile XML does not match the schema (WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA)"
+        );
+        assert!(matches!(
+            context.clone().error_with_reason(ERROR_BAD_PROFILE, 0),
+            WinWifiError::BadProfile {
+                reason_code: None,
+                ..
+            }
+        ));
+        assert!(matches!(
+            context.error_with_reason(ERROR_ACCESS_DENIED, 524289),
+            WinWifiError::AccessDenied(_)
+        ));
+    }
+
+    /// Checks that error messages name the call, interface and profile
+    #[test]
+    fn error_messages() {
+        let context = WlanErrorContext::new(WlanCall::Connect)
+            .with_interface(&TEST_GUID)
+            .with_profile("Home");
+
+        assert_eq!(
+            context.clone().error(ERROR_ACCESS_DENIED).to_string(),
+            "WlanConnect for interface 12345678-1234-1234-1122-334455667788 with profile 'Home': access denied"
+        );
+        assert_eq!(
+            WinWifiError::BadProfile {
+                context: context.clone(),
+                reason_code: Some(ReasonCode::new(524289)),
+            }
+            .to_string(),
+            "WlanConnect for interface 12345678-1234-1234-1122-334455667788 with profile 'Home': invalid profile: The profile XML does not match the schema (WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA)"
+        );
+        assert_eq!(
+            WlanErrorContext::new(WlanCall::OpenHandle)
+                .error(ERROR_INVALID_HANDLE)
+                .to_string(),
+            "WlanOpenHandle: returned error code 6"
+        );
+    }
 }
@@ -1,7 +1,10 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use windows::core::GUID;
 
+use crate::errors::WinWifiError;
+
 /// Reference to a Windows GUID with added functionality
 #[derive(Debug)]
 #[repr(transparent)]
@@ -15,7 +18,7 @@ impl<'a> GuidRef<'a> {
         GuidRef(&*guid)
     }
 
-    pub(crate) fn as_ptr(&self) -> *const GUID {
+    pub(crate) fn as_guid(&self) -> &'a GUID {
         self.0
     }
 }
@@ -53,6 +56,156 @@ impl Display for GuidRef<'_> {
     }
 }
 
+/// Owned GUID of a wireless interface which can be parsed, compared, hashed and serialized.
+/// Ordering follows the hyphenated string form
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct InterfaceGuid(GUID);
+
+impl InterfaceGuid {
+    /// Creates a GUID from its 128 bit value
+    pub const fn from_u128(value: u128) -> InterfaceGuid {
+        InterfaceGuid(GUID::from_u128(value))
+    }
+
+    /// Returns the 128 bit value of the GUID
+    pub const fn to_u128(&self) -> u128 {
+        self.0.to_u128()
+    }
+
+    /// Returns a reference to the GUID
+    pub fn as_guid_ref(&self) -> GuidRef<'_> {
+        GuidRef(&self.0)
+    }
+
+    /// Returns the Windows GUID
+    pub fn as_guid(&self) -> &GUID {
+        &self.0
+    }
+}
+
+impl PartialOrd for InterfaceGuid {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InterfaceGuid {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.to_u128().cmp(&other.to_u128())
+    }
+}
+
+impl std::cmp::PartialEq<GUID> for InterfaceGuid {
+    fn eq(&self, other: &GUID) -> bool {
+        &self.0 == other
+    }
+}
+
+impl From<GUID> for InterfaceGuid {
+    fn from(value: GUID) -> Self {
+        InterfaceGuid(value)
+    }
+}
+
+impl From<&GUID> for InterfaceGuid {
+    fn from(value: &GUID) -> Self {
+        InterfaceGuid(*value)
+    }
+}
+
+impl From<InterfaceGuid> for GUID {
+    fn from(value: InterfaceGuid) -> Self {
+        value.0
+    }
+}
+
+impl From<GuidRef<'_>> for InterfaceGuid {
+    fn from(value: GuidRef<'_>) -> Self {
+        InterfaceGuid(*value.0)
+    }
+}
+
+impl<'a> From<&'a InterfaceGuid> for GuidRef<'a> {
+    fn from(value: &'a InterfaceGuid) -> Self {
+        GuidRef(&value.0)
+    }
+}
+
+impl Display for InterfaceGuid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_guid_ref().fmt(f)
+    }
+}
+
+/// Parses the hyphenated form printed by `netsh wlan show interfaces`, the braced form used in
+/// the registry and the compact form without hyphens. Hex digits can be in either case
+impl FromStr for InterfaceGuid {
+    type Err = WinWifiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || WinWifiError::InvalidGuid(format!("'{s}' is not a valid GUID"));
+
+        let guid = s.trim();
+        let guid = match guid.strip_prefix('{') {
+            Some(braced) => braced.strip_suffix('}').ok_or_else(invalid)?,
+            None => guid,
+        };
+
+        let digits = match guid.len() {
+            32 => guid.to_string(),
+            36 if [8, 13, 18, 23]
+                .into_iter()
+                .all(|index| guid.as_bytes()[index] == b'-') =>
+            {
+                guid.replace('-', "")
+            }
+            _ => return Err(invalid()),
+        };
+
+        if digits.len() != 32 || !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        u128::from_str_radix(&digits, 16)
+            .map(InterfaceGuid::from_u128)
+            .map_err(|_| invalid())
+    }
+}
+
+/// Serialized in the hyphenated form
+impl Serialize for InterfaceGuid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for InterfaceGuid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Serializes Windows GUIDs like [`InterfaceGuid`]
+pub(crate) mod serde_guid {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use windows::core::GUID;
+
+    use super::InterfaceGuid;
+
+    pub(crate) fn serialize<S: Serializer>(guid: &GUID, serializer: S) -> Result<S::Ok, S::Error> {
+        InterfaceGuid::from(guid).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<GUID, D::Error> {
+        InterfaceGuid::deserialize(deserializer).map(GUID::from)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +225,72 @@ mod tests {
 
         assert_eq!(guid_string.as_str(), TEST_GUID_STRING);
     }
+
+    /// Checks that the forms of a GUID found in netsh output, the registry and config files parse
+    #[test]
+    fn parse_interface_guid() {
+        let guid = InterfaceGuid::from_u128(0x12345678_0123_1234_1122_334455667788);
+
+        assert_eq!(
+            "{1234ABCD-0123-1234-1122-334455667788}"
+                .parse::<InterfaceGuid>()
+                .unwrap(),
+            InterfaceGuid::from_u128(0x1234abcd_0123_1234_1122_334455667788)
+        );
+
+        for input in [
+            "12345678-0123-1234-1122-334455667788",
+            "{12345678-0123-1234-1122-334455667788}",
+            "12345678012312341122334455667788",
+            " 12345678-0123-1234-1122-334455667788\r\n",
+        ] {
+            assert_eq!(input.parse::<InterfaceGuid>().unwrap(), guid, "{input:?}");
+        }
+
+        for input in [
+            "",
+            "{12345678-0123-1234-1122-334455667788",
+            "12345678-0123-1234-1122-3344556677",
+            "1234567-80123-1234-1122-334455667788",
+            "12345678-0123-1234-1122-33445566778g",
+            "+2345678012312341122334455667788",
+        ] {
+            assert!(matches!(
+                input.parse::<InterfaceGuid>(),
+                Err(WinWifiError::InvalidGuid(_))
+            ));
+        }
+    }
+
+    /// Checks the conversions, ordering and serialization of owned GUIDs
+    #[test]
+    fn interface_guid() {
+        let raw = GUID::from_u128(0x12345678_0123_1234_1122_334455667788);
+        let guid = InterfaceGuid::from(GuidRef::from(&raw));
+
+        assert_eq!(guid, raw);
+        assert_eq!(GuidRef::from(&guid), raw);
+        assert_eq!(GUID::from(guid), raw);
+        assert_eq!(guid.to_string(), GuidRef::from(&raw).to_string());
+
+        let mut guids = [
+            InterfaceGuid::from_u128(0x2 << 64),
+            InterfaceGuid::from_u128(0x1 << 120),
+            guid,
+        ];
+        guids.sort();
+        assert_eq!(
+            guids.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "00000000-0000-0002-0000-000000000000",
+                "01000000-0000-0000-0000-000000000000",
+                "12345678-0123-1234-1122-334455667788",
+            ]
+        );
+
+        let json = serde_json::to_string(&guid).unwrap();
+        assert_eq!(json, r#""12345678-0123-1234-1122-334455667788""#);
+        assert_eq!(serde_json::from_str::<InterfaceGuid>(&json).unwrap(), guid);
+        assert!(serde_json::from_str::<InterfaceGuid>(r#""not a guid""#).is_err());
+    }
 }
@@ -1,9 +1,21 @@
-use windows::Win32::{
-    Foundation::{HANDLE, INVALID_HANDLE_VALUE, WIN32_ERROR},
-    NetworkManagement::WiFi::{WlanOpenHandle, WLAN_API_VERSION_1_0, WLAN_API_VERSION_2_0},
+use std::sync::Arc;
+
+use windows::{
+    core::GUID,
+    Win32::{
+        Foundation::HANDLE,
+        NetworkManagement::WiFi::{WLAN_API_VERSION_1_0, WLAN_API_VERSION_2_0},
+    },
 };
 
-use crate::{errors::WinWifiError, interfaces::WlanInterfaces};
+use crate::{
+    backend::{WlanBackend, WlanCall},
+    bss::WlanBssEntry,
+    errors::{WinWifiError, WlanErrorContext},
+    interfaces::WlanInterfaces,
+    profiles::info::WlanProfile,
+    snapshot::{InterfaceInfo, ProfileInfo},
+};
 
 /// Windows WLAN Api version. Refer to https://learn.microsoft.com/en-us/windows/win32/api/wlanapi/nf-wlanapi-wlanopenhandle for more details.
 #[derive(Default)]
@@ -27,45 +39,188 @@ impl From<WlanApiVersion> for u32 {
 }
 
 /// Object for interacting with the Windows WLAN subsystem
-#[repr(transparent)]
-pub struct WlanHandle(HANDLE);
+pub struct WlanHandle {
+    /// Client handle returned by the backend
+    handle: HANDLE,
+
+    /// Backend which performs the WLAN api calls
+    backend: Arc<dyn WlanBackend>,
+}
 
 impl WlanHandle {
+    #[cfg(windows)]
     pub fn new() -> Result<WlanHandle, WinWifiError> {
         Self::with_api_version(WlanApiVersion::ApiVersion2)
     }
 
     #[allow(unused)]
-    pub(crate) const fn new_invalid() -> WlanHandle {
-        Self(INVALID_HANDLE_VALUE)
+    pub(crate) fn new_invalid() -> WlanHandle {
+        WlanHandle {
+            handle: windows::Win32::Foundation::INVALID_HANDLE_VALUE,
+            backend: Arc::new(crate::backend::FakeBackend::new()),
+        }
     }
 
-    pub(crate) const unsafe fn as_ptr(&self) -> *const HANDLE {
-        &self.0
+    /// Returns the raw client handle
+    pub(crate) fn raw(&self) -> HANDLE {
+        self.handle
     }
 
-    pub fn with_api_version(version: WlanApiVersion) -> Result<WlanHandle, WinWifiError> {
-        let mut negotiated_version = 0;
-        let mut handle = INVALID_HANDLE_VALUE;
+    /// Returns the backend which performs the WLAN api calls
+    pub(crate) fn backend(&self) -> &dyn WlanBackend {
+        self.backend.as_ref()
+    }
 
-        WIN32_ERROR(unsafe {
-            WlanOpenHandle(version.into(), None, &mut negotiated_version, &mut handle)
-        })
-        .ok()?;
+    #[cfg(windows)]
+    pub fn with_api_version(version: WlanApiVersion) -> Result<WlanHandle, WinWifiError> {
+        Self::with_backend(Arc::new(crate::backend::WindowsBackend), version)
+    }
 
-        Ok(WlanHandle(handle))
+    /// Opens a handle using the specified backend instead of the Windows WLAN api, e.g. a
+    /// [`FakeBackend`](crate::backend::FakeBackend) for testing
+    pub fn with_backend(
+        backend: Arc<dyn WlanBackend>,
+        version: WlanApiVersion,
+    ) -> Result<WlanHandle, WinWifiError> {
+        let (handle, _) = backend
+            .open_handle(version.into())
+            .map_err(|code| WlanErrorContext::new(WlanCall::OpenHandle).error(code))?;
+        Ok(WlanHandle { handle, backend })
     }
 
     pub fn into_interfaces(self) -> Result<WlanInterfaces, WinWifiError> {
         WlanInterfaces::with_handle(self)
     }
+
+    /// Returns owned copies of the wireless interfaces on the system
+    pub(crate) fn interface_infos(&self) -> Result<Vec<InterfaceInfo>, WinWifiError> {
+        let interface_list_ptr = self
+            .backend
+            .enum_interfaces(self.handle)
+            .map_err(|code| WlanErrorContext::new(WlanCall::EnumInterfaces).error(code))?;
+
+        let interfaces = unsafe {
+            let first_interface = std::ptr::addr_of!((*interface_list_ptr).InterfaceInfo[0]);
+            (0..(*interface_list_ptr).dwNumberOfItems as usize)
+                .map(|index| InterfaceInfo::from(&*first_interface.add(index)))
+                .collect()
+        };
+
+        unsafe { self.backend.free_memory(interface_list_ptr.cast()) };
+        Ok(interfaces)
+    }
+
+    /// Returns owned copies of the saved profiles of an interface
+    pub(crate) fn profile_infos(
+        &self,
+        interface_guid: &GUID,
+    ) -> Result<Vec<ProfileInfo>, WinWifiError> {
+        let profile_list_ptr = self
+            .backend
+            .get_profile_list(self.handle, interface_guid)
+            .map_err(|code| {
+                WlanErrorContext::new(WlanCall::GetProfileList)
+                    .with_interface(interface_guid)
+                    .error(code)
+            })?;
+
+        let profiles = unsafe {
+            let first_profile = std::ptr::addr_of!((*profile_list_ptr).ProfileInfo[0]);
+            (0..(*profile_list_ptr).dwNumberOfItems as usize)
+                .map(|index| ProfileInfo::from(&*first_profile.add(index)))
+                .collect()
+        };
+
+        unsafe { self.backend.free_memory(profile_list_ptr.cast()) };
+        Ok(profiles)
+    }
+
+    /// Requests a scan for wireless networks on an interface
+    pub(crate) fn scan(&self, interface_guid: &GUID) -> Result<(), WinWifiError> {
+        self.backend
+            .scan(self.handle, interface_guid)
+            .map_err(|code| {
+                WlanErrorContext::new(WlanCall::Scan)
+                    .with_interface(interface_guid)
+                    .error(code)
+            })
+    }
+
+    /// Returns the access points found by the scans of an interface
+    pub(crate) fn bss_entries(
+        &self,
+        interface_guid: &GUID,
+    ) -> Result<Vec<WlanBssEntry>, WinWifiError> {
+        let bss_list_ptr = self
+            .backend
+            .get_network_bss_list(self.handle, interface_guid)
+            .map_err(|code| {
+                WlanErrorContext::new(WlanCall::GetNetworkBssList)
+                    .with_interface(interface_guid)
+                    .error(code)
+            })?;
+
+        let entries = unsafe {
+            let first_entry = std::ptr::addr_of!((*bss_list_ptr).wlanBssEntries[0]);
+            (0..(*bss_list_ptr).dwNumberOfItems as usize)
+                .map(|index| WlanBssEntry::from(&*first_entry.add(index)))
+                .collect()
+        };
+
+        unsafe { self.backend.free_memory(bss_list_ptr.cast()) };
+        Ok(entries)
+    }
+
+    /// Connects an interface to the network of a saved profile
+    pub(crate) fn connect(
+        &self,
+        interface_guid: &GUID,
+        profile_name: &str,
+    ) -> Result<(), WinWifiError> {
+        self.backend
+            .connect(self.handle, interface_guid, profile_name)
+            .map_err(|code| {
+                WlanErrorContext::new(WlanCall::Connect)
+                    .with_interface(interface_guid)
+                    .with_profile(profile_name)
+                    .error(code)
+            })
+    }
+
+    /// Disconnects an interface from its network
+    pub(crate) fn disconnect(&self, interface_guid: &GUID) -> Result<(), WinWifiError> {
+        self.backend
+            .disconnect(self.handle, interface_guid)
+            .map_err(|code| {
+                WlanErrorContext::new(WlanCall::Disconnect)
+                    .with_interface(interface_guid)
+                    .error(code)
+            })
+    }
+
+    /// Saves a profile for an interface. A rejected profile returns
+    /// [`WinWifiError::BadProfile`] with the reason code of the rejection
+    pub(crate) fn set_profile(
+        &self,
+        interface_guid: &GUID,
+        profile: &WlanProfile,
+        overwrite: bool,
+    ) -> Result<(), WinWifiError> {
+        let profile_xml = profile.to_xml()?;
+
+        self.backend
+            .set_profile(self.handle, interface_guid, &profile_xml, overwrite)
+            .map_err(|(code, reason_code)| {
+                WlanErrorContext::new(WlanCall::SetProfile)
+                    .with_interface(interface_guid)
+                    .with_profile(&profile.name)
+                    .error_with_reason(code, reason_code)
+            })
+    }
 }
 
 impl Drop for WlanHandle {
     fn drop(&mut self) {
-        #[cfg(not(test))]
-        unsafe {
-            windows::Win32::NetworkManagement::WiFi::WlanCloseHandle(self.0, None)
-        };
+        let _ = self.backend.close_handle(self.handle);
     }
 }
@@ -0,0 +1,51 @@
+use std::fmt::Write;
+
+/// Formats bytes as lowercase hex digits
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    encode_separated(bytes, "")
+}
+
+/// Formats bytes as lowercase hex digits with a separator between the bytes
+pub(crate) fn encode_separated(bytes: &[u8], separator: &str) -> String {
+    let mut hex = String::with_capacity(bytes.len() * (2 + separator.len()));
+
+    for (index, byte) in bytes.iter().enumerate() {
+        if index > 0 {
+            hex.push_str(separator);
+        }
+        let _ = write!(hex, "{byte:02x}");
+    }
+
+    hex
+}
+
+/// Parses hex digits of either case into bytes. Returns `None` if the string has an odd length
+/// or contains anything other than hex digits
+pub(crate) fn decode(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that bytes are encoded and decoded again
+    #[test]
+    fn round_trip() {
+        assert_eq!(encode(&[0x0a, 0x1b, 0xff]), "0a1bff");
+        assert_eq!(encode_separated(&[0x0a, 0x1b, 0xff], " "), "0a 1b ff");
+        assert_eq!(decode("0a1BfF"), Some(vec![0x0a, 0x1b, 0xff]));
+        assert_eq!(decode(""), Some(Vec::new()));
+
+        assert_eq!(decode("abc"), None);
+        assert_eq!(decode("+f"), None);
+        assert_eq!(decode("zz"), None);
+    }
+}
@@ -1,46 +1,54 @@
-use std::{ffi::OsString, marker::PhantomData, os::windows::ffi::OsStringExt, ptr::NonNull};
-
-use windows::Win32::{
-    Foundation::WIN32_ERROR,
-    NetworkManagement::WiFi::{
-        wlan_interface_state_ad_hoc_network_formed, wlan_interface_state_associating,
-        wlan_interface_state_authenticating, wlan_interface_state_connected,
-        wlan_interface_state_disconnected, wlan_interface_state_disconnecting,
-        wlan_interface_state_discovering, wlan_interface_state_not_ready, WlanEnumInterfaces,
-        WlanFreeMemory, WLAN_INTERFACE_INFO, WLAN_INTERFACE_INFO_LIST, WLAN_INTERFACE_STATE,
-    },
+use std::{ffi::OsString, marker::PhantomData, ptr::NonNull};
+
+use serde::{Deserialize, Serialize};
+
+use windows::Win32::NetworkManagement::WiFi::{
+    wlan_interface_state_ad_hoc_network_formed, wlan_interface_state_associating,
+    wlan_interface_state_authenticating, wlan_interface_state_connected,
+    wlan_interface_state_disconnected, wlan_interface_state_disconnecting,
+    wlan_interface_state_discovering, wlan_interface_state_not_ready, WLAN_INTERFACE_INFO,
+    WLAN_INTERFACE_INFO_LIST, WLAN_INTERFACE_STATE,
 };
 
 use crate::{
-    errors::WinWifiError, guid::GuidRef, handle::WlanHandle, profiles::WlanInterfaceProfiles,
+    backend::{os_string_from_wide, WlanCall},
+    bss::WlanBssEntry,
+    errors::{WinWifiError, WlanErrorContext},
+    guid::GuidRef,
+    handle::WlanHandle,
+    profiles::{info::WlanProfile, WlanInterfaceProfiles},
+    snapshot::InterfaceInfo,
 };
 
 /// A wireless interface state
-#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WlanInterfaceState {
     /// Wireless interface is not ready
-    NotReady = wlan_interface_state_not_ready.0,
+    NotReady,
 
     /// Wireless interface is connected
-    Connected = wlan_interface_state_connected.0,
+    Connected,
 
     /// An AD HOC network is formed on the wireless interface
-    AdHocNetworkFormed = wlan_interface_state_ad_hoc_network_formed.0,
+    AdHocNetworkFormed,
 
     /// Wireless interface is currently disconnecting
-    Disconnecting = wlan_interface_state_disconnecting.0,
+    Disconnecting,
 
     /// Wireless interface is disconnected
-    Disconnected = wlan_interface_state_disconnected.0,
+    Disconnected,
 
     /// Wireless interface is currently associating
-    Associating = wlan_interface_state_associating.0,
+    Associating,
 
     /// Wireless interface is currently discovering
-    Discovering = wlan_interface_state_discovering.0,
+    Discovering,
 
     /// Wireless interface is currently authenticating
-    Authenticating = wlan_interface_state_authenticating.0,
+    Authenticating,
+
+    /// State value which is not known to this crate
+    Unknown(i32),
 }
 
 impl From<WLAN_INTERFACE_STATE> for WlanInterfaceState {
@@ -55,7 +63,23 @@ impl From<WLAN_INTERFACE_STATE> for WlanInterfaceState {
             wlan_interface_state_associating => Self::Associating,
             wlan_interface_state_discovering => Self::Discovering,
             wlan_interface_state_authenticating => Self::Authenticating,
-            _ => unreachable!(),
+            WLAN_INTERFACE_STATE(value) => Self::Unknown(value),
+        }
+    }
+}
+
+impl From<WlanInterfaceState> for WLAN_INTERFACE_STATE {
+    fn from(value: WlanInterfaceState) -> Self {
+        match value {
+            WlanInterfaceState::NotReady => wlan_interface_state_not_ready,
+            WlanInterfaceState::Connected => wlan_interface_state_connected,
+            WlanInterfaceState::AdHocNetworkFormed => wlan_interface_state_ad_hoc_network_formed,
+            WlanInterfaceState::Disconnecting => wlan_interface_state_disconnecting,
+            WlanInterfaceState::Disconnected => wlan_interface_state_disconnected,
+            WlanInterfaceState::Associating => wlan_interface_state_associating,
+            WlanInterfaceState::Discovering => wlan_interface_state_discovering,
+            WlanInterfaceState::Authenticating => wlan_interface_state_authenticating,
+            WlanInterfaceState::Unknown(value) => WLAN_INTERFACE_STATE(value),
         }
     }
 }
@@ -74,6 +98,7 @@ pub struct WlanInterfaces {
 
 impl WlanInterfaces {
     /// Gets the list of wireless interfaces on the system
+    #[cfg(windows)]
     pub fn new() -> Result<WlanInterfaces, WinWifiError> {
         let handle = WlanHandle::new()?;
         Self::with_handle(handle)
@@ -81,9 +106,10 @@ impl WlanInterfaces {
 
     /// Gets the list of wireless interfaces on the system but using an already opened WlanHandle
     pub fn with_handle(handle: WlanHandle) -> Result<WlanInterfaces, WinWifiError> {
-        let mut interface_list_ptr = std::ptr::null_mut();
-        WIN32_ERROR(unsafe { WlanEnumInterfaces(*handle.as_ptr(), None, &mut interface_list_ptr) })
-            .ok()?;
+        let interface_list_ptr = handle
+            .backend()
+            .enum_interfaces(handle.raw())
+            .map_err(|code| WlanErrorContext::new(WlanCall::EnumInterfaces).error(code))?;
 
         Ok(WlanInterfaces {
             handle,
@@ -134,7 +160,11 @@ impl WlanInterfaces {
 
 impl Drop for WlanInterfaces {
     fn drop(&mut self) {
-        unsafe { WlanFreeMemory(self.interface_list_ptr.as_ptr().cast()) };
+        unsafe {
+            self.handle
+                .backend()
+                .free_memory(self.interface_list_ptr.as_ptr().cast())
+        };
     }
 }
 
@@ -181,7 +211,7 @@ impl<'interfaces, 'handle: 'interfaces> Iterator for WlanInterfacesIterator<'int
     }
 }
 
-impl<'interfaces, 'handle: 'interfaces> ExactSizeIterator for WlanInterfacesIterator<'_, '_> {
+impl ExactSizeIterator for WlanInterfacesIterator<'_, '_> {
     fn len(&self) -> usize {
         self.item_count
     }
@@ -215,29 +245,62 @@ impl<'interfaces, 'handle: 'interfaces> WlanInterface<'interfaces, 'handle> {
             .iter()
             .position(|v| v == &0)?;
 
-        Some(OsString::from_wide(
+        Some(os_string_from_wide(
             &interface.strInterfaceDescription[..null_index],
         ))
     }
 
     /// Returns the state of the interface
-    // TODO: Write test
     pub fn if_state(&self) -> WlanInterfaceState {
         let interface = unsafe { self.interface_ptr.as_ref() };
         interface.isState.into()
     }
 
+    /// Copies the interface into an owned snapshot
+    pub fn to_owned(&self) -> InterfaceInfo {
+        InterfaceInfo::from(unsafe { self.interface_ptr.as_ref() })
+    }
+
     /// Get the saved profiles associated with this interface
     pub fn profiles(
         &'interfaces self,
     ) -> Result<WlanInterfaceProfiles<'interfaces, 'handle>, WinWifiError> {
         WlanInterfaceProfiles::new(self)
     }
+
+    /// Requests a scan for wireless networks. The scan completes asynchronously, the results are
+    /// returned by [`WlanInterface::bss_entries`] afterwards
+    pub fn scan(&self) -> Result<(), WinWifiError> {
+        self.handle.scan(self.guid().as_guid())
+    }
+
+    /// Returns the access points found by the scans of the interface
+    pub fn bss_entries(&self) -> Result<Vec<WlanBssEntry>, WinWifiError> {
+        self.handle.bss_entries(self.guid().as_guid())
+    }
+
+    /// Connects the interface to the network of a saved profile. The connection completes
+    /// asynchronously
+    pub fn connect(&self, profile_name: &str) -> Result<(), WinWifiError> {
+        self.handle.connect(self.guid().as_guid(), profile_name)
+    }
+
+    /// Disconnects the interface from its network
+    pub fn disconnect(&self) -> Result<(), WinWifiError> {
+        self.handle.disconnect(self.guid().as_guid())
+    }
+
+    /// Saves a profile for the interface. An existing profile with the same name is only
+    /// replaced if `overwrite` is set
+    pub fn set_profile(&self, profile: &WlanProfile, overwrite: bool) -> Result<(), WinWifiError> {
+        self.handle
+            .set_profile(self.guid().as_guid(), profile, overwrite)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{mem::ManuallyDrop, os::windows::ffi::OsStrExt};
+    use std::mem::ManuallyDrop;
 
     use super::*;
     use windows::core::GUID;
@@ -388,22 +451,43 @@ mod tests {
         );
     }
 
+    /// Checks that the state of the interface is converted
+    #[test]
+    fn correct_interface_state() {
+        let mut raw_interface_list = WLAN_INTERFACE_INFO_LIST {
+            dwNumberOfItems: 1,
+            dwIndex: 0,
+            InterfaceInfo: [WLAN_INTERFACE_INFO {
+                isState: wlan_interface_state_authenticating,
+                ..Default::default()
+            }],
+        };
+
+        let handle = create_test_handle!();
+        let interface_list = create_test_interfaces!(handle, &mut raw_interface_list);
+
+        let first_interface = interface_list
+            .iter()
+            .next()
+            .expect("Failed to get first interface");
+
+        assert_eq!(
+            first_interface.if_state(),
+            WlanInterfaceState::Authenticating
+        );
+    }
+
     /// Checks for a correct interface description
     #[test]
     fn correct_interface_description() {
         const TEST_DESCRIPTION: &str = "testing testing";
 
         let test_description_os_string = OsString::from(TEST_DESCRIPTION);
-        let test_description_bytes = test_description_os_string
-            .encode_wide()
-            .collect::<Vec<u16>>();
+        let test_description_bytes = TEST_DESCRIPTION.encode_utf16().collect::<Vec<u16>>();
 
         let mut interface_description = [0u16; 256];
-        interface_description[..test_description_bytes.len()].copy_from_slice(
-            &test_description_os_string
-                .encode_wide()
-                .collect::<Vec<u16>>(),
-        );
+        interface_description[..test_description_bytes.len()]
+            .copy_from_slice(&test_description_bytes);
 
         let mut raw_interface_list = WLAN_INTERFACE_INFO_LIST {
             dwNumberOfItems: 1,
@@ -434,4 +518,17 @@ mod tests {
             found_description.to_string_lossy()
         );
     }
+
+    /// Checks that state values unknown to the crate are kept instead of panicking
+    #[test]
+    fn unknown_interface_state() {
+        let state = WlanInterfaceState::from(WLAN_INTERFACE_STATE(42));
+        assert_eq!(state, WlanInterfaceState::Unknown(42));
+        assert_eq!(WLAN_INTERFACE_STATE::from(state), WLAN_INTERFACE_STATE(42));
+
+        assert_eq!(
+            WLAN_INTERFACE_STATE::from(WlanInterfaceState::Connected),
+            wlan_interface_state_connected
+        );
+    }
 }
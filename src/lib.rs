@@ -1,10 +1,24 @@
+pub mod backend;
+mod bss;
 pub mod errors;
 mod guid;
 mod handle;
+mod hex;
 mod interfaces;
-mod profiles;
+pub mod profiles;
+mod reason;
+mod session;
+mod snapshot;
+mod ssid;
 
 #[cfg(test)]
 mod testing;
 
+pub use bss::WlanBssEntry;
+pub use guid::{GuidRef, InterfaceGuid};
+pub use handle::{WlanApiVersion, WlanHandle};
 pub use interfaces::*;
+pub use reason::{ReasonCategory, ReasonCode};
+pub use session::{SessionInterface, SessionProfile, WlanSession};
+pub use snapshot::{InterfaceInfo, ProfileInfo};
+pub use ssid::Ssid;
@@ -0,0 +1,446 @@
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::{
+    eap::{EapConfig, PeapConfig},
+    info::{
+        AuthEncryption, Authentication, ConnectionMode, ConnectionType, EnabledState, Encryption,
+        KeyType, MacRandomization, Msm, OneX, OneXAuthMode, Security, SharedKey, SsidConfig,
+        SsidElement, WlanProfile,
+    },
+    psk::{check_passphrase, derive_psk, psk_to_hex},
+    unknown::UnknownXml,
+};
+
+/// Security settings selected in a [`WlanProfileBuilder`]
+#[derive(Debug, Clone)]
+enum BuilderSecurity {
+    /// Open network without encryption
+    Open,
+
+    /// WPA2-Personal with a passphrase
+    Wpa2Psk(String),
+
+    /// WPA3-Personal with a passphrase
+    Wpa3Sae(String),
+
+    /// WPA3-Personal in transition mode with a passphrase
+    Wpa2Wpa3Transition(String),
+
+    /// WPA2-Enterprise using 802.1X authentication
+    Wpa2Enterprise,
+}
+
+/// Builder for creating wireless profiles for common security types
+///
+/// ```
+/// use winwifi::profiles::WlanProfileBuilder;
+///
+/// let profile = WlanProfileBuilder::new("Office")
+///     .ssid("Office WiFi")
+///     .wpa2_psk("passphrase")
+///     .auto_connect(true)
+///     .hidden(true)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(profile.name, "Office");
+/// ```
+#[derive(Debug, Clone)]
+pub struct WlanProfileBuilder {
+    /// Name of the profile
+    name: String,
+
+    /// SSID of the network. Defaults to the name of the profile
+    ssid: Option<Ssid>,
+
+    /// Type of the network
+    connection_type: ConnectionType,
+
+    /// Security settings of the network
+    security: BuilderSecurity,
+
+    /// Whether to connect to the network automatically
+    auto_connect: bool,
+
+    /// Whether the network does not broadcast its SSID
+    hidden: bool,
+
+    /// Whether to switch to a more preferred network when it is in range
+    auto_switch: Option<bool>,
+
+    /// Whether MAC address randomization is enabled
+    mac_randomization: Option<bool>,
+
+    /// Whether the PSK derived from the passphrase is stored instead of the passphrase
+    derive_psk: bool,
+
+    /// Type of credentials used for 802.1X authentication
+    one_x_auth_mode: OneXAuthMode,
+
+    /// EAP configuration used for 802.1X authentication
+    eap_config: Option<EapConfig>,
+}
+
+impl WlanProfileBuilder {
+    /// Creates a new builder for an open network profile with the specified name
+    pub fn new(name: impl Into<String>) -> WlanProfileBuilder {
+        WlanProfileBuilder {
+            name: name.into(),
+            ssid: None,
+            connection_type: ConnectionType::Ess,
+            security: BuilderSecurity::Open,
+            auto_connect: true,
+            hidden: false,
+            auto_switch: None,
+            mac_randomization: None,
+            derive_psk: false,
+            one_x_auth_mode: OneXAuthMode::User,
+            eap_config: None,
+        }
+    }
+
+    /// Sets the SSID of the network. The name of the profile is used if this is not set
+    pub fn ssid(mut self, ssid: impl Into<Ssid>) -> Self {
+        self.ssid = Some(ssid.into());
+        self
+    }
+
+    /// Sets the type of the network
+    pub fn connection_type(mut self, connection_type: ConnectionType) -> Self {
+        self.connection_type = connection_type;
+        self
+    }
+
+    /// Uses an open network without encryption
+    pub fn open(mut self) -> Self {
+        self.security = BuilderSecurity::Open;
+        self
+    }
+
+    /// Uses WPA2-Personal with the specified passphrase
+    pub fn wpa2_psk(mut self, passphrase: impl Into<String>) -> Self {
+        self.security = BuilderSecurity::Wpa2Psk(passphrase.into());
+        self
+    }
+
+    /// Uses WPA3-Personal with the specified passphrase
+    pub fn wpa3_sae(mut self, passphrase: impl Into<String>) -> Self {
+        self.security = BuilderSecurity::Wpa3Sae(passphrase.into());
+        self
+    }
+
+    /// Uses WPA3-Personal in transition mode with the specified passphrase. Allows connecting
+    /// to access points which only support WPA2-Personal
+    pub fn wpa2_wpa3_transition(mut self, passphrase: impl Into<String>) -> Self {
+        self.security = BuilderSecurity::Wpa2Wpa3Transition(passphrase.into());
+        self
+    }
+
+    /// Uses WPA2-Enterprise. PEAP with MSCHAPv2 is used unless another EAP configuration is
+    /// set with [`WlanProfileBuilder::eap_config`]
+    pub fn wpa2_enterprise(mut self) -> Self {
+        self.security = BuilderSecurity::Wpa2Enterprise;
+        self
+    }
+
+    /// Sets the type of credentials used for 802.1X authentication
+    pub fn one_x_auth_mode(mut self, auth_mode: OneXAuthMode) -> Self {
+        self.one_x_auth_mode = auth_mode;
+        self
+    }
+
+    /// Sets the EAP configuration used for 802.1X authentication
+    pub fn eap_config(mut self, eap_config: EapConfig) -> Self {
+        self.eap_config = Some(eap_config);
+        self
+    }
+
+    /// Sets whether to connect to the network automatically
+    pub fn auto_connect(mut self, auto_connect: bool) -> Self {
+        self.auto_connect = auto_connect;
+        self
+    }
+
+    /// Sets whether the network does not broadcast its SSID
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Sets whether to switch to a more preferred network when it is in range
+    pub fn auto_switch(mut self, auto_switch: bool) -> Self {
+        self.auto_switch = Some(auto_switch);
+        self
+    }
+
+    /// Sets whether MAC address randomization is enabled for the network
+    pub fn mac_randomization(mut self, enabled: bool) -> Self {
+        self.mac_randomization = Some(enabled);
+        self
+    }
+
+    /// Sets whether the 256-bit PSK derived from the passphrase and SSID is stored as a network
+    /// key with 64 hex digits instead of the passphrase. Only WPA2-Personal supports this
+    pub fn derive_psk(mut self, derive_psk: bool) -> Self {
+        self.derive_psk = derive_psk;
+        self
+    }
+
+    /// Creates the wireless profile. Returns an error if the settings can not be used together
+    pub fn build(self) -> Result<WlanProfile, WinWifiError> {
+        if self.name.is_empty() {
+            return Err(invalid("the profile name is empty"));
+        }
+
+        let ssid = self.ssid.unwrap_or_else(|| Ssid::from(self.name.as_str()));
+        if ssid.is_empty() || ssid.len() > Ssid::MAX_LENGTH {
+            return Err(invalid("the SSID must be between 1 and 32 bytes long"));
+        }
+
+        if self.connection_type == ConnectionType::Ibss
+            && !matches!(
+                self.security,
+                BuilderSecurity::Open | BuilderSecurity::Wpa2Psk(_)
+            )
+        {
+            return Err(invalid(
+                "ad hoc networks only support open or WPA2-Personal security",
+            ));
+        }
+
+        if self.eap_config.is_some() && !matches!(self.security, BuilderSecurity::Wpa2Enterprise) {
+            return Err(invalid("an EAP configuration requires WPA2-Enterprise"));
+        }
+
+        if self.derive_psk && !matches!(self.security, BuilderSecurity::Wpa2Psk(_)) {
+            return Err(invalid("only WPA2-Personal can store a derived PSK"));
+        }
+
+        let security = match self.security {
+            BuilderSecurity::Open => base_security(Authentication::Open, Encryption::None),
+            BuilderSecurity::Wpa2Psk(passphrase) if self.derive_psk => {
+                let mut security = passphrase_security(Authentication::Wpa2Psk, passphrase)?;
+                if let Some(shared_key) = &mut security.shared_key {
+                    shared_key.key_type = KeyType::NetworkKey;
+                    shared_key.key_material =
+                        psk_to_hex(&derive_psk(&shared_key.key_material, &ssid)?);
+                }
+                security
+            }
+            BuilderSecurity::Wpa2Psk(passphrase) => {
+                passphrase_security(Authentication::Wpa2Psk, passphrase)?
+            }
+            BuilderSecurity::Wpa3Sae(passphrase) => {
+                passphrase_security(Authentication::Wpa3Sae, passphrase)?
+            }
+            BuilderSecurity::Wpa2Wpa3Transition(passphrase) => {
+                let mut security = passphrase_security(Authentication::Wpa3Sae, passphrase)?;
+                security.auth_encryption.transition_mode = Some(true);
+                security
+            }
+            BuilderSecurity::Wpa2Enterprise => {
+                let eap_config = self
+                    .eap_config
+                    .unwrap_or_else(|| EapConfig::Peap(PeapConfig::default()));
+
+                let mut security = base_security(Authentication::Wpa2, Encryption::Aes);
+                security.auth_encryption.use_one_x = Some(true);
+                security.pmk_cache_mode = Some(EnabledState::Enabled);
+                security.pmk_cache_ttl = Some(720);
+                security.pmk_cache_size = Some(128);
+                security.pre_auth_mode = Some(EnabledState::Disabled);
+                security.one_x = Some(OneX {
+                    cache_user_data: None,
+                    held_period: None,
+                    auth_period: None,
+                    start_period: None,
+                    max_start: None,
+                    max_auth_failures: None,
+                    auth_mode: Some(self.one_x_auth_mode),
+                    eap_config: Some(eap_config),
+                });
+                security
+            }
+        };
+
+        Ok(WlanProfile {
+            name: self.name,
+            ssid_config: SsidConfig {
+                ssid: SsidElement::from(ssid),
+                non_broadcast: self.hidden.then_some(true),
+            },
+            connection_type: self.connection_type,
+            connection_mode: Some(if self.auto_connect {
+                ConnectionMode::Auto
+            } else {
+                ConnectionMode::Manual
+            }),
+            auto_switch: self.auto_switch,
+            msm: Msm { security },
+            mac_randomization: self.mac_randomization.map(|enabled| MacRandomization {
+                enable_randomization: enabled,
+                randomization_seed: None,
+            }),
+            unknown: UnknownXml::default(),
+        })
+    }
+}
+
+/// Creates security settings without a shared key or 802.1X settings
+fn base_security(authentication: Authentication, encryption: Encryption) -> Security {
+    Security {
+        auth_encryption: AuthEncryption {
+            authentication,
+            encryption,
+            use_one_x: Some(false),
+            fips_mode: None,
+            transition_mode: None,
+        },
+        shared_key: None,
+        key_index: None,
+        pmk_cache_mode: None,
+        pmk_cache_ttl: None,
+        pmk_cache_size: None,
+        pre_auth_mode: None,
+        pre_auth_throttle: None,
+        one_x: None,
+    }
+}
+
+/// Creates AES security settings using an unprotected passphrase
+fn passphrase_security(
+    authentication: Authentication,
+    passphrase: String,
+) -> Result<Security, WinWifiError> {
+    check_passphrase(&passphrase)?;
+
+    let mut security = base_security(authentication, Encryption::Aes);
+    security.shared_key = Some(SharedKey {
+        key_type: KeyType::PassPhrase,
+        protected: false,
+        key_material: passphrase,
+    });
+
+    Ok(security)
+}
+
+/// Creates an error for invalid profile builder settings
+fn invalid(reason: &str) -> WinWifiError {
+    WinWifiError::InvalidProfile(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_profile;
+
+    /// Checks that a built WPA2-Personal profile matches the example profile
+    #[test]
+    fn wpa2_psk_profile() {
+        let mut expected = example_profile(3);
+        expected.mac_randomization = None;
+
+        let profile = WlanProfileBuilder::new("REDACTED WPA2PSK NONBROADCAST")
+            .ssid("REDACTED WPA2PSK NONBROADCAST")
+            .wpa2_psk("password")
+            .auto_connect(true)
+            .hidden(true)
+            .build()
+            .expect("Failed to build profile");
+
+        // The name in the example profile does not match its hex SSID
+        expected.ssid_config.ssid.name = profile.ssid_config.ssid.name.clone();
+
+        assert_eq!(profile, expected);
+    }
+
+    /// Checks that a built open profile matches the example profile
+    #[test]
+    fn open_profile() {
+        let profile = WlanProfileBuilder::new("REDACTED OPEN")
+            .open()
+            .mac_randomization(false)
+            .build()
+            .expect("Failed to build profile");
+
+        let mut expected = example_profile(1);
+        expected
+            .mac_randomization
+            .as_mut()
+            .expect("Missing MacRandomization element")
+            .randomization_seed = None;
+
+        assert_eq!(profile, expected);
+    }
+
+    /// Checks that a built WPA2-Enterprise profile has the same shape as the example profile
+    #[test]
+    fn wpa2_enterprise_profile() {
+        let profile = WlanProfileBuilder::new("REDACTED EAP")
+            .wpa2_enterprise()
+            .build()
+            .expect("Failed to build profile");
+
+        let expected = example_profile(0);
+        assert_eq!(profile.ssid_config, expected.ssid_config);
+
+        let security = &profile.msm.security;
+        let expected_security = &expected.msm.security;
+        assert_eq!(security.auth_encryption, expected_security.auth_encryption);
+        assert_eq!(security.pmk_cache_mode, expected_security.pmk_cache_mode);
+        assert_eq!(security.pmk_cache_ttl, expected_security.pmk_cache_ttl);
+        assert_eq!(security.pmk_cache_size, expected_security.pmk_cache_size);
+        assert_eq!(security.pre_auth_mode, expected_security.pre_auth_mode);
+
+        let eap_config = security
+            .one_x
+            .as_ref()
+            .and_then(|one_x| one_x.eap_config.as_ref())
+            .expect("Missing EAPConfig element");
+
+        assert_eq!(eap_config, &EapConfig::Peap(PeapConfig::default()));
+    }
+
+    /// Checks that transition mode profiles are serialized with the v4 namespace
+    #[test]
+    fn transition_mode_profile() {
+        let profile = WlanProfileBuilder::new("Transition")
+            .wpa2_wpa3_transition("passphrase")
+            .build()
+            .expect("Failed to build profile");
+
+        assert_eq!(
+            profile.msm.security.auth_encryption.authentication,
+            Authentication::Wpa3Sae
+        );
+
+        let serialized = profile.to_xml().expect("Failed to serialize profile");
+        assert!(serialized.contains(
+            "<transitionMode xmlns=\"http://www.microsoft.com/networking/WLAN/profile/v4\">true</transitionMode>"
+        ));
+    }
+
+    /// Makes sure that invalid settings are rejected
+    #[test]
+    fn invalid_settings() {
+        assert!(WlanProfileBuilder::new("").build().is_err());
+        assert!(WlanProfileBuilder::new("Short")
+            .wpa2_psk("short")
+            .build()
+            .is_err());
+        assert!(WlanProfileBuilder::new("Long")
+            .ssid("a".repeat(33))
+            .build()
+            .is_err());
+        assert!(WlanProfileBuilder::new("Ad hoc")
+            .connection_type(ConnectionType::Ibss)
+            .wpa3_sae("passphrase")
+            .build()
+            .is_err());
+        assert!(WlanProfileBuilder::new("EAP")
+            .wpa2_psk("passphrase")
+            .eap_config(EapConfig::Peap(PeapConfig::default()))
+            .build()
+            .is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use sha2::{Digest, Sha256};
+
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::{
+    info::{OneX, Security, WlanProfile},
+    validate::{Diagnostic, Severity},
+};
+
+/// Collects the settings which could not be converted to or from another format
+#[derive(Default)]
+pub(super) struct Unconverted {
+    pub(super) diagnostics: Vec<Diagnostic>,
+}
+
+impl Unconverted {
+    pub(super) fn push(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    /// Records a profile setting which has no equivalent in the other format
+    pub(super) fn profile(&mut self, path: &str, message: impl Into<String>) {
+        self.push(format!("WLANProfile/{path}"), message);
+    }
+
+    /// Records the PMK caching and pre-authentication settings of a profile
+    pub(super) fn pmk_cache(&mut self, security: &Security, message: &str) {
+        for (path, present) in [
+            (
+                "MSM/security/PMKCacheMode",
+                security.pmk_cache_mode.is_some(),
+            ),
+            ("MSM/security/PMKCacheTTL", security.pmk_cache_ttl.is_some()),
+            (
+                "MSM/security/PMKCacheSize",
+                security.pmk_cache_size.is_some(),
+            ),
+            ("MSM/security/preAuthMode", security.pre_auth_mode.is_some()),
+            (
+                "MSM/security/preAuthThrottle",
+                security.pre_auth_throttle.is_some(),
+            ),
+        ] {
+            if present {
+                self.profile(path, message);
+            }
+        }
+    }
+
+    /// Records the 802.1X timing and caching settings of a profile
+    pub(super) fn one_x(&mut self, one_x: &OneX, message: &str) {
+        for (path, present) in [
+            ("cacheUserData", one_x.cache_user_data.is_some()),
+            ("heldPeriod", one_x.held_period.is_some()),
+            ("authPeriod", one_x.auth_period.is_some()),
+            ("startPeriod", one_x.start_period.is_some()),
+            ("maxStart", one_x.max_start.is_some()),
+            ("maxAuthFailures", one_x.max_auth_failures.is_some()),
+            ("authMode", one_x.auth_mode.is_some()),
+        ] {
+            if present {
+                self.profile(&format!("MSM/security/OneX/{path}"), message);
+            }
+        }
+    }
+}
+
+/// Returns the SSID of a profile which is being exported. If the SSID name does not match the
+/// hex SSID, the hex SSID is used and the name is reported
+pub(super) fn export_ssid(
+    profile: &WlanProfile,
+    unconverted: &mut Unconverted,
+) -> Result<Ssid, WinWifiError> {
+    match (
+        profile.ssid_config.ssid.ssid(),
+        &profile.ssid_config.ssid.hex,
+    ) {
+        (Ok(ssid), _) => Ok(ssid),
+        (Err(_), Some(hex)) => {
+            unconverted.profile(
+                "SSIDConfig/SSID/name",
+                "the SSID name does not match the hex SSID. The hex SSID is used",
+            );
+            Ok(hex.clone())
+        }
+        (Err(e), None) => Err(e),
+    }
+}
+
+/// Creates a UUID from the hash of the specified parts, so that converting the same profile
+/// again results in the same UUID
+pub(super) fn stable_uuid(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for (index, part) in parts.iter().enumerate() {
+        if index > 0 {
+            hasher.update([0]);
+        }
+        hasher.update(part);
+    }
+
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&hasher.finalize()[..16]);
+
+    // Version 8 (custom) UUID with the RFC 4122 variant
+    bytes[6] = (bytes[6] & 0x0f) | 0x80;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = crate::hex::encode(&bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
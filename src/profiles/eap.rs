@@ -0,0 +1,1082 @@
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::errors::WinWifiError;
+
+use super::xml::XmlElement;
+
+/// Namespace of the EapHost configuration schema
+pub const EAP_HOST_CONFIG_NAMESPACE: &str = "http://www.microsoft.com/provisioning/EapHostConfig";
+
+/// Namespace of the common EAP method schema
+pub const EAP_COMMON_NAMESPACE: &str = "http://www.microsoft.com/provisioning/EapCommon";
+
+/// Namespace of the base EAP connection properties schema
+pub const BASE_EAP_NAMESPACE: &str =
+    "http://www.microsoft.com/provisioning/BaseEapConnectionPropertiesV1";
+
+/// Namespace of the PEAP connection properties schema
+pub const PEAP_V1_NAMESPACE: &str =
+    "http://www.microsoft.com/provisioning/MsPeapConnectionPropertiesV1";
+
+/// Namespace of the version 2 PEAP connection properties extensions
+pub const PEAP_V2_NAMESPACE: &str =
+    "http://www.microsoft.com/provisioning/MsPeapConnectionPropertiesV2";
+
+/// Namespace of the version 3 PEAP connection properties extensions
+pub const PEAP_V3_NAMESPACE: &str =
+    "http://www.microsoft.com/provisioning/MsPeapConnectionPropertiesV3";
+
+/// Namespace of the MSCHAPv2 connection properties schema
+pub const MSCHAPV2_NAMESPACE: &str =
+    "http://www.microsoft.com/provisioning/MsChapV2ConnectionPropertiesV1";
+
+/// Namespace of the EAP-TLS connection properties schema
+pub const EAP_TLS_V1_NAMESPACE: &str =
+    "http://www.microsoft.com/provisioning/EapTlsConnectionPropertiesV1";
+
+/// Namespace of the version 2 EAP-TLS connection properties extensions
+pub const EAP_TLS_V2_NAMESPACE: &str =
+    "http://www.microsoft.com/provisioning/EapTlsConnectionPropertiesV2";
+
+/// Namespace of the version 3 EAP-TLS connection properties extensions
+pub const EAP_TLS_V3_NAMESPACE: &str =
+    "http://www.microsoft.com/provisioning/EapTlsConnectionPropertiesV3";
+
+/// Namespace of the EAP-TTLS connection properties schema
+pub const EAP_TTLS_NAMESPACE: &str =
+    "http://www.microsoft.com/provisioning/EapTtlsConnectionPropertiesV1";
+
+/// EAP type number of EAP-TLS
+pub const EAP_TYPE_TLS: u8 = 13;
+
+/// EAP type number of EAP-TTLS
+pub const EAP_TYPE_TTLS: u8 = 21;
+
+/// EAP type number of PEAP
+pub const EAP_TYPE_PEAP: u8 = 25;
+
+/// EAP type number of EAP-MSCHAPv2
+pub const EAP_TYPE_MSCHAPV2: u8 = 26;
+
+/// Author ID of the Microsoft EAP-TTLS implementation
+const EAP_TTLS_AUTHOR_ID: u32 = 311;
+
+/// Child elements of the PEAP `EapType` element which are modeled by [`PeapConfig`]
+const PEAP_ELEMENTS: &[&str] = &[
+    "ServerValidation",
+    "FastReconnect",
+    "InnerEapOptional",
+    "Eap",
+    "EnableQuarantineChecks",
+    "RequireCryptoBinding",
+    "PeapExtensions",
+];
+
+/// Child elements of the EAP-TLS `EapType` element which are modeled by [`TlsConfig`]
+const TLS_ELEMENTS: &[&str] = &[
+    "CredentialsSource",
+    "ServerValidation",
+    "DifferentUsername",
+    "PerformServerValidation",
+    "AcceptServerName",
+    "TLSExtensions",
+];
+
+/// EAP method configuration of an 802.1X profile. Refer to https://learn.microsoft.com/en-us/windows/win32/eaphost/eaphostconfigschema-elements for more details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EapConfig {
+    /// Protected EAP
+    Peap(PeapConfig),
+
+    /// EAP-TLS using a client certificate
+    Tls(TlsConfig),
+
+    /// EAP-TTLS
+    Ttls(TtlsConfig),
+
+    /// An EAP method which is not modeled
+    Other(OtherEapConfig),
+}
+
+/// EAP method identifiers from the `EapMethod` element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EapMethod {
+    /// EAP type number
+    pub eap_type: u8,
+
+    /// Vendor ID of the method
+    pub vendor_id: u32,
+
+    /// Vendor type of the method
+    pub vendor_type: u32,
+
+    /// Author ID of the method implementation
+    pub author_id: u32,
+}
+
+/// Configuration of an EAP method which is not modeled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtherEapConfig {
+    /// Identifiers of the EAP method
+    pub method: EapMethod,
+
+    /// The method specific `Config` element
+    pub config: XmlElement,
+}
+
+/// Server certificate validation settings
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerValidation {
+    /// Whether the user is not prompted when the server can not be validated
+    pub disable_user_prompt: bool,
+
+    /// Semicolon separated list of server names the certificate must match
+    pub server_names: String,
+
+    /// Thumbprints of the trusted root certificate authorities as hex strings
+    pub trusted_root_ca: Vec<String>,
+
+    /// Whether the server certificate is validated
+    pub perform_server_validation: Option<bool>,
+
+    /// Whether the server name is validated against the certificate
+    pub accept_server_name: Option<bool>,
+}
+
+/// PEAP settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeapConfig {
+    /// Server certificate validation settings
+    pub server_validation: ServerValidation,
+
+    /// Whether fast reconnect is enabled
+    pub fast_reconnect: bool,
+
+    /// Whether the inner EAP method is optional
+    pub inner_eap_optional: Option<bool>,
+
+    /// Inner authentication method
+    pub inner_method: PeapInnerMethod,
+
+    /// Whether network access protection checks are enabled
+    pub enable_quarantine_checks: bool,
+
+    /// Whether cryptobinding is required
+    pub require_crypto_binding: bool,
+
+    /// Whether the user is prompted when the server CA certificate is not found
+    pub allow_prompting_when_server_ca_not_found: Option<bool>,
+
+    /// Child elements of the `EapType` element which are not modeled. Written after the
+    /// modeled elements
+    pub other: Vec<XmlElement>,
+}
+
+impl Default for PeapConfig {
+    fn default() -> Self {
+        PeapConfig {
+            server_validation: ServerValidation {
+                perform_server_validation: Some(true),
+                accept_server_name: Some(true),
+                ..Default::default()
+            },
+            fast_reconnect: true,
+            inner_eap_optional: Some(false),
+            inner_method: PeapInnerMethod::MsChapV2 {
+                use_winlogon_credentials: false,
+            },
+            enable_quarantine_checks: false,
+            require_crypto_binding: false,
+            allow_prompting_when_server_ca_not_found: None,
+            other: Vec::new(),
+        }
+    }
+}
+
+/// Inner authentication method of PEAP
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeapInnerMethod {
+    /// EAP-MSCHAPv2 using a user name and password
+    MsChapV2 {
+        /// Whether the Windows logon credentials are used
+        use_winlogon_credentials: bool,
+    },
+
+    /// EAP-TLS using a client certificate
+    Tls(Box<TlsConfig>),
+
+    /// An inner method which is not modeled. Contains the inner `Eap` element
+    Other(XmlElement),
+}
+
+/// EAP-TLS settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// Where the client certificate is taken from
+    pub credentials_source: TlsCredentialsSource,
+
+    /// Server certificate validation settings
+    pub server_validation: ServerValidation,
+
+    /// Whether a different user name than the one in the certificate is used
+    pub different_username: bool,
+
+    /// Hashes of the issuers a client certificate must be issued by
+    pub issuer_hashes: Vec<String>,
+
+    /// Child elements of the `EapType` element which are not modeled. Written after the
+    /// modeled elements
+    pub other: Vec<XmlElement>,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        TlsConfig {
+            credentials_source: TlsCredentialsSource::CertificateStore {
+                simple_cert_selection: true,
+            },
+            server_validation: ServerValidation {
+                perform_server_validation: Some(true),
+                accept_server_name: Some(true),
+                ..Default::default()
+            },
+            different_username: false,
+            issuer_hashes: Vec::new(),
+            other: Vec::new(),
+        }
+    }
+}
+
+/// Source of the client certificate used for EAP-TLS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlsCredentialsSource {
+    /// A certificate from the certificate store of the computer
+    CertificateStore {
+        /// Whether the certificate is selected without prompting the user
+        simple_cert_selection: bool,
+    },
+
+    /// A certificate from a smart card
+    SmartCard,
+}
+
+/// EAP-TTLS settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TtlsConfig {
+    /// Server certificate validation settings. The trusted root CAs are certificate hashes
+    pub server_validation: ServerValidation,
+
+    /// Inner authentication method
+    pub phase2: TtlsPhase2,
+
+    /// Whether the identity is hidden in the outer authentication
+    pub identity_privacy: Option<bool>,
+
+    /// Identity sent in the outer authentication when identity privacy is enabled
+    pub anonymous_identity: Option<String>,
+}
+
+impl Default for TtlsConfig {
+    fn default() -> Self {
+        TtlsConfig {
+            server_validation: ServerValidation::default(),
+            phase2: TtlsPhase2::MsChapV2 {
+                use_winlogon_credentials: false,
+            },
+            identity_privacy: None,
+            anonymous_identity: None,
+        }
+    }
+}
+
+/// Inner authentication method of EAP-TTLS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TtlsPhase2 {
+    /// PAP
+    Pap,
+
+    /// CHAP
+    Chap,
+
+    /// MSCHAP
+    MsChap,
+
+    /// MSCHAPv2
+    MsChapV2 {
+        /// Whether the Windows logon credentials are used
+        use_winlogon_credentials: bool,
+    },
+
+    /// An inner method which is not modeled. Contains the child of `Phase2Authentication`
+    Other(XmlElement),
+}
+
+impl EapConfig {
+    /// Returns the EAP method identifiers of the configuration
+    pub fn method(&self) -> EapMethod {
+        match self {
+            EapConfig::Peap(_) => EapMethod::microsoft(EAP_TYPE_PEAP, 0),
+            EapConfig::Tls(_) => EapMethod::microsoft(EAP_TYPE_TLS, 0),
+            EapConfig::Ttls(_) => EapMethod::microsoft(EAP_TYPE_TTLS, EAP_TTLS_AUTHOR_ID),
+            EapConfig::Other(other) => other.method,
+        }
+    }
+
+    /// Returns the server certificate validation settings of the configuration
+    pub fn server_validation(&self) -> Option<&ServerValidation> {
+        match self {
+            EapConfig::Peap(peap) => Some(&peap.server_validation),
+            EapConfig::Tls(tls) => Some(&tls.server_validation),
+            EapConfig::Ttls(ttls) => Some(&ttls.server_validation),
+            EapConfig::Other(_) => None,
+        }
+    }
+
+    /// Returns the server certificate validation settings of the configuration for editing
+    pub fn server_validation_mut(&mut self) -> Option<&mut ServerValidation> {
+        match self {
+            EapConfig::Peap(peap) => Some(&mut peap.server_validation),
+            EapConfig::Tls(tls) => Some(&mut tls.server_validation),
+            EapConfig::Ttls(ttls) => Some(&mut ttls.server_validation),
+            EapConfig::Other(_) => None,
+        }
+    }
+
+    /// Parses the EAP configuration from an `EAPConfig` element
+    pub fn from_element(element: &XmlElement) -> Result<EapConfig, WinWifiError> {
+        let host_config = required_child(element, "EapHostConfig")?;
+        let method = EapMethod::from_element(required_child(host_config, "EapMethod")?)?;
+        let config = required_child(host_config, "Config")?;
+
+        let parsed = match (method.eap_type, method.vendor_id) {
+            (EAP_TYPE_PEAP, 0) => match eap_type_element(config, EAP_TYPE_PEAP)? {
+                Some(eap_type) => Some(EapConfig::Peap(PeapConfig::from_element(eap_type)?)),
+                None => None,
+            },
+            (EAP_TYPE_TLS, 0) => match eap_type_element(config, EAP_TYPE_TLS)? {
+                Some(eap_type) => Some(EapConfig::Tls(TlsConfig::from_element(eap_type)?)),
+                None => None,
+            },
+            (EAP_TYPE_TTLS, 0) => match config.child("EapTtls") {
+                Some(eap_ttls) => Some(EapConfig::Ttls(TtlsConfig::from_element(eap_ttls)?)),
+                None => None,
+            },
+            _ => None,
+        };
+
+        Ok(parsed.unwrap_or_else(|| {
+            EapConfig::Other(OtherEapConfig {
+                method,
+                config: config.clone(),
+            })
+        }))
+    }
+
+    /// Creates the `EAPConfig` element for the EAP configuration
+    pub fn to_element(&self) -> XmlElement {
+        let config = match self {
+            EapConfig::Peap(peap) => XmlElement::new("Config")
+                .with_namespace(EAP_HOST_CONFIG_NAMESPACE)
+                .with_child(base_eap_element(EAP_TYPE_PEAP, peap.to_element())),
+            EapConfig::Tls(tls) => XmlElement::new("Config")
+                .with_namespace(EAP_HOST_CONFIG_NAMESPACE)
+                .with_child(base_eap_element(EAP_TYPE_TLS, tls.to_element())),
+            EapConfig::Ttls(ttls) => XmlElement::new("Config")
+                .with_namespace(EAP_HOST_CONFIG_NAMESPACE)
+                .with_child(ttls.to_element()),
+            EapConfig::Other(other) => {
+                let mut config = other.config.clone();
+                config.name = "Config".to_string();
+                config
+            }
+        };
+
+        XmlElement::new("EAPConfig").with_child(
+            XmlElement::new("EapHostConfig")
+                .with_namespace(EAP_HOST_CONFIG_NAMESPACE)
+                .with_child(self.method().to_element())
+                .with_child(config),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for EapConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let element = XmlElement::deserialize(deserializer)?;
+        EapConfig::from_element(&element).map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for EapConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_element().serialize(serializer)
+    }
+}
+
+impl EapMethod {
+    /// Creates the identifiers of a method implemented by Microsoft
+    const fn microsoft(eap_type: u8, author_id: u32) -> EapMethod {
+        EapMethod {
+            eap_type,
+            vendor_id: 0,
+            vendor_type: 0,
+            author_id,
+        }
+    }
+
+    /// Parses the method identifiers from an `EapMethod` element
+    fn from_element(element: &XmlElement) -> Result<EapMethod, WinWifiError> {
+        Ok(EapMethod {
+            eap_type: parse_number(element, "Type")?.ok_or_else(|| missing("Type"))?,
+            vendor_id: parse_number(element, "VendorId")?.unwrap_or_default(),
+            vendor_type: parse_number(element, "VendorType")?.unwrap_or_default(),
+            author_id: parse_number(element, "AuthorId")?.unwrap_or_default(),
+        })
+    }
+
+    /// Creates the `EapMethod` element
+    fn to_element(self) -> XmlElement {
+        let common = |name: &str, value: String| {
+            XmlElement::new(name)
+                .with_namespace(EAP_COMMON_NAMESPACE)
+                .with_text(value)
+        };
+
+        XmlElement::new("EapMethod")
+            .with_child(common("Type", self.eap_type.to_string()))
+            .with_child(common("VendorId", self.vendor_id.to_string()))
+            .with_child(common("VendorType", self.vendor_type.to_string()))
+            .with_child(common("AuthorId", self.author_id.to_string()))
+    }
+}
+
+impl ServerValidation {
+    /// Parses the `ServerValidation` element used by PEAP and EAP-TLS
+    fn from_element(element: &XmlElement) -> Result<ServerValidation, WinWifiError> {
+        Ok(ServerValidation {
+            disable_user_prompt: parse_bool(element, "DisableUserPromptForServerValidation")?
+                .unwrap_or_default(),
+            server_names: element
+                .child_text("ServerNames")
+                .unwrap_or_default()
+                .to_string(),
+            trusted_root_ca: element
+                .children_named("TrustedRootCA")
+                .filter_map(|ca| ca.text.clone())
+                .collect(),
+            perform_server_validation: None,
+            accept_server_name: None,
+        })
+    }
+
+    /// Creates the `ServerValidation` element used by PEAP and EAP-TLS
+    fn to_element(&self) -> XmlElement {
+        let mut element = XmlElement::new("ServerValidation")
+            .with_child(bool_element(
+                "DisableUserPromptForServerValidation",
+                self.disable_user_prompt,
+            ))
+            .with_child(text_element("ServerNames", &self.server_names));
+
+        for ca in &self.trusted_root_ca {
+            element = element.with_child(text_element("TrustedRootCA", ca));
+        }
+
+        element
+    }
+}
+
+impl PeapConfig {
+    /// Parses the PEAP `EapType` element
+    fn from_element(element: &XmlElement) -> Result<PeapConfig, WinWifiError> {
+        let mut server_validation =
+            ServerValidation::from_element(required_child(element, "ServerValidation")?)?;
+
+        let extensions = element.child("PeapExtensions");
+        if let Some(extensions) = extensions {
+            server_validation.perform_server_validation =
+                parse_bool(extensions, "PerformServerValidation")?;
+            server_validation.accept_server_name = parse_bool(extensions, "AcceptServerName")?;
+        }
+
+        let inner_eap = required_child(element, "Eap")?;
+        let inner_method = match parse_number::<u8>(inner_eap, "Type")? {
+            Some(EAP_TYPE_MSCHAPV2) => PeapInnerMethod::MsChapV2 {
+                use_winlogon_credentials: match inner_eap.child("EapType") {
+                    Some(eap_type) => {
+                        parse_bool(eap_type, "UseWinLogonCredentials")?.unwrap_or_default()
+                    }
+                    None => false,
+                },
+            },
+            Some(EAP_TYPE_TLS) => PeapInnerMethod::Tls(Box::new(TlsConfig::from_element(
+                required_child(inner_eap, "EapType")?,
+            )?)),
+            _ => PeapInnerMethod::Other(inner_eap.clone()),
+        };
+
+        Ok(PeapConfig {
+            server_validation,
+            fast_reconnect: parse_bool(element, "FastReconnect")?.unwrap_or_default(),
+            inner_eap_optional: parse_bool(element, "InnerEapOptional")?,
+            inner_method,
+            enable_quarantine_checks: parse_bool(element, "EnableQuarantineChecks")?
+                .unwrap_or_default(),
+            require_crypto_binding: parse_bool(element, "RequireCryptoBinding")?
+                .unwrap_or_default(),
+            allow_prompting_when_server_ca_not_found: match extensions
+                .and_then(|extensions| extensions.child("PeapExtensionsV2"))
+            {
+                Some(extensions) => parse_bool(extensions, "AllowPromptingWhenServerCANotFound")?,
+                None => None,
+            },
+            other: other_children(element, PEAP_ELEMENTS),
+        })
+    }
+
+    /// Creates the PEAP `EapType` element
+    fn to_element(&self) -> XmlElement {
+        let mut element = XmlElement::new("EapType")
+            .with_namespace(PEAP_V1_NAMESPACE)
+            .with_child(self.server_validation.to_element())
+            .with_child(bool_element("FastReconnect", self.fast_reconnect));
+
+        if let Some(inner_eap_optional) = self.inner_eap_optional {
+            element = element.with_child(bool_element("InnerEapOptional", inner_eap_optional));
+        }
+
+        let inner_eap = match &self.inner_method {
+            PeapInnerMethod::MsChapV2 {
+                use_winlogon_credentials,
+            } => base_eap_element(
+                EAP_TYPE_MSCHAPV2,
+                XmlElement::new("EapType")
+                    .with_namespace(MSCHAPV2_NAMESPACE)
+                    .with_child(bool_element(
+                        "UseWinLogonCredentials",
+                        *use_winlogon_credentials,
+                    )),
+            ),
+            PeapInnerMethod::Tls(tls) => base_eap_element(EAP_TYPE_TLS, tls.to_element()),
+            PeapInnerMethod::Other(inner_eap) => inner_eap.clone(),
+        };
+
+        element = element
+            .with_child(inner_eap)
+            .with_child(bool_element(
+                "EnableQuarantineChecks",
+                self.enable_quarantine_checks,
+            ))
+            .with_child(bool_element(
+                "RequireCryptoBinding",
+                self.require_crypto_binding,
+            ));
+
+        let mut extensions = XmlElement::new("PeapExtensions");
+
+        if let Some(perform_server_validation) = self.server_validation.perform_server_validation {
+            extensions = extensions.with_child(
+                bool_element("PerformServerValidation", perform_server_validation)
+                    .with_namespace(PEAP_V2_NAMESPACE),
+            );
+        }
+
+        if let Some(accept_server_name) = self.server_validation.accept_server_name {
+            extensions = extensions.with_child(
+                bool_element("AcceptServerName", accept_server_name)
+                    .with_namespace(PEAP_V2_NAMESPACE),
+            );
+        }
+
+        if let Some(allow_prompting) = self.allow_prompting_when_server_ca_not_found {
+            extensions = extensions.with_child(
+                XmlElement::new("PeapExtensionsV2")
+                    .with_namespace(PEAP_V2_NAMESPACE)
+                    .with_child(
+                        bool_element("AllowPromptingWhenServerCANotFound", allow_prompting)
+                            .with_namespace(PEAP_V3_NAMESPACE),
+                    ),
+            );
+        }
+
+        if !extensions.children.is_empty() {
+            element = element.with_child(extensions);
+        }
+
+        element.children.extend(self.other.iter().cloned());
+        element
+    }
+}
+
+impl TlsConfig {
+    /// Parses the EAP-TLS `EapType` element
+    fn from_element(element: &XmlElement) -> Result<TlsConfig, WinWifiError> {
+        let credentials_source = required_child(element, "CredentialsSource")?;
+        let credentials_source = if credentials_source.child("SmartCard").is_some() {
+            TlsCredentialsSource::SmartCard
+        } else {
+            TlsCredentialsSource::CertificateStore {
+                simple_cert_selection: match credentials_source.child("CertificateStore") {
+                    Some(store) => parse_bool(store, "SimpleCertSelection")?.unwrap_or_default(),
+                    None => false,
+                },
+            }
+        };
+
+        let mut server_validation =
+            ServerValidation::from_element(required_child(element, "ServerValidation")?)?;
+        server_validation.perform_server_validation =
+            parse_bool(element, "PerformServerValidation")?;
+        server_validation.accept_server_name = parse_bool(element, "AcceptServerName")?;
+
+        let issuer_hashes = element
+            .child("TLSExtensions")
+            .and_then(|extensions| extensions.child("FilteringInfo"))
+            .and_then(|filtering| filtering.child("CAHashList"))
+            .map(|hashes| {
+                hashes
+                    .children_named("IssuerHash")
+                    .filter_map(|hash| hash.text.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(TlsConfig {
+            credentials_source,
+            server_validation,
+            different_username: parse_bool(element, "DifferentUsername")?.unwrap_or_default(),
+            issuer_hashes,
+            other: other_children(element, TLS_ELEMENTS),
+        })
+    }
+
+    /// Creates the EAP-TLS `EapType` element
+    fn to_element(&self) -> XmlElement {
+        let credentials_source = match self.credentials_source {
+            TlsCredentialsSource::CertificateStore {
+                simple_cert_selection,
+            } => XmlElement::new("CertificateStore")
+                .with_child(bool_element("SimpleCertSelection", simple_cert_selection)),
+            TlsCredentialsSource::SmartCard => XmlElement::new("SmartCard"),
+        };
+
+        let mut element = XmlElement::new("EapType")
+            .with_namespace(EAP_TLS_V1_NAMESPACE)
+            .with_child(XmlElement::new("CredentialsSource").with_child(credentials_source))
+            .with_child(self.server_validation.to_element())
+            .with_child(bool_element("DifferentUsername", self.different_username));
+
+        if let Some(perform_server_validation) = self.server_validation.perform_server_validation {
+            element = element.with_child(
+                bool_element("PerformServerValidation", perform_server_validation)
+                    .with_namespace(EAP_TLS_V2_NAMESPACE),
+            );
+        }
+
+        if let Some(accept_server_name) = self.server_validation.accept_server_name {
+            element = element.with_child(
+                bool_element("AcceptServerName", accept_server_name)
+                    .with_namespace(EAP_TLS_V2_NAMESPACE),
+            );
+        }
+
+        if !self.issuer_hashes.is_empty() {
+            let mut hashes = XmlElement::new("CAHashList");
+            hashes
+                .attributes
+                .push(("Enabled".to_string(), "true".to_string()));
+
+            for hash in &self.issuer_hashes {
+                hashes = hashes.with_child(text_element("IssuerHash", hash));
+            }
+
+            element = element.with_child(
+                XmlElement::new("TLSExtensions")
+                    .with_namespace(EAP_TLS_V2_NAMESPACE)
+                    .with_child(
+                        XmlElement::new("FilteringInfo")
+                            .with_namespace(EAP_TLS_V3_NAMESPACE)
+                            .with_child(hashes),
+                    ),
+            );
+        }
+
+        element.children.extend(self.other.iter().cloned());
+        element
+    }
+}
+
+impl TtlsConfig {
+    /// Parses the `EapTtls` element
+    fn from_element(element: &XmlElement) -> Result<TtlsConfig, WinWifiError> {
+        let validation = required_child(element, "ServerValidation")?;
+        let server_validation = ServerValidation {
+            disable_user_prompt: parse_bool(validation, "DisablePrompt")?.unwrap_or_default(),
+            server_names: validation
+                .child_text("ServerNames")
+                .unwrap_or_default()
+                .to_string(),
+            trusted_root_ca: validation
+                .children_named("TrustedRootCAHash")
+                .filter_map(|ca| ca.text.clone())
+                .collect(),
+            perform_server_validation: None,
+            accept_server_name: None,
+        };
+
+        let phase2 = required_child(element, "Phase2Authentication")?
+            .children
+            .first()
+            .ok_or_else(|| missing("Phase2Authentication"))?;
+
+        let phase2 = match phase2.name.as_str() {
+            "PAPAuthentication" => TtlsPhase2::Pap,
+            "CHAPAuthentication" => TtlsPhase2::Chap,
+            "MSCHAPAuthentication" => TtlsPhase2::MsChap,
+            "MSCHAPv2Authentication" => TtlsPhase2::MsChapV2 {
+                use_winlogon_credentials: parse_bool(phase2, "UseWinlogonCredentials")?
+                    .unwrap_or_default(),
+            },
+            _ => TtlsPhase2::Other(phase2.clone()),
+        };
+
+        let phase1 = element.child("Phase1Identity");
+
+        Ok(TtlsConfig {
+            server_validation,
+            phase2,
+            identity_privacy: match phase1 {
+                Some(phase1) => parse_bool(phase1, "IdentityPrivacy")?,
+                None => None,
+            },
+            anonymous_identity: phase1
+                .and_then(|phase1| phase1.child_text("AnonymousIdentity"))
+                .map(str::to_string),
+        })
+    }
+
+    /// Creates the `EapTtls` element
+    fn to_element(&self) -> XmlElement {
+        let mut validation = XmlElement::new("ServerValidation").with_child(text_element(
+            "ServerNames",
+            &self.server_validation.server_names,
+        ));
+
+        for ca in &self.server_validation.trusted_root_ca {
+            validation = validation.with_child(text_element("TrustedRootCAHash", ca));
+        }
+
+        validation = validation.with_child(bool_element(
+            "DisablePrompt",
+            self.server_validation.disable_user_prompt,
+        ));
+
+        let phase2 = match &self.phase2 {
+            TtlsPhase2::Pap => XmlElement::new("PAPAuthentication"),
+            TtlsPhase2::Chap => XmlElement::new("CHAPAuthentication"),
+            TtlsPhase2::MsChap => XmlElement::new("MSCHAPAuthentication"),
+            TtlsPhase2::MsChapV2 {
+                use_winlogon_credentials,
+            } => XmlElement::new("MSCHAPv2Authentication").with_child(bool_element(
+                "UseWinlogonCredentials",
+                *use_winlogon_credentials,
+            )),
+            TtlsPhase2::Other(phase2) => phase2.clone(),
+        };
+
+        let mut element = XmlElement::new("EapTtls")
+            .with_namespace(EAP_TTLS_NAMESPACE)
+            .with_child(validation)
+            .with_child(XmlElement::new("Phase2Authentication").with_child(phase2));
+
+        if self.identity_privacy.is_some() || self.anonymous_identity.is_some() {
+            let mut phase1 = XmlElement::new("Phase1Identity");
+
+            if let Some(identity_privacy) = self.identity_privacy {
+                phase1 = phase1.with_child(bool_element("IdentityPrivacy", identity_privacy));
+            }
+
+            if let Some(anonymous_identity) = &self.anonymous_identity {
+                phase1 = phase1.with_child(text_element("AnonymousIdentity", anonymous_identity));
+            }
+
+            element = element.with_child(phase1);
+        }
+
+        element
+    }
+}
+
+/// Returns the `EapType` element of a `Config` element if it contains the expected EAP type
+fn eap_type_element(
+    config: &XmlElement,
+    eap_type: u8,
+) -> Result<Option<&XmlElement>, WinWifiError> {
+    let Some(eap) = config.child("Eap") else {
+        return Ok(None);
+    };
+
+    if parse_number::<u8>(eap, "Type")? != Some(eap_type) {
+        return Ok(None);
+    }
+
+    Ok(eap.child("EapType"))
+}
+
+/// Returns the child elements which are not in the list of modeled elements
+fn other_children(element: &XmlElement, modeled: &[&str]) -> Vec<XmlElement> {
+    element
+        .children
+        .iter()
+        .filter(|child| !modeled.contains(&child.name.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Creates an `Eap` element in the base EAP connection properties namespace
+fn base_eap_element(eap_type: u8, eap_type_element: XmlElement) -> XmlElement {
+    XmlElement::new("Eap")
+        .with_namespace(BASE_EAP_NAMESPACE)
+        .with_child(text_element("Type", &eap_type.to_string()))
+        .with_child(eap_type_element)
+}
+
+/// Creates an element containing text
+fn text_element(name: &str, text: &str) -> XmlElement {
+    let element = XmlElement::new(name);
+
+    if text.is_empty() {
+        element
+    } else {
+        element.with_text(text)
+    }
+}
+
+/// Creates an element containing a boolean value
+fn bool_element(name: &str, value: bool) -> XmlElement {
+    XmlElement::new(name).with_text(value.to_string())
+}
+
+/// Returns a child element or an error if it is missing
+fn required_child<'a>(element: &'a XmlElement, name: &str) -> Result<&'a XmlElement, WinWifiError> {
+    element.child(name).ok_or_else(|| missing(name))
+}
+
+/// Parses the boolean value of a child element
+fn parse_bool(element: &XmlElement, name: &str) -> Result<Option<bool>, WinWifiError> {
+    match element.child_text(name).map(str::trim) {
+        Some("true") | Some("1") => Ok(Some(true)),
+        Some("false") | Some("0") => Ok(Some(false)),
+        Some(value) => Err(WinWifiError::InvalidProfile(format!(
+            "'{value}' is not a valid boolean value for the {name} element"
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// Parses the numeric value of a child element
+fn parse_number<T: std::str::FromStr>(
+    element: &XmlElement,
+    name: &str,
+) -> Result<Option<T>, WinWifiError> {
+    element
+        .child_text(name)
+        .map(|value| {
+            value.trim().parse().map_err(|_| {
+                WinWifiError::InvalidProfile(format!(
+                    "'{value}' is not a valid number for the {name} element"
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Creates an error for a missing element in the EAP configuration
+fn missing(name: &str) -> WinWifiError {
+    WinWifiError::InvalidProfile(format!(
+        "the EAP configuration is missing the {name} element"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{example_document, example_profile};
+
+    /// Returns the raw `EAPConfig` element of the first example profile
+    fn example_eap_config() -> XmlElement {
+        let document = example_document(0);
+
+        let start = document
+            .find("<EAPConfig>")
+            .expect("Missing EAPConfig element");
+        let end = document
+            .find("</EAPConfig>")
+            .expect("Missing EAPConfig element")
+            + "</EAPConfig>".len();
+
+        quick_xml::de::from_str(&document[start..end]).expect("Failed to parse EAPConfig element")
+    }
+
+    /// Checks the parsed values of the PEAP configuration in the example profile
+    #[test]
+    fn parse_example_peap() {
+        let eap_config =
+            EapConfig::from_element(&example_eap_config()).expect("Failed to parse EAP config");
+
+        let EapConfig::Peap(peap) = &eap_config else {
+            panic!("Expected a PEAP configuration but found {eap_config:?}");
+        };
+
+        assert_eq!(peap.server_validation.server_names, "");
+        assert_eq!(
+            peap.server_validation.trusted_root_ca,
+            ["00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00"]
+        );
+        assert_eq!(peap.server_validation.perform_server_validation, Some(true));
+        assert_eq!(peap.server_validation.accept_server_name, Some(true));
+        assert!(peap.fast_reconnect);
+        assert_eq!(
+            peap.inner_method,
+            PeapInnerMethod::MsChapV2 {
+                use_winlogon_credentials: false
+            }
+        );
+        assert_eq!(peap.allow_prompting_when_server_ca_not_found, Some(true));
+        assert_eq!(eap_config.method(), EapMethod::microsoft(EAP_TYPE_PEAP, 0));
+    }
+
+    /// Checks that writing the example PEAP configuration results in the same XML
+    #[test]
+    fn write_example_peap() {
+        let element = example_eap_config();
+        let eap_config = EapConfig::from_element(&element).expect("Failed to parse EAP config");
+
+        let mut expected = element;
+        expected.name = "EAPConfig".to_string();
+
+        assert_eq!(eap_config.to_element(), expected);
+    }
+
+    /// Checks that EAP-TLS configurations can be read and written
+    #[test]
+    fn tls_round_trip() {
+        let eap_config = EapConfig::Tls(TlsConfig {
+            server_validation: ServerValidation {
+                server_names: "radius.example.com".to_string(),
+                trusted_root_ca: vec!["0a 1b 2c".to_string()],
+                ..TlsConfig::default().server_validation
+            },
+            issuer_hashes: vec!["3d 4e 5f".to_string()],
+            ..Default::default()
+        });
+
+        let parsed =
+            EapConfig::from_element(&eap_config.to_element()).expect("Failed to parse EAP config");
+        assert_eq!(parsed, eap_config);
+        assert_eq!(parsed.method().eap_type, EAP_TYPE_TLS);
+    }
+
+    /// Checks that PEAP and EAP-TLS elements which are not modeled are kept
+    #[test]
+    fn other_elements_round_trip() {
+        let extra = XmlElement::new("VendorSetting")
+            .with_namespace("urn:example:vendor")
+            .with_text("enabled");
+
+        fn child_mut<'a>(element: &'a mut XmlElement, name: &str) -> &'a mut XmlElement {
+            element
+                .children
+                .iter_mut()
+                .find(|child| child.name == name)
+                .expect("Missing element")
+        }
+
+        let mut element = example_eap_config();
+        let eap_type = ["EapHostConfig", "Config", "Eap", "EapType"]
+            .into_iter()
+            .fold(&mut element, child_mut);
+        eap_type.children.push(extra.clone());
+
+        let EapConfig::Peap(peap) =
+            EapConfig::from_element(&element).expect("Failed to parse EAP config")
+        else {
+            panic!("Expected a PEAP configuration");
+        };
+        assert_eq!(peap.other, std::slice::from_ref(&extra));
+
+        let mut expected = element;
+        expected.name = "EAPConfig".to_string();
+        assert_eq!(EapConfig::Peap(peap).to_element(), expected);
+
+        let tls = EapConfig::Tls(TlsConfig {
+            other: vec![extra],
+            ..Default::default()
+        });
+        let parsed =
+            EapConfig::from_element(&tls.to_element()).expect("Failed to parse EAP config");
+        assert_eq!(parsed, tls);
+    }
+
+    /// Checks that EAP-TTLS configurations can be read and written
+    #[test]
+    fn ttls_round_trip() {
+        let eap_config = EapConfig::Ttls(TtlsConfig {
+            server_validation: ServerValidation {
+                server_names: "radius.example.com".to_string(),
+                trusted_root_ca: vec!["0a 1b 2c".to_string()],
+                ..Default::default()
+            },
+            phase2: TtlsPhase2::Pap,
+            identity_privacy: Some(true),
+            anonymous_identity: Some("anonymous".to_string()),
+        });
+
+        let parsed =
+            EapConfig::from_element(&eap_config.to_element()).expect("Failed to parse EAP config");
+        assert_eq!(parsed, eap_config);
+        assert_eq!(parsed.method().author_id, EAP_TTLS_AUTHOR_ID);
+    }
+
+    /// Checks that changing the server validation settings is reflected in the profile XML
+    #[test]
+    fn edit_server_validation() {
+        let mut profile = example_profile(0);
+
+        let server_validation = profile
+            .msm
+            .security
+            .one_x
+            .as_mut()
+            .and_then(|one_x| one_x.eap_config.as_mut())
+            .and_then(|eap_config| eap_config.server_validation_mut())
+            .expect("Missing server validation settings");
+
+        server_validation.server_names = "radius.example.com".to_string();
+        server_validation.trusted_root_ca = vec!["0a 1b 2c".to_string()];
+
+        let serialized = profile.to_xml().expect("Failed to serialize profile");
+        assert!(serialized.contains("<ServerNames>radius.example.com</ServerNames>"));
+        assert!(serialized.contains("<TrustedRootCA>0a 1b 2c</TrustedRootCA>"));
+    }
+
+    /// Makes sure that unknown EAP methods are kept as is
+    #[test]
+    fn other_method() {
+        let eap_config = EapConfig::Other(OtherEapConfig {
+            method: EapMethod {
+                eap_type: 43,
+                vendor_id: 0,
+                vendor_type: 0,
+                author_id: 0,
+            },
+            config: XmlElement::new("Config")
+                .with_namespace(EAP_HOST_CONFIG_NAMESPACE)
+                .with_child(XmlElement::new("Fast").with_text("true")),
+        });
+
+        let parsed =
+            EapConfig::from_element(&eap_config.to_element()).expect("Failed to parse EAP config");
+        assert_eq!(parsed, eap_config);
+    }
+}
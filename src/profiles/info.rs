@@ -1 +1,658 @@
+use std::str::FromStr;
 
+use serde::{Deserialize, Serialize};
+
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::{
+    eap::EapConfig,
+    redact::Redaction,
+    unknown::UnknownXml,
+    xml::{format_profile_document, protect_leaf_whitespace},
+};
+
+/// A wireless profile. Refer to https://learn.microsoft.com/en-us/windows/win32/nativewifi/wlan-profileschema-elements for more details.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "WLANProfile", rename_all = "camelCase")]
+pub struct WlanProfile {
+    /// Name of the profile
+    pub name: String,
+
+    /// SSID of the wireless network
+    #[serde(rename = "SSIDConfig")]
+    pub ssid_config: SsidConfig,
+
+    /// Type of the wireless network
+    pub connection_type: ConnectionType,
+
+    /// Whether the network is connected to automatically or manually
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_mode: Option<ConnectionMode>,
+
+    /// Whether to switch to a more preferred network when it is in range
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_switch: Option<bool>,
+
+    /// Media specific module settings
+    #[serde(rename = "MSM")]
+    pub msm: Msm,
+
+    /// MAC address randomization settings
+    #[serde(
+        rename = "MacRandomization",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mac_randomization: Option<MacRandomization>,
+
+    /// Elements and attributes of a parsed profile which are not part of the model. They are
+    /// written back out when the profile is serialized
+    #[serde(skip)]
+    pub unknown: UnknownXml,
+}
+
+impl WlanProfile {
+    /// Parses a wireless profile from a single `WLANProfile` XML document
+    pub fn from_xml(xml: &str) -> Result<WlanProfile, WinWifiError> {
+        let mut profile: WlanProfile = quick_xml::de::from_str(&protect_leaf_whitespace(xml)?)?;
+        profile.unknown =
+            UnknownXml::from_documents(xml, &profile.to_xml_with(Redaction::Reveal)?)?;
+        Ok(profile)
+    }
+
+    /// Serializes the wireless profile to an XML document in the format used by Windows. Secrets
+    /// are kept so the document can be passed back to Windows. Use [`WlanProfile::to_xml_with`]
+    /// to redact them
+    pub fn to_xml(&self) -> Result<String, WinWifiError> {
+        self.to_xml_with(Redaction::Reveal)
+    }
+
+    /// Serializes the wireless profile to an XML document with its secrets handled using the
+    /// specified redaction
+    pub fn to_xml_with(&self, redaction: Redaction) -> Result<String, WinWifiError> {
+        let redacted;
+        let profile = match redaction {
+            Redaction::Reveal => self,
+            redaction => {
+                redacted = self.redacted(redaction);
+                &redacted
+            }
+        };
+
+        let serialized = quick_xml::se::to_string(profile)?;
+        let document = format_profile_document(&serialized)?;
+        Ok(profile.unknown.merge_document(document)?)
+    }
+}
+
+impl FromStr for WlanProfile {
+    type Err = WinWifiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_xml(s)
+    }
+}
+
+/// SSID settings of a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SsidConfig {
+    /// SSID of the wireless network
+    #[serde(rename = "SSID")]
+    pub ssid: SsidElement,
+
+    /// Whether the network does not broadcast its SSID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub non_broadcast: Option<bool>,
+}
+
+/// The `SSID` element of a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SsidElement {
+    /// SSID stored as a hex string in the profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hex: Option<Ssid>,
+
+    /// SSID as a string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl SsidElement {
+    /// Returns the SSID of the element. The hex SSID is used when present since that is what
+    /// Windows uses. Returns an error if the element is empty or the name does not match the
+    /// hex SSID
+    pub fn ssid(&self) -> Result<Ssid, WinWifiError> {
+        match (&self.hex, &self.name) {
+            (Some(hex), Some(name)) if hex.as_bytes() != name.as_bytes() => {
+                Err(WinWifiError::InvalidSsid(format!(
+                    "the name '{name}' does not match the hex SSID '{}'",
+                    hex.to_hex()
+                )))
+            }
+            (Some(hex), _) => Ok(hex.clone()),
+            (None, Some(name)) => Ok(Ssid::from(name.as_str())),
+            (None, None) => Err(WinWifiError::InvalidSsid(
+                "the SSID element does not contain a hex or name element".to_string(),
+            )),
+        }
+    }
+}
+
+impl From<Ssid> for SsidElement {
+    /// Creates the element with both the hex SSID and the name. The name is left out if the
+    /// SSID is not valid UTF-8
+    fn from(ssid: Ssid) -> Self {
+        SsidElement {
+            name: ssid.to_str().map(str::to_string),
+            hex: Some(ssid),
+        }
+    }
+}
+
+/// Type of a wireless network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionType {
+    /// Infrastructure network
+    #[serde(rename = "ESS")]
+    Ess,
+
+    /// Ad hoc network
+    #[serde(rename = "IBSS")]
+    Ibss,
+}
+
+/// Whether a wireless network is connected to automatically or manually
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionMode {
+    /// Connect automatically when the network is in range
+    Auto,
+
+    /// Only connect when requested by the user
+    Manual,
+}
+
+/// A setting which is either enabled or disabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EnabledState {
+    /// Setting is enabled
+    Enabled,
+
+    /// Setting is disabled
+    Disabled,
+}
+
+/// Media specific module (MSM) settings of a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Msm {
+    /// Security settings of the wireless network
+    pub security: Security,
+}
+
+/// Security settings of a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Security {
+    /// Authentication and encryption methods of the network
+    pub auth_encryption: AuthEncryption,
+
+    /// Network key or passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_key: Option<SharedKey>,
+
+    /// Index of the WEP key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_index: Option<u8>,
+
+    /// Whether PMK caching is used
+    #[serde(
+        rename = "PMKCacheMode",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub pmk_cache_mode: Option<EnabledState>,
+
+    /// Time to live of the PMK cache in minutes
+    #[serde(
+        rename = "PMKCacheTTL",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub pmk_cache_ttl: Option<u32>,
+
+    /// Number of entries in the PMK cache
+    #[serde(
+        rename = "PMKCacheSize",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub pmk_cache_size: Option<u32>,
+
+    /// Whether pre-authentication is used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_auth_mode: Option<EnabledState>,
+
+    /// Number of pre-authentication attempts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_auth_throttle: Option<u32>,
+
+    /// 802.1X settings of the network
+    #[serde(rename = "OneX", default, skip_serializing_if = "Option::is_none")]
+    pub one_x: Option<OneX>,
+}
+
+/// Authentication and encryption methods of a wireless network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthEncryption {
+    /// Authentication method
+    pub authentication: Authentication,
+
+    /// Encryption method
+    pub encryption: Encryption,
+
+    /// Whether 802.1X authentication is used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_one_x: Option<bool>,
+
+    /// Whether FIPS mode is enabled
+    #[serde(rename = "FIPSMode", default, skip_serializing_if = "Option::is_none")]
+    pub fips_mode: Option<bool>,
+
+    /// Whether WPA3-Personal transition mode is used, allowing WPA2-Personal connections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transition_mode: Option<bool>,
+}
+
+/// Authentication method of a wireless network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Authentication {
+    /// Open 802.11 authentication
+    #[serde(rename = "open")]
+    Open,
+
+    /// Shared key 802.11 authentication
+    #[serde(rename = "shared")]
+    Shared,
+
+    /// WPA-Enterprise
+    #[serde(rename = "WPA")]
+    Wpa,
+
+    /// WPA-Personal
+    #[serde(rename = "WPAPSK")]
+    WpaPsk,
+
+    /// WPA2-Enterprise
+    #[serde(rename = "WPA2")]
+    Wpa2,
+
+    /// WPA2-Personal
+    #[serde(rename = "WPA2PSK")]
+    Wpa2Psk,
+
+    /// WPA3-Enterprise 192-bit mode. Older alias of `WPA3ENT192`
+    #[serde(rename = "WPA3")]
+    Wpa3,
+
+    /// WPA3-Personal
+    #[serde(rename = "WPA3SAE")]
+    Wpa3Sae,
+
+    /// WPA3-Enterprise 192-bit mode
+    #[serde(rename = "WPA3ENT192")]
+    Wpa3Ent192,
+
+    /// WPA3-Enterprise
+    #[serde(rename = "WPA3ENT")]
+    Wpa3Ent,
+
+    /// Opportunistic wireless encryption
+    #[serde(rename = "OWE")]
+    Owe,
+}
+
+/// Encryption method of a wireless network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Encryption {
+    /// No encryption
+    #[serde(rename = "none")]
+    None,
+
+    /// WEP encryption
+    #[serde(rename = "WEP")]
+    Wep,
+
+    /// TKIP encryption
+    #[serde(rename = "TKIP")]
+    Tkip,
+
+    /// AES-CCMP encryption
+    #[serde(rename = "AES")]
+    Aes,
+
+    /// GCMP-128 encryption
+    #[serde(rename = "GCMP")]
+    Gcmp,
+
+    /// GCMP-256 encryption
+    #[serde(rename = "GCMP256")]
+    Gcmp256,
+}
+
+/// Network key or passphrase of a wireless network
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedKey {
+    /// Type of key stored in the key material
+    pub key_type: KeyType,
+
+    /// Whether the key material is encrypted
+    pub protected: bool,
+
+    /// The network key or passphrase
+    pub key_material: String,
+}
+
+impl std::fmt::Debug for SharedKey {
+    /// Masks the key material so it is not shown in logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedKey")
+            .field("key_type", &self.key_type)
+            .field("protected", &self.protected)
+            .field("key_material", &super::redact::MASK_PLACEHOLDER)
+            .finish()
+    }
+}
+
+/// Type of key stored in a shared key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyType {
+    /// Raw network key
+    NetworkKey,
+
+    /// Passphrase which the network key is derived from
+    PassPhrase,
+}
+
+/// 802.1X settings of a wireless profile. Refer to https://learn.microsoft.com/en-us/windows/win32/nativewifi/onexschema-elements for more details.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OneX {
+    /// Whether user credentials are cached for later connections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_user_data: Option<bool>,
+
+    /// Seconds to wait after a failed authentication attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub held_period: Option<u32>,
+
+    /// Seconds to wait for a response from the authenticator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_period: Option<u32>,
+
+    /// Seconds to wait before sending an EAPOL-Start message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_period: Option<u32>,
+
+    /// Number of EAPOL-Start messages to send
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_start: Option<u32>,
+
+    /// Number of authentication failures allowed for a set of credentials
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_auth_failures: Option<u32>,
+
+    /// Type of credentials used for authentication
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_mode: Option<OneXAuthMode>,
+
+    /// EAP method configuration
+    #[serde(rename = "EAPConfig", default, skip_serializing_if = "Option::is_none")]
+    pub eap_config: Option<EapConfig>,
+}
+
+/// Type of credentials used for 802.1X authentication
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OneXAuthMode {
+    /// Machine credentials are used when no user is logged on
+    MachineOrUser,
+
+    /// Only machine credentials are used
+    Machine,
+
+    /// Only user credentials are used
+    User,
+
+    /// Guest credentials are used
+    Guest,
+}
+
+/// MAC address randomization settings of a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacRandomization {
+    /// Whether MAC address randomization is enabled for the network
+    pub enable_randomization: bool,
+
+    /// Seed used for generating the randomized MAC address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub randomization_seed: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{example_document, example_profile};
+
+    /// Checks that every example profile can be parsed
+    #[test]
+    fn parse_example_profiles() {
+        let names = (0..4)
+            .map(|index| example_profile(index).name)
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            names,
+            [
+                "REDACTED EAP",
+                "REDACTED OPEN",
+                "REDACTED EAP2",
+                "REDACTED WPA2PSK NONBROADCAST"
+            ]
+        );
+    }
+
+    /// Checks the parsed values of the example 802.1X profile
+    #[test]
+    fn parse_eap_profile() {
+        let profile: WlanProfile = example_document(0)
+            .parse()
+            .expect("Failed to parse example profile");
+
+        assert_eq!(profile.connection_type, ConnectionType::Ess);
+        assert_eq!(profile.connection_mode, Some(ConnectionMode::Auto));
+
+        let security = &profile.msm.security;
+        assert_eq!(
+            security.auth_encryption.authentication,
+            Authentication::Wpa2
+        );
+        assert_eq!(security.auth_encryption.encryption, Encryption::Aes);
+        assert_eq!(security.auth_encryption.use_one_x, Some(true));
+        assert_eq!(security.pmk_cache_mode, Some(EnabledState::Enabled));
+        assert_eq!(security.pmk_cache_ttl, Some(720));
+        assert_eq!(security.pmk_cache_size, Some(128));
+        assert_eq!(security.pre_auth_mode, Some(EnabledState::Disabled));
+        assert!(security.shared_key.is_none());
+
+        let one_x = security.one_x.as_ref().expect("Missing OneX element");
+        assert_eq!(one_x.auth_mode, Some(OneXAuthMode::User));
+
+        let mac_randomization = profile
+            .mac_randomization
+            .expect("Missing MacRandomization element");
+        assert!(!mac_randomization.enable_randomization);
+        assert_eq!(mac_randomization.randomization_seed, Some(0));
+    }
+
+    /// Checks the parsed values of the example WPA2-Personal profile
+    #[test]
+    fn parse_psk_profile() {
+        let profile = example_profile(3);
+
+        assert_eq!(
+            profile.ssid_config.ssid.hex,
+            Some(Ssid::from("REDACTED WPA2PSK NONBROADCAST"))
+        );
+        assert_eq!(
+            profile.ssid_config.ssid.name.as_deref(),
+            Some("REDACTED WPA2PSK")
+        );
+        assert_eq!(profile.ssid_config.non_broadcast, Some(true));
+
+        let security = &profile.msm.security;
+        assert_eq!(
+            security.auth_encryption.authentication,
+            Authentication::Wpa2Psk
+        );
+
+        let shared_key = security
+            .shared_key
+            .as_ref()
+            .expect("Missing sharedKey element");
+        assert_eq!(shared_key.key_type, KeyType::PassPhrase);
+        assert!(!shared_key.protected);
+        assert_eq!(shared_key.key_material, "password");
+        assert!(security.one_x.is_none());
+    }
+
+    /// Checks that serializing and parsing the example profiles again results in the same profiles
+    #[test]
+    fn round_trip_example_profiles() {
+        for index in 0..4 {
+            let profile = example_profile(index);
+            let serialized = profile
+                .to_xml_with(Redaction::Reveal)
+                .expect("Failed to serialize example profile");
+            let reparsed =
+                WlanProfile::from_xml(&serialized).expect("Failed to parse serialized profile");
+
+            assert_eq!(profile, reparsed);
+        }
+    }
+
+    /// Checks that spaces at the start and end of a passphrase survive a round trip
+    #[test]
+    fn round_trip_key_material_whitespace() {
+        let mut profile = example_profile(3);
+
+        for key_material in ["  pass phrase ", "        "] {
+            let shared_key = profile.msm.security.shared_key.as_mut().unwrap();
+            shared_key.key_material = key_material.into();
+
+            let serialized = profile
+                .to_xml_with(Redaction::Reveal)
+                .expect("Failed to serialize profile");
+            assert!(serialized.contains(&format!("<keyMaterial>{key_material}</keyMaterial>")));
+
+            let reparsed =
+                WlanProfile::from_xml(&serialized).expect("Failed to parse serialized profile");
+            assert_eq!(reparsed, profile);
+        }
+    }
+
+    /// Checks that serialized profiles have the namespaces Windows expects
+    #[test]
+    fn serialized_namespaces() {
+        let profile = example_profile(0);
+        let serialized = profile
+            .to_xml()
+            .expect("Failed to serialize example profile");
+
+        assert!(serialized.starts_with(
+            "<?xml version=\"1.0\"?>\n<WLANProfile xmlns=\"http://www.microsoft.com/networking/WLAN/profile/v1\">"
+        ));
+        assert!(serialized.contains("<OneX xmlns=\"http://www.microsoft.com/networking/OneX/v1\">"));
+        assert!(serialized.contains(
+            "<MacRandomization xmlns=\"http://www.microsoft.com/networking/WLAN/profile/v3\">"
+        ));
+        assert!(serialized.contains(
+            "<PerformServerValidation xmlns=\"http://www.microsoft.com/provisioning/MsPeapConnectionPropertiesV2\">true</PerformServerValidation>"
+        ));
+    }
+
+    /// Checks that the EAP configuration is kept when serializing a profile
+    #[test]
+    fn serialized_eap_config() {
+        let profile = example_profile(0);
+        let serialized = profile
+            .to_xml()
+            .expect("Failed to serialize example profile");
+
+        let eap_config = |profile: &WlanProfile| {
+            profile
+                .msm
+                .security
+                .one_x
+                .as_ref()
+                .and_then(|one_x| one_x.eap_config.clone())
+                .expect("Missing EAPConfig element")
+        };
+
+        let original = eap_config(&profile);
+        let reparsed = eap_config(
+            &WlanProfile::from_xml(&serialized).expect("Failed to parse serialized profile"),
+        );
+
+        assert_eq!(original, reparsed);
+        assert!(matches!(original, EapConfig::Peap(_)));
+    }
+
+    /// Checks that secrets are kept in the XML unless they are redacted and masked in debug output
+    #[test]
+    fn serialized_key_material() {
+        let profile = example_profile(3);
+
+        let serialized = profile
+            .to_xml()
+            .expect("Failed to serialize example profile");
+        assert!(serialized.contains("<keyMaterial>password</keyMaterial>"));
+        assert!(!format!("{profile:?}").contains("password"));
+
+        let masked = profile
+            .to_xml_with(Redaction::Mask)
+            .expect("Failed to serialize example profile");
+        assert!(masked.contains("<keyMaterial>********</keyMaterial>"));
+    }
+
+    /// Makes sure that a profile with an unknown authentication method is rejected
+    #[test]
+    fn invalid_authentication() {
+        let document = example_document(1).replace(
+            "<authentication>open</authentication>",
+            "<authentication>bogus</authentication>",
+        );
+
+        assert!(WlanProfile::from_xml(&document).is_err());
+    }
+
+    /// Makes sure that a name which does not match the hex SSID is detected
+    #[test]
+    fn mismatched_ssid() {
+        let ssid = &example_profile(3).ssid_config.ssid;
+
+        assert!(matches!(ssid.ssid(), Err(WinWifiError::InvalidSsid(_))));
+
+        let ssid = &example_profile(1).ssid_config.ssid;
+
+        assert_eq!(
+            ssid.ssid().expect("Failed to get SSID"),
+            Ssid::from("REDACTED OPEN")
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use std::fmt::Display;
+
+use super::{
+    eap::ServerValidation,
+    info::{Authentication, Encryption, WlanProfile},
+    validate::Severity,
+};
+
+/// Path of the EAP configuration used in the paths of findings
+const EAP_CONFIG_PATH: &str = "MSM/security/OneX/EAPConfig";
+
+/// A security rule checked by [`WlanProfile::lint`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LintRule {
+    /// The network is open without encryption
+    OpenNetwork,
+
+    /// The network uses WEP encryption
+    WepEncryption,
+
+    /// The network uses TKIP encryption
+    TkipEncryption,
+
+    /// The 802.1X server certificate is not validated
+    ServerValidationDisabled,
+
+    /// The 802.1X server certificate may be issued to any server name
+    EmptyServerNames,
+
+    /// A trusted root CA thumbprint is all zeros and does not refer to a real certificate
+    ZeroTrustedRootCa,
+
+    /// The shared key is stored in plain text
+    UnprotectedKeyMaterial,
+
+    /// MAC address randomization is disabled for the network
+    MacRandomizationDisabled,
+}
+
+impl LintRule {
+    /// Every rule which is checked
+    pub const ALL: &'static [LintRule] = &[
+        LintRule::OpenNetwork,
+        LintRule::WepEncryption,
+        LintRule::TkipEncryption,
+        LintRule::ServerValidationDisabled,
+        LintRule::EmptyServerNames,
+        LintRule::ZeroTrustedRootCa,
+        LintRule::UnprotectedKeyMaterial,
+        LintRule::MacRandomizationDisabled,
+    ];
+
+    /// Stable identifier of the rule
+    pub fn id(self) -> &'static str {
+        match self {
+            LintRule::OpenNetwork => "open-network",
+            LintRule::WepEncryption => "wep-encryption",
+            LintRule::TkipEncryption => "tkip-encryption",
+            LintRule::ServerValidationDisabled => "server-validation-disabled",
+            LintRule::EmptyServerNames => "empty-server-names",
+            LintRule::ZeroTrustedRootCa => "zero-trusted-root-ca",
+            LintRule::UnprotectedKeyMaterial => "unprotected-key-material",
+            LintRule::MacRandomizationDisabled => "mac-randomization-disabled",
+        }
+    }
+
+    /// Severity of the findings of the rule. Errors are configurations which can be attacked
+    /// directly, warnings weaken the protection of the network or its users
+    pub fn severity(self) -> Severity {
+        match self {
+            LintRule::OpenNetwork
+            | LintRule::WepEncryption
+            | LintRule::TkipEncryption
+            | LintRule::ServerValidationDisabled => Severity::Error,
+            LintRule::EmptyServerNames
+            | LintRule::ZeroTrustedRootCa
+            | LintRule::UnprotectedKeyMaterial
+            | LintRule::MacRandomizationDisabled => Severity::Warning,
+        }
+    }
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// A weak configuration found while linting a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// Rule which found the weak configuration
+    pub rule: LintRule,
+
+    /// Severity of the finding
+    pub severity: Severity,
+
+    /// Path of the element with the weak configuration, e.g. `WLANProfile/MSM/security/sharedKey`
+    pub path: String,
+
+    /// Description of the weak configuration
+    pub message: String,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}: {}",
+            self.severity, self.rule, self.path, self.message
+        )
+    }
+}
+
+/// Collects the findings of the rules
+#[derive(Default)]
+struct Linter {
+    findings: Vec<Finding>,
+}
+
+impl Linter {
+    fn push(&mut self, rule: LintRule, path: &str, message: impl Into<String>) {
+        self.findings.push(Finding {
+            rule,
+            severity: rule.severity(),
+            path: format!("WLANProfile/{path}"),
+            message: message.into(),
+        });
+    }
+
+    /// Checks the server certificate validation settings of an EAP method
+    fn server_validation(&mut self, server_validation: &ServerValidation) {
+        if server_validation.perform_server_validation == Some(false) {
+            self.push(
+                LintRule::ServerValidationDisabled,
+                &format!("{EAP_CONFIG_PATH}/PerformServerValidation"),
+                "the server certificate is not validated, so any server can collect the user credentials",
+            );
+            return;
+        }
+
+        if server_validation.server_names.trim().is_empty() {
+            self.push(
+                LintRule::EmptyServerNames,
+                &format!("{EAP_CONFIG_PATH}/ServerNames"),
+                "any server with a certificate from a trusted CA is accepted",
+            );
+        }
+
+        for thumbprint in &server_validation.trusted_root_ca {
+            let digits = thumbprint
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>();
+
+            if !digits.is_empty() && digits.chars().all(|c| c == '0') {
+                self.push(
+                    LintRule::ZeroTrustedRootCa,
+                    &format!("{EAP_CONFIG_PATH}/TrustedRootCA"),
+                    "the thumbprint is all zeros and does not refer to a CA certificate",
+                );
+            }
+        }
+    }
+}
+
+impl WlanProfile {
+    /// Checks the profile for weak security configurations. Returns every finding, or an empty
+    /// list if none of the rules in [`LintRule::ALL`] apply
+    pub fn lint(&self) -> Vec<Finding> {
+        let mut linter = Linter::default();
+
+        let security = &self.msm.security;
+        let authentication = security.auth_encryption.authentication;
+        let encryption = security.auth_encryption.encryption;
+        let use_one_x = security.auth_encryption.use_one_x == Some(true);
+
+        match encryption {
+            Encryption::None if authentication == Authentication::Open && !use_one_x => linter
+                .push(
+                    LintRule::OpenNetwork,
+                    "MSM/security/authEncryption/authentication",
+                    "the network is open and its traffic is not encrypted",
+                ),
+            Encryption::Wep => linter.push(
+                LintRule::WepEncryption,
+                "MSM/security/authEncryption/encryption",
+                "WEP keys can be recovered from captured traffic",
+            ),
+            Encryption::Tkip => linter.push(
+                LintRule::TkipEncryption,
+                "MSM/security/authEncryption/encryption",
+                "TKIP is deprecated and vulnerable to packet injection",
+            ),
+            _ => (),
+        }
+
+        if let Some(eap_config) = security
+            .one_x
+            .as_ref()
+            .filter(|_| use_one_x)
+            .and_then(|one_x| one_x.eap_config.as_ref())
+        {
+            if let Some(server_validation) = eap_config.server_validation() {
+                linter.server_validation(server_validation);
+            }
+        }
+
+        if security
+            .shared_key
+            .as_ref()
+            .is_some_and(|shared_key| !shared_key.protected)
+        {
+            linter.push(
+                LintRule::UnprotectedKeyMaterial,
+                "MSM/security/sharedKey/protected",
+                "the key material is stored in plain text",
+            );
+        }
+
+        if self
+            .mac_randomization
+            .as_ref()
+            .is_some_and(|mac_randomization| !mac_randomization.enable_randomization)
+        {
+            linter.push(
+                LintRule::MacRandomizationDisabled,
+                "MacRandomization/enableRandomization",
+                "the hardware MAC address can be used to track the device",
+            );
+        }
+
+        linter.findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        profiles::{
+            eap::{EapConfig, TtlsConfig},
+            WlanProfileBuilder,
+        },
+        testing::example_profile,
+    };
+
+    /// Returns the rule ids and paths of findings
+    fn findings(findings: &[Finding]) -> Vec<(&'static str, &str)> {
+        findings
+            .iter()
+            .map(|finding| (finding.rule.id(), finding.path.as_str()))
+            .collect()
+    }
+
+    /// Checks the findings for the example profiles
+    #[test]
+    fn lint_example_profiles() {
+        assert_eq!(
+            findings(&example_profile(0).lint()),
+            [
+                (
+                    "empty-server-names",
+                    "WLANProfile/MSM/security/OneX/EAPConfig/ServerNames"
+                ),
+                (
+                    "zero-trusted-root-ca",
+                    "WLANProfile/MSM/security/OneX/EAPConfig/TrustedRootCA"
+                ),
+                (
+                    "mac-randomization-disabled",
+                    "WLANProfile/MacRandomization/enableRandomization"
+                ),
+            ]
+        );
+
+        assert_eq!(
+            findings(&example_profile(1).lint()),
+            [
+                (
+                    "open-network",
+                    "WLANProfile/MSM/security/authEncryption/authentication"
+                ),
+                (
+                    "mac-randomization-disabled",
+                    "WLANProfile/MacRandomization/enableRandomization"
+                ),
+            ]
+        );
+
+        let psk = example_profile(3).lint();
+        assert_eq!(
+            findings(&psk),
+            [
+                (
+                    "unprotected-key-material",
+                    "WLANProfile/MSM/security/sharedKey/protected"
+                ),
+                (
+                    "mac-randomization-disabled",
+                    "WLANProfile/MacRandomization/enableRandomization"
+                ),
+            ]
+        );
+        assert_eq!(
+            psk[0].to_string(),
+            "warning[unprotected-key-material]: WLANProfile/MSM/security/sharedKey/protected: the key material is stored in plain text"
+        );
+    }
+
+    /// Checks the rules for weak encryption and disabled server validation
+    #[test]
+    fn lint_weak_security() {
+        let mut profile = WlanProfileBuilder::new("Weak")
+            .wpa2_psk("passphrase")
+            .build()
+            .expect("Failed to build profile");
+        profile.msm.security.auth_encryption.encryption = Encryption::Tkip;
+        profile.msm.security.shared_key.as_mut().unwrap().protected = true;
+
+        let lint = profile.lint();
+        assert_eq!(
+            findings(&lint),
+            [(
+                "tkip-encryption",
+                "WLANProfile/MSM/security/authEncryption/encryption"
+            )]
+        );
+        assert_eq!(lint[0].severity, Severity::Error);
+
+        let profile = WlanProfileBuilder::new("Weak")
+            .wpa2_enterprise()
+            .eap_config(EapConfig::Ttls(TtlsConfig {
+                server_validation: ServerValidation {
+                    perform_server_validation: Some(false),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }))
+            .build()
+            .expect("Failed to build profile");
+
+        assert_eq!(
+            findings(&profile.lint()),
+            [(
+                "server-validation-disabled",
+                "WLANProfile/MSM/security/OneX/EAPConfig/PerformServerValidation"
+            )]
+        );
+    }
+}
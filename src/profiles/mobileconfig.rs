@@ -0,0 +1,594 @@
+use quick_xml::escape::escape;
+
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::{
+    convert::{export_ssid, stable_uuid, Unconverted},
+    eap::{
+        EapConfig, PeapInnerMethod, ServerValidation, TtlsPhase2, EAP_TYPE_PEAP, EAP_TYPE_TLS,
+        EAP_TYPE_TTLS,
+    },
+    info::{Authentication, ConnectionMode, ConnectionType, Encryption, WlanProfile},
+    redact::Redaction,
+    validate::Diagnostic,
+};
+
+/// Payload type of Wi-Fi payloads
+pub const WIFI_PAYLOAD_TYPE: &str = "com.apple.wifi.managed";
+
+/// Prefix of the reverse DNS identifiers of the configuration profile and its payloads
+pub const PAYLOAD_IDENTIFIER_PREFIX: &str = "com.github.mehrn00.winwifi";
+
+/// Document type declaration of property lists
+const PLIST_DOCTYPE: &str = r#"<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">"#;
+
+/// An Apple configuration profile converted from a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MobileConfig {
+    /// Contents of the `.mobileconfig` property list
+    pub plist: String,
+
+    /// Settings of the profile which have no configuration profile equivalent and were left out
+    pub unconverted: Vec<Diagnostic>,
+}
+
+/// A property list value
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PlistValue {
+    String(String),
+    Integer(i64),
+    Bool(bool),
+    Array(Vec<PlistValue>),
+    Dict(PlistDict),
+}
+
+/// A property list dictionary with its keys in the order they are written
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct PlistDict {
+    entries: Vec<(&'static str, PlistValue)>,
+}
+
+impl PlistDict {
+    fn set(&mut self, key: &'static str, value: PlistValue) {
+        self.entries.push((key, value));
+    }
+
+    fn set_string(&mut self, key: &'static str, value: impl Into<String>) {
+        self.set(key, PlistValue::String(value.into()));
+    }
+
+    fn set_integer(&mut self, key: &'static str, value: i64) {
+        self.set(key, PlistValue::Integer(value));
+    }
+
+    fn set_bool(&mut self, key: &'static str, value: bool) {
+        self.set(key, PlistValue::Bool(value));
+    }
+}
+
+impl PlistValue {
+    /// Writes the value as XML indented by the specified number of tabs
+    fn write(&self, plist: &mut String, indent: usize) {
+        let tabs = "\t".repeat(indent);
+
+        match self {
+            PlistValue::String(value) => {
+                plist.push_str(&format!("{tabs}<string>{}</string>\n", escape(value)))
+            }
+            PlistValue::Integer(value) => {
+                plist.push_str(&format!("{tabs}<integer>{value}</integer>\n"))
+            }
+            PlistValue::Bool(true) => plist.push_str(&format!("{tabs}<true/>\n")),
+            PlistValue::Bool(false) => plist.push_str(&format!("{tabs}<false/>\n")),
+            PlistValue::Array(values) => {
+                plist.push_str(&format!("{tabs}<array>\n"));
+                for value in values {
+                    value.write(plist, indent + 1);
+                }
+                plist.push_str(&format!("{tabs}</array>\n"));
+            }
+            PlistValue::Dict(dict) => {
+                plist.push_str(&format!("{tabs}<dict>\n"));
+                for (key, value) in &dict.entries {
+                    plist.push_str(&format!("{tabs}\t<key>{key}</key>\n"));
+                    value.write(plist, indent + 1);
+                }
+                plist.push_str(&format!("{tabs}</dict>\n"));
+            }
+        }
+    }
+}
+
+impl WlanProfile {
+    /// Converts the profile to an Apple configuration profile with a `com.apple.wifi.managed`
+    /// payload for macOS and iOS. The network key is written to the `Password` key of the
+    /// payload, which is left out if the key is removed. Settings which have no configuration
+    /// profile equivalent are reported in the result
+    pub fn to_mobileconfig(&self, redaction: Redaction) -> Result<MobileConfig, WinWifiError> {
+        let mut unconverted = Unconverted::default();
+
+        let ssid = export_ssid(self, &mut unconverted)?;
+
+        let ssid_str = match ssid.to_str() {
+            Some(ssid) => ssid.to_string(),
+            None => {
+                unconverted.profile(
+                    "SSIDConfig/SSID/hex",
+                    "configuration profiles only support SSIDs which are valid UTF-8",
+                );
+                ssid.to_string_lossy().into_owned()
+            }
+        };
+
+        let profile = self.redacted(redaction);
+        let payload = wifi_payload(&profile, &ssid, ssid_str, &mut unconverted);
+        let configuration_uuid = configuration_uuid(&profile, &ssid);
+
+        let mut configuration = PlistDict::default();
+        configuration.set("PayloadContent", PlistValue::Array(vec![payload]));
+        configuration.set_string("PayloadDisplayName", profile.name.as_str());
+        configuration.set_string(
+            "PayloadIdentifier",
+            format!("{PAYLOAD_IDENTIFIER_PREFIX}.{configuration_uuid}"),
+        );
+        configuration.set_bool("PayloadRemovalDisallowed", false);
+        configuration.set_string("PayloadType", "Configuration");
+        configuration.set_string("PayloadUUID", configuration_uuid);
+        configuration.set_integer("PayloadVersion", 1);
+
+        let mut plist = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        plist.push_str(PLIST_DOCTYPE);
+        plist.push_str("\n<plist version=\"1.0\">\n");
+        PlistValue::Dict(configuration).write(&mut plist, 0);
+        plist.push_str("</plist>\n");
+
+        Ok(MobileConfig {
+            plist,
+            unconverted: unconverted.diagnostics,
+        })
+    }
+}
+
+/// Creates the `com.apple.wifi.managed` payload of a profile
+fn wifi_payload(
+    profile: &WlanProfile,
+    ssid: &Ssid,
+    ssid_str: String,
+    unconverted: &mut Unconverted,
+) -> PlistValue {
+    let security = &profile.msm.security;
+    let auth_encryption = &security.auth_encryption;
+    let authentication = auth_encryption.authentication;
+    let encryption = auth_encryption.encryption;
+    let use_one_x = auth_encryption.use_one_x == Some(true);
+
+    let payload_uuid = stable_uuid(&[WIFI_PAYLOAD_TYPE.as_bytes(), ssid.as_bytes()]);
+
+    let mut payload = PlistDict::default();
+    payload.set_bool(
+        "AutoJoin",
+        profile.connection_mode != Some(ConnectionMode::Manual),
+    );
+
+    if profile.connection_type == ConnectionType::Ibss {
+        unconverted.profile(
+            "connectionType",
+            "configuration profiles only support infrastructure networks",
+        );
+    }
+
+    if profile.auto_switch.is_some() {
+        unconverted.profile(
+            "autoSwitch",
+            "Apple devices do not switch to more preferred networks based on the profile",
+        );
+    }
+
+    let encryption_type = match authentication {
+        Authentication::Open | Authentication::Shared if encryption == Encryption::Wep => "WEP",
+        Authentication::Open | Authentication::Shared => "None",
+        Authentication::Owe => {
+            unconverted.profile(
+                "MSM/security/authEncryption/authentication",
+                "configuration profiles can not require OWE. The network is added as an open network",
+            );
+            "None"
+        }
+        Authentication::WpaPsk | Authentication::Wpa => "WPA",
+        Authentication::Wpa2Psk | Authentication::Wpa2 => "WPA2",
+        Authentication::Wpa3Sae
+        | Authentication::Wpa3
+        | Authentication::Wpa3Ent192
+        | Authentication::Wpa3Ent => "WPA3",
+    };
+    payload.set_string("EncryptionType", encryption_type);
+
+    if authentication == Authentication::Shared {
+        unconverted.profile(
+            "MSM/security/authEncryption/authentication",
+            "Apple devices do not support shared key authentication",
+        );
+    }
+
+    if auth_encryption.transition_mode == Some(true) {
+        unconverted.profile(
+            "MSM/security/authEncryption/transitionMode",
+            "Apple devices only connect to the network using WPA3",
+        );
+    }
+
+    if auth_encryption.fips_mode == Some(true) {
+        unconverted.profile(
+            "MSM/security/authEncryption/FIPSMode",
+            "configuration profiles do not have a FIPS mode setting",
+        );
+    }
+
+    payload.set_bool(
+        "HIDDEN_NETWORK",
+        profile.ssid_config.non_broadcast == Some(true),
+    );
+
+    if !use_one_x && matches!(encryption_type, "WEP" | "WPA" | "WPA2" | "WPA3") {
+        match &security.shared_key {
+            Some(shared_key) if shared_key.protected => unconverted.profile(
+                "MSM/security/sharedKey/keyMaterial",
+                "the key material is encrypted by Windows and has to be entered again",
+            ),
+            Some(shared_key) => payload.set_string("Password", shared_key.key_material.as_str()),
+            None => (),
+        }
+    }
+
+    if let Some(mac_randomization) = &profile.mac_randomization {
+        payload.set_bool(
+            "DisableAssociationMACRandomization",
+            !mac_randomization.enable_randomization,
+        );
+        if mac_randomization.randomization_seed.is_some() {
+            unconverted.profile(
+                "MacRandomization/randomizationSeed",
+                "Apple devices derive the private Wi-Fi address on their own",
+            );
+        }
+    }
+
+    unconverted.pmk_cache(security, "PMK caching is managed by the operating system");
+
+    if let Some(one_x) = security.one_x.as_ref().filter(|_| use_one_x) {
+        match &one_x.eap_config {
+            Some(eap_config) => {
+                if let Some(eap_client) = eap_client_configuration(eap_config, unconverted) {
+                    payload.set("EAPClientConfiguration", PlistValue::Dict(eap_client));
+                }
+            }
+            None => unconverted.profile(
+                "MSM/security/OneX",
+                "the profile does not contain an EAP configuration",
+            ),
+        }
+
+        unconverted.one_x(
+            one_x,
+            "configuration profiles do not have an equivalent 802.1X setting",
+        );
+    }
+
+    payload.set_string("PayloadDisplayName", format!("Wi-Fi ({})", profile.name));
+    payload.set_string(
+        "PayloadIdentifier",
+        format!("{PAYLOAD_IDENTIFIER_PREFIX}.wifi.{payload_uuid}"),
+    );
+    payload.set_string("PayloadType", WIFI_PAYLOAD_TYPE);
+    payload.set_string("PayloadUUID", payload_uuid);
+    payload.set_integer("PayloadVersion", 1);
+    payload.set_string("SSID_STR", ssid_str);
+
+    PlistValue::Dict(payload)
+}
+
+/// Creates the `EAPClientConfiguration` dictionary of an EAP configuration. Returns `None` if
+/// the EAP method is not supported
+fn eap_client_configuration(
+    eap_config: &EapConfig,
+    unconverted: &mut Unconverted,
+) -> Option<PlistDict> {
+    const PATH: &str = "MSM/security/OneX/EAPConfig";
+
+    let mut eap_client = PlistDict::default();
+
+    match eap_config {
+        EapConfig::Peap(peap) => {
+            eap_client.set(
+                "AcceptEAPTypes",
+                PlistValue::Array(vec![PlistValue::Integer(EAP_TYPE_PEAP.into())]),
+            );
+            match &peap.inner_method {
+                PeapInnerMethod::MsChapV2 {
+                    use_winlogon_credentials,
+                } => {
+                    if *use_winlogon_credentials {
+                        unconverted.profile(
+                            &format!("{PATH}/UseWinLogonCredentials"),
+                            "the Windows logon credentials can not be used",
+                        );
+                    }
+                }
+                PeapInnerMethod::Tls(_) => unconverted.profile(
+                    &format!("{PATH}/Eap"),
+                    "Apple devices only support EAP-MSCHAPv2 inside PEAP",
+                ),
+                PeapInnerMethod::Other(_) => unconverted.profile(
+                    &format!("{PATH}/Eap"),
+                    "the inner EAP method is not supported",
+                ),
+            }
+
+            if peap.enable_quarantine_checks {
+                unconverted.profile(
+                    &format!("{PATH}/EnableQuarantineChecks"),
+                    "Apple devices do not support network access protection",
+                );
+            }
+
+            convert_server_validation(&peap.server_validation, &mut eap_client, unconverted);
+        }
+        EapConfig::Tls(tls) => {
+            eap_client.set(
+                "AcceptEAPTypes",
+                PlistValue::Array(vec![PlistValue::Integer(EAP_TYPE_TLS.into())]),
+            );
+            unconverted.profile(
+                &format!("{PATH}/CredentialsSource"),
+                "the client certificate has to be added as a certificate payload and referenced by PayloadCertificateUUID",
+            );
+            convert_server_validation(&tls.server_validation, &mut eap_client, unconverted);
+        }
+        EapConfig::Ttls(ttls) => {
+            eap_client.set(
+                "AcceptEAPTypes",
+                PlistValue::Array(vec![PlistValue::Integer(EAP_TYPE_TTLS.into())]),
+            );
+            match &ttls.phase2 {
+                TtlsPhase2::Pap => eap_client.set_string("TTLSInnerAuthentication", "PAP"),
+                TtlsPhase2::Chap => eap_client.set_string("TTLSInnerAuthentication", "CHAP"),
+                TtlsPhase2::MsChap => eap_client.set_string("TTLSInnerAuthentication", "MSCHAP"),
+                TtlsPhase2::MsChapV2 {
+                    use_winlogon_credentials,
+                } => {
+                    eap_client.set_string("TTLSInnerAuthentication", "MSCHAPv2");
+                    if *use_winlogon_credentials {
+                        unconverted.profile(
+                            &format!("{PATH}/Phase2Authentication"),
+                            "the Windows logon credentials can not be used",
+                        );
+                    }
+                }
+                TtlsPhase2::Other(_) => unconverted.profile(
+                    &format!("{PATH}/Phase2Authentication"),
+                    "the inner authentication method is not supported",
+                ),
+            }
+            if let Some(anonymous_identity) = &ttls.anonymous_identity {
+                eap_client.set_string("OuterIdentity", anonymous_identity.as_str());
+            }
+            convert_server_validation(&ttls.server_validation, &mut eap_client, unconverted);
+        }
+        EapConfig::Other(other) => {
+            unconverted.profile(
+                PATH,
+                format!("EAP type {} is not supported", other.method.eap_type),
+            );
+            return None;
+        }
+    }
+
+    Some(eap_client)
+}
+
+/// Adds the server certificate validation settings to the `EAPClientConfiguration` dictionary
+fn convert_server_validation(
+    server_validation: &ServerValidation,
+    eap_client: &mut PlistDict,
+    unconverted: &mut Unconverted,
+) {
+    const PATH: &str = "MSM/security/OneX/EAPConfig";
+
+    if server_validation.perform_server_validation == Some(false) {
+        unconverted.profile(
+            &format!("{PATH}/PerformServerValidation"),
+            "Apple devices always validate the server certificate",
+        );
+        return;
+    }
+
+    let server_names = server_validation
+        .server_names
+        .split(';')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| PlistValue::String(name.to_string()))
+        .collect::<Vec<_>>();
+
+    if !server_names.is_empty() {
+        eap_client.set("TLSTrustedServerNames", PlistValue::Array(server_names));
+    }
+
+    if !server_validation.trusted_root_ca.is_empty() {
+        unconverted.profile(
+            &format!("{PATH}/TrustedRootCA"),
+            "certificate thumbprints can not be used. The CA certificates have to be added as certificate payloads and referenced by PayloadCertificateAnchorUUID",
+        );
+    }
+}
+
+/// Creates the UUID of the configuration profile of a wireless profile
+fn configuration_uuid(profile: &WlanProfile, ssid: &Ssid) -> String {
+    stable_uuid(&[b"Configuration", profile.name.as_bytes(), ssid.as_bytes()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        profiles::{eap::TtlsConfig, WlanProfileBuilder},
+        testing::example_profile,
+    };
+
+    /// Returns the lines of the plist without indentation
+    fn lines(plist: &str) -> Vec<&str> {
+        plist.lines().map(str::trim).collect()
+    }
+
+    /// Returns the value line following a key of the plist
+    fn value<'a>(plist: &'a str, key: &str) -> Option<&'a str> {
+        let lines = lines(plist);
+        let index = lines
+            .iter()
+            .position(|line| *line == format!("<key>{key}</key>"))?;
+        lines.get(index + 1).copied()
+    }
+
+    /// Checks the conversion of a WPA2-Personal profile
+    #[test]
+    fn psk_profile() {
+        let profile = WlanProfileBuilder::new("Home & Office")
+            .wpa2_psk("pass<word>")
+            .build()
+            .expect("Failed to build profile");
+
+        let config = profile
+            .to_mobileconfig(Redaction::Reveal)
+            .expect("Failed to convert profile");
+
+        assert!(config.plist.starts_with("<?xml version=\"1.0\""));
+        assert_eq!(
+            value(&config.plist, "PayloadType"),
+            Some("<string>com.apple.wifi.managed</string>")
+        );
+        assert_eq!(
+            value(&config.plist, "SSID_STR"),
+            Some("<string>Home &amp; Office</string>")
+        );
+        assert_eq!(
+            value(&config.plist, "EncryptionType"),
+            Some("<string>WPA2</string>")
+        );
+        assert_eq!(value(&config.plist, "HIDDEN_NETWORK"), Some("<false/>"));
+        assert_eq!(
+            value(&config.plist, "Password"),
+            Some("<string>pass&lt;word&gt;</string>")
+        );
+        assert!(config.unconverted.is_empty());
+
+        let masked = profile
+            .to_mobileconfig(Redaction::default())
+            .expect("Failed to convert profile");
+        assert_eq!(
+            value(&masked.plist, "Password"),
+            Some("<string>********</string>")
+        );
+
+        let removed = profile
+            .to_mobileconfig(Redaction::Remove)
+            .expect("Failed to convert profile");
+        assert_eq!(value(&removed.plist, "Password"), None);
+
+        // The UUIDs are the same each time the profile is converted
+        assert_eq!(
+            value(&masked.plist, "PayloadUUID"),
+            value(&removed.plist, "PayloadUUID")
+        );
+    }
+
+    /// Checks the conversion of the example hidden network profile
+    #[test]
+    fn hidden_profile() {
+        let config = example_profile(3)
+            .to_mobileconfig(Redaction::Mask)
+            .expect("Failed to convert profile");
+
+        assert_eq!(value(&config.plist, "HIDDEN_NETWORK"), Some("<true/>"));
+        assert_eq!(
+            config.unconverted[0].path,
+            "WLANProfile/SSIDConfig/SSID/name"
+        );
+    }
+
+    /// Checks the conversion of the example PEAP profile
+    #[test]
+    fn peap_profile() {
+        let config = example_profile(0)
+            .to_mobileconfig(Redaction::Mask)
+            .expect("Failed to convert profile");
+
+        let lines = lines(&config.plist);
+        let start = lines
+            .iter()
+            .position(|line| *line == "<key>EAPClientConfiguration</key>")
+            .expect("Missing EAPClientConfiguration");
+        assert_eq!(
+            lines[start + 1..start + 7],
+            [
+                "<dict>",
+                "<key>AcceptEAPTypes</key>",
+                "<array>",
+                "<integer>25</integer>",
+                "</array>",
+                "</dict>"
+            ]
+        );
+        assert_eq!(value(&config.plist, "Password"), None);
+        assert!(config
+            .unconverted
+            .iter()
+            .any(|diagnostic| diagnostic.path
+                == "WLANProfile/MSM/security/OneX/EAPConfig/TrustedRootCA"));
+    }
+
+    /// Checks the server names and inner method of an EAP-TTLS profile
+    #[test]
+    fn ttls_profile() {
+        let profile = WlanProfileBuilder::new("TTLS")
+            .wpa2_enterprise()
+            .eap_config(EapConfig::Ttls(TtlsConfig {
+                server_validation: ServerValidation {
+                    server_names: "radius1.example.com;radius2.example.com".to_string(),
+                    ..Default::default()
+                },
+                phase2: TtlsPhase2::Pap,
+                anonymous_identity: Some("anonymous".to_string()),
+                ..Default::default()
+            }))
+            .build()
+            .expect("Failed to build profile");
+
+        let config = profile
+            .to_mobileconfig(Redaction::Reveal)
+            .expect("Failed to convert profile");
+
+        assert_eq!(
+            value(&config.plist, "TTLSInnerAuthentication"),
+            Some("<string>PAP</string>")
+        );
+        assert_eq!(
+            value(&config.plist, "OuterIdentity"),
+            Some("<string>anonymous</string>")
+        );
+
+        let lines = lines(&config.plist);
+        let start = lines
+            .iter()
+            .position(|line| *line == "<key>TLSTrustedServerNames</key>")
+            .expect("Missing TLSTrustedServerNames");
+        assert_eq!(
+            lines[start + 1..start + 5],
+            [
+                "<array>",
+                "<string>radius1.example.com</string>",
+                "<string>radius2.example.com</string>",
+                "</array>"
+            ]
+        );
+    }
+}
@@ -1,16 +1,35 @@
-use std::{ffi::OsString, marker::PhantomData, os::windows::ffi::OsStringExt, ptr::NonNull};
-
-use windows::Win32::{
-    Foundation::WIN32_ERROR,
-    NetworkManagement::WiFi::{
-        WlanFreeMemory, WlanGetProfileList, WLAN_PROFILE_GROUP_POLICY, WLAN_PROFILE_INFO,
-        WLAN_PROFILE_INFO_LIST, WLAN_PROFILE_USER,
-    },
+use std::{ffi::OsString, marker::PhantomData, ptr::NonNull};
+
+use windows::Win32::NetworkManagement::WiFi::{
+    WLAN_PROFILE_GROUP_POLICY, WLAN_PROFILE_INFO, WLAN_PROFILE_INFO_LIST, WLAN_PROFILE_USER,
 };
 
-use crate::{errors::WinWifiError, interfaces::WlanInterface};
+use crate::{
+    backend::{os_string_from_wide, WlanCall},
+    errors::{WinWifiError, WlanErrorContext},
+    interfaces::WlanInterface,
+    snapshot::ProfileInfo,
+};
 
-mod info;
+mod builder;
+mod convert;
+pub mod eap;
+pub mod info;
+pub mod lint;
+pub mod mobileconfig;
+pub mod networkmanager;
+pub mod onc;
+pub mod psk;
+pub mod qr;
+pub mod reader;
+pub mod redact;
+pub mod syncml;
+pub mod unknown;
+pub mod validate;
+pub mod wpa_supplicant;
+pub mod xml;
+
+pub use builder::WlanProfileBuilder;
 
 /// A list of saved wireless profiles for a wireless interface
 pub struct WlanInterfaceProfiles<'interfaces, 'handle: 'interfaces> {
@@ -28,19 +47,17 @@ impl<'interfaces, 'handle: 'interfaces> WlanInterfaceProfiles<'interfaces, 'hand
     pub fn new(
         interface: &'interfaces WlanInterface<'interfaces, 'handle>,
     ) -> Result<WlanInterfaceProfiles<'interfaces, 'handle>, WinWifiError> {
-        let mut profile_list_ptr = std::ptr::null_mut();
-
         let wlan_handle = interface.handle;
 
-        WIN32_ERROR(unsafe {
-            WlanGetProfileList(
-                *wlan_handle.as_ptr(),
-                interface.guid().as_ptr(),
-                None,
-                &mut profile_list_ptr,
-            )
-        })
-        .ok()?;
+        let interface_guid = interface.guid().as_guid();
+        let profile_list_ptr = wlan_handle
+            .backend()
+            .get_profile_list(wlan_handle.raw(), interface_guid)
+            .map_err(|code| {
+                WlanErrorContext::new(WlanCall::GetProfileList)
+                    .with_interface(interface_guid)
+                    .error(code)
+            })?;
 
         Ok(WlanInterfaceProfiles {
             interface,
@@ -89,7 +106,12 @@ impl<'interfaces, 'handle: 'interfaces> WlanInterfaceProfiles<'interfaces, 'hand
 
 impl<'interfaces, 'handle: 'interfaces> Drop for WlanInterfaceProfiles<'interfaces, 'handle> {
     fn drop(&mut self) {
-        unsafe { WlanFreeMemory(self.profile_list_ptr.as_ptr().cast()) };
+        unsafe {
+            self.interface
+                .handle
+                .backend()
+                .free_memory(self.profile_list_ptr.as_ptr().cast())
+        };
     }
 }
 
@@ -138,9 +160,7 @@ impl<'profiles, 'interfaces: 'profiles, 'handle: 'interfaces> Iterator
     }
 }
 
-impl<'handle: 'interfaces, 'interfaces: 'profiles, 'profiles> ExactSizeIterator
-    for WlanInterfaceProfilesIterator<'_, '_, '_>
-{
+impl ExactSizeIterator for WlanInterfaceProfilesIterator<'_, '_, '_> {
     fn len(&self) -> usize {
         self.item_count
     }
@@ -159,7 +179,7 @@ pub struct WlanInterfaceProfile<'profiles, 'interfaces: 'profiles, 'handle: 'int
     _marker: PhantomData<&'profiles WLAN_PROFILE_INFO>,
 }
 
-impl<'profiles, 'interfaces: 'profiles, 'handle: 'interfaces> WlanInterfaceProfile<'_, '_, '_> {
+impl WlanInterfaceProfile<'_, '_, '_> {
     pub fn group_policy_profile(&self) -> bool {
         let profile = unsafe { self.profile_ptr.as_ref() };
         profile.dwFlags & WLAN_PROFILE_GROUP_POLICY != 0
@@ -173,6 +193,11 @@ impl<'profiles, 'interfaces: 'profiles, 'handle: 'interfaces> WlanInterfaceProfi
     pub fn name(&self) -> Option<OsString> {
         let profile = unsafe { self.profile_ptr.as_ref() };
         let null_index = profile.strProfileName.iter().position(|v| v == &0)?;
-        Some(OsString::from_wide(&profile.strProfileName[..null_index]))
+        Some(os_string_from_wide(&profile.strProfileName[..null_index]))
+    }
+
+    /// Copies the profile into an owned snapshot
+    pub fn to_owned(&self) -> ProfileInfo {
+        ProfileInfo::from(unsafe { self.profile_ptr.as_ref() })
     }
 }
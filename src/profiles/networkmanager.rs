@@ -0,0 +1,607 @@
+use std::fmt::Write;
+
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::{
+    convert::{export_ssid, stable_uuid, Unconverted},
+    eap::{EapConfig, PeapInnerMethod, ServerValidation, TlsCredentialsSource, TtlsPhase2},
+    info::{Authentication, ConnectionMode, ConnectionType, Encryption, WlanProfile},
+    redact::Redaction,
+    validate::Diagnostic,
+};
+
+/// Value of the secret flags which makes NetworkManager ask for the secret when connecting
+const SECRET_FLAGS_NOT_SAVED: &str = "2";
+
+/// A NetworkManager connection converted from a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NmConnection {
+    /// Contents of the `.nmconnection` keyfile
+    pub keyfile: String,
+
+    /// Settings of the profile which have no NetworkManager equivalent and were left out
+    pub unconverted: Vec<Diagnostic>,
+}
+
+/// A section of a keyfile with its keys in the order they are written
+struct KeyfileSection {
+    name: &'static str,
+    entries: Vec<(&'static str, String)>,
+}
+
+impl KeyfileSection {
+    fn new(name: &'static str) -> KeyfileSection {
+        KeyfileSection {
+            name,
+            entries: Vec::new(),
+        }
+    }
+
+    fn set(&mut self, key: &'static str, value: impl Into<String>) {
+        self.entries.push((key, value.into()));
+    }
+}
+
+/// Builds the keyfile and collects the settings which could not be converted
+#[derive(Default)]
+struct Converter {
+    sections: Vec<KeyfileSection>,
+    unconverted: Unconverted,
+}
+
+impl Converter {
+    fn into_connection(self) -> NmConnection {
+        let mut keyfile = String::new();
+
+        for section in self.sections {
+            if !keyfile.is_empty() {
+                keyfile.push('\n');
+            }
+
+            let _ = writeln!(keyfile, "[{}]", section.name);
+            for (key, value) in section.entries {
+                let _ = writeln!(keyfile, "{key}={}", escape_value(&value));
+            }
+        }
+
+        NmConnection {
+            keyfile,
+            unconverted: self.unconverted.diagnostics,
+        }
+    }
+}
+
+impl WlanProfile {
+    /// Converts the profile to a NetworkManager `.nmconnection` keyfile. Secrets are left out
+    /// unless the redaction is [`Redaction::Reveal`], since NetworkManager would use a
+    /// placeholder as the real key. Keys which are left out or encrypted are marked as not
+    /// saved, so NetworkManager asks for them when connecting. Settings which have no
+    /// NetworkManager equivalent are reported in the result
+    pub fn to_nm_connection(&self, redaction: Redaction) -> Result<NmConnection, WinWifiError> {
+        let mut converter = Converter::default();
+
+        let ssid = export_ssid(self, &mut converter.unconverted)?;
+
+        let profile = self.redacted(match redaction {
+            Redaction::Reveal => Redaction::Reveal,
+            _ => Redaction::Remove,
+        });
+        let security = &profile.msm.security;
+        let auth_encryption = &security.auth_encryption;
+
+        let mut connection = KeyfileSection::new("connection");
+        connection.set("id", profile.name.as_str());
+        connection.set("uuid", connection_uuid(&profile.name, &ssid));
+        connection.set("type", "wifi");
+        if profile.connection_mode == Some(ConnectionMode::Manual) {
+            connection.set("autoconnect", "false");
+        }
+        if profile.auto_switch.is_some() {
+            converter.unconverted.profile(
+                "autoSwitch",
+                "NetworkManager does not switch to more preferred networks",
+            );
+        }
+        converter.sections.push(connection);
+
+        let mut wifi = KeyfileSection::new("wifi");
+        wifi.set(
+            "mode",
+            match profile.connection_type {
+                ConnectionType::Ess => "infrastructure",
+                ConnectionType::Ibss => "adhoc",
+            },
+        );
+        wifi.set("ssid", ssid_value(&ssid));
+        if profile.ssid_config.non_broadcast == Some(true) {
+            wifi.set("hidden", "true");
+        }
+        if let Some(mac_randomization) = &profile.mac_randomization {
+            wifi.set(
+                "cloned-mac-address",
+                if mac_randomization.enable_randomization {
+                    "stable"
+                } else {
+                    "permanent"
+                },
+            );
+            if mac_randomization.randomization_seed.is_some() {
+                converter.unconverted.profile(
+                    "MacRandomization/randomizationSeed",
+                    "NetworkManager derives the randomized MAC address from its own secret key",
+                );
+            }
+        }
+        converter.sections.push(wifi);
+
+        let authentication = auth_encryption.authentication;
+        let encryption = auth_encryption.encryption;
+        let use_one_x = auth_encryption.use_one_x == Some(true);
+
+        let mut wifi_security = KeyfileSection::new("wifi-security");
+        match authentication {
+            Authentication::Open | Authentication::Shared if encryption == Encryption::Wep => {
+                if use_one_x {
+                    wifi_security.set("key-mgmt", "ieee8021x");
+                } else {
+                    wifi_security.set("key-mgmt", "none");
+                    if authentication == Authentication::Shared {
+                        wifi_security.set("auth-alg", "shared");
+                    }
+                    if let Some(key_index) = security.key_index {
+                        wifi_security.set("wep-tx-keyidx", key_index.to_string());
+                    }
+                    // Both key types of Windows are raw WEP keys, NetworkManager's passphrase
+                    // type is hashed into a key
+                    wifi_security.set("wep-key-type", "1");
+                    convert_shared_key(&profile, &mut wifi_security, &mut converter);
+                }
+            }
+            Authentication::Open | Authentication::Shared => (),
+            Authentication::Owe => wifi_security.set("key-mgmt", "owe"),
+            Authentication::WpaPsk | Authentication::Wpa2Psk | Authentication::Wpa3Sae => {
+                if authentication == Authentication::Wpa3Sae {
+                    wifi_security.set("key-mgmt", "sae");
+                    wifi_security.set("pmf", "3");
+                } else {
+                    wifi_security.set("key-mgmt", "wpa-psk");
+                }
+                convert_shared_key(&profile, &mut wifi_security, &mut converter);
+            }
+            Authentication::Wpa
+            | Authentication::Wpa2
+            | Authentication::Wpa3
+            | Authentication::Wpa3Ent192
+            | Authentication::Wpa3Ent => {
+                wifi_security.set(
+                    "key-mgmt",
+                    match authentication {
+                        Authentication::Wpa3 | Authentication::Wpa3Ent192 => "wpa-eap-suite-b-192",
+                        _ => "wpa-eap",
+                    },
+                );
+                if matches!(
+                    authentication,
+                    Authentication::Wpa3 | Authentication::Wpa3Ent192 | Authentication::Wpa3Ent
+                ) {
+                    wifi_security.set("pmf", "3");
+                }
+            }
+        }
+
+        match authentication {
+            Authentication::Wpa | Authentication::WpaPsk => wifi_security.set("proto", "wpa"),
+            Authentication::Wpa2 | Authentication::Wpa2Psk => wifi_security.set("proto", "rsn"),
+            _ => (),
+        }
+
+        match encryption {
+            Encryption::Tkip => wifi_security.set("pairwise", "tkip"),
+            Encryption::Aes => wifi_security.set("pairwise", "ccmp"),
+            _ => (),
+        }
+
+        if auth_encryption.transition_mode == Some(true) {
+            wifi_security.entries.retain(|(key, _)| *key != "pmf");
+            wifi_security.set("pmf", "2");
+            converter.unconverted.profile(
+                "MSM/security/authEncryption/transitionMode",
+                "NetworkManager only connects to the network using WPA3-Personal",
+            );
+        }
+
+        if auth_encryption.fips_mode == Some(true) {
+            converter.unconverted.profile(
+                "MSM/security/authEncryption/FIPSMode",
+                "NetworkManager does not have a FIPS mode setting",
+            );
+        }
+
+        converter
+            .unconverted
+            .pmk_cache(security, "PMK caching is managed by wpa_supplicant");
+
+        if !wifi_security.entries.is_empty() {
+            converter.sections.push(wifi_security);
+        }
+
+        if let Some(one_x) = security.one_x.as_ref().filter(|_| use_one_x) {
+            let mut one_x_section = KeyfileSection::new("802-1x");
+
+            match &one_x.eap_config {
+                Some(eap_config) => {
+                    convert_eap_config(eap_config, &mut one_x_section, &mut converter)
+                }
+                None => converter.unconverted.profile(
+                    "MSM/security/OneX",
+                    "the profile does not contain an EAP configuration",
+                ),
+            }
+
+            converter.unconverted.one_x(
+                one_x,
+                "NetworkManager does not have an equivalent 802.1X setting",
+            );
+
+            converter.sections.push(one_x_section);
+        }
+
+        for name in ["ipv4", "ipv6"] {
+            let mut section = KeyfileSection::new(name);
+            section.set("method", "auto");
+            converter.sections.push(section);
+        }
+
+        Ok(converter.into_connection())
+    }
+}
+
+/// Adds the network key of the profile to the `wifi-security` section
+fn convert_shared_key(
+    profile: &WlanProfile,
+    wifi_security: &mut KeyfileSection,
+    converter: &mut Converter,
+) {
+    let wep = profile.msm.security.auth_encryption.encryption == Encryption::Wep;
+    let (key, flags) = if wep {
+        ("wep-key0", "wep-key-flags")
+    } else {
+        ("psk", "psk-flags")
+    };
+
+    match &profile.msm.security.shared_key {
+        Some(shared_key) if shared_key.protected => {
+            wifi_security.set(flags, SECRET_FLAGS_NOT_SAVED);
+            converter.unconverted.profile(
+                "MSM/security/sharedKey/keyMaterial",
+                "the key material is encrypted by Windows and has to be entered again",
+            );
+        }
+        Some(shared_key) => wifi_security.set(key, shared_key.key_material.as_str()),
+        None => wifi_security.set(flags, SECRET_FLAGS_NOT_SAVED),
+    }
+}
+
+/// Adds the EAP settings to the `802-1x` section
+fn convert_eap_config(
+    eap_config: &EapConfig,
+    one_x: &mut KeyfileSection,
+    converter: &mut Converter,
+) {
+    const PATH: &str = "MSM/security/OneX/EAPConfig";
+
+    match eap_config {
+        EapConfig::Peap(peap) => {
+            one_x.set("eap", "peap");
+            match &peap.inner_method {
+                PeapInnerMethod::MsChapV2 {
+                    use_winlogon_credentials,
+                } => {
+                    one_x.set("phase2-auth", "mschapv2");
+                    if *use_winlogon_credentials {
+                        converter.unconverted.profile(
+                            &format!("{PATH}/UseWinLogonCredentials"),
+                            "the Windows logon credentials can not be used",
+                        );
+                    }
+                }
+                PeapInnerMethod::Tls(_) => {
+                    one_x.set("phase2-auth", "tls");
+                    converter.unconverted.profile(
+                        &format!("{PATH}/CredentialsSource"),
+                        "certificates from the Windows certificate store have to be exported and set as phase2-client-cert",
+                    );
+                }
+                PeapInnerMethod::Other(_) => converter.unconverted.profile(
+                    &format!("{PATH}/Eap"),
+                    "the inner EAP method is not supported",
+                ),
+            }
+            convert_server_validation(&peap.server_validation, one_x, converter);
+
+            if peap.enable_quarantine_checks {
+                converter.unconverted.profile(
+                    &format!("{PATH}/EnableQuarantineChecks"),
+                    "NetworkManager does not support network access protection",
+                );
+            }
+        }
+        EapConfig::Tls(tls) => {
+            one_x.set("eap", "tls");
+            convert_server_validation(&tls.server_validation, one_x, converter);
+            converter.unconverted.profile(
+                &format!("{PATH}/CredentialsSource"),
+                match tls.credentials_source {
+                    TlsCredentialsSource::CertificateStore { .. } => {
+                        "certificates from the Windows certificate store have to be exported and set as client-cert"
+                    }
+                    TlsCredentialsSource::SmartCard => {
+                        "smart card certificates have to be set as a PKCS#11 URI in client-cert"
+                    }
+                },
+            );
+        }
+        EapConfig::Ttls(ttls) => {
+            one_x.set("eap", "ttls");
+            match &ttls.phase2 {
+                TtlsPhase2::Pap => one_x.set("phase2-auth", "pap"),
+                TtlsPhase2::Chap => one_x.set("phase2-auth", "chap"),
+                TtlsPhase2::MsChap => one_x.set("phase2-auth", "mschap"),
+                TtlsPhase2::MsChapV2 { .. } => one_x.set("phase2-auth", "mschapv2"),
+                TtlsPhase2::Other(_) => converter.unconverted.profile(
+                    &format!("{PATH}/Phase2Authentication"),
+                    "the inner authentication method is not supported",
+                ),
+            }
+            if let Some(anonymous_identity) = &ttls.anonymous_identity {
+                one_x.set("anonymous-identity", anonymous_identity.as_str());
+            }
+            convert_server_validation(&ttls.server_validation, one_x, converter);
+        }
+        EapConfig::Other(other) => {
+            converter.unconverted.profile(
+                PATH,
+                format!("EAP type {} is not supported", other.method.eap_type),
+            );
+            return;
+        }
+    }
+
+    one_x.set("password-flags", SECRET_FLAGS_NOT_SAVED);
+}
+
+/// Adds the server certificate validation settings to the `802-1x` section
+fn convert_server_validation(
+    server_validation: &ServerValidation,
+    one_x: &mut KeyfileSection,
+    converter: &mut Converter,
+) {
+    const PATH: &str = "MSM/security/OneX/EAPConfig";
+
+    if server_validation.perform_server_validation == Some(false) {
+        return;
+    }
+
+    one_x.set("system-ca-certs", "true");
+
+    if !server_validation.server_names.is_empty() {
+        one_x.set(
+            "domain-suffix-match",
+            server_validation.server_names.as_str(),
+        );
+    }
+
+    if !server_validation.trusted_root_ca.is_empty() {
+        converter.unconverted.profile(
+            &format!("{PATH}/TrustedRootCA"),
+            "certificate thumbprints can not be used. The CA certificates have to be exported and set as ca-cert",
+        );
+    }
+}
+
+/// Formats the SSID as a string, or as a list of bytes if it can not be written as a string
+fn ssid_value(ssid: &Ssid) -> String {
+    match ssid.to_str() {
+        Some(ssid) if !ssid.contains(';') && !ssid.chars().any(char::is_control) => {
+            ssid.to_string()
+        }
+        _ => ssid
+            .as_bytes()
+            .iter()
+            .fold(String::new(), |mut value, byte| {
+                let _ = write!(value, "{byte};");
+                value
+            }),
+    }
+}
+
+/// Creates a UUID for the connection which is the same each time a profile is converted
+fn connection_uuid(name: &str, ssid: &Ssid) -> String {
+    stable_uuid(&[name.as_bytes(), ssid.as_bytes()])
+}
+
+/// Escapes a keyfile value the way GLib key files do
+fn escape_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for (index, c) in value.chars().enumerate() {
+        match c {
+            ' ' if index == 0 => escaped.push_str("\\s"),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{profiles::info::KeyType, testing::example_profile};
+
+    /// Returns the keyfile lines of a section
+    fn section<'a>(keyfile: &'a str, name: &str) -> Vec<&'a str> {
+        keyfile
+            .lines()
+            .skip_while(|line| *line != format!("[{name}]"))
+            .skip(1)
+            .take_while(|line| !line.is_empty())
+            .collect()
+    }
+
+    /// Checks the conversion of the example open profile
+    #[test]
+    fn open_profile() {
+        let connection = example_profile(1)
+            .to_nm_connection(Redaction::Mask)
+            .expect("Failed to convert profile");
+
+        assert_eq!(
+            section(&connection.keyfile, "wifi"),
+            [
+                "mode=infrastructure",
+                "ssid=REDACTED OPEN",
+                "cloned-mac-address=permanent"
+            ]
+        );
+        assert!(section(&connection.keyfile, "wifi-security").is_empty());
+        assert_eq!(
+            connection
+                .unconverted
+                .iter()
+                .map(|diagnostic| diagnostic.path.as_str())
+                .collect::<Vec<_>>(),
+            ["WLANProfile/MacRandomization/randomizationSeed"]
+        );
+    }
+
+    /// Checks the conversion of a WPA2-Personal profile
+    #[test]
+    fn psk_profile() {
+        let mut profile = example_profile(3);
+        profile.ssid_config.ssid.name = None;
+
+        let connection = profile
+            .to_nm_connection(Redaction::Reveal)
+            .expect("Failed to convert profile");
+
+        let connection_section = section(&connection.keyfile, "connection");
+        assert_eq!(connection_section[0], "id=REDACTED WPA2PSK NONBROADCAST");
+        assert_eq!(connection_section[2], "type=wifi");
+        assert!(section(&connection.keyfile, "wifi").contains(&"hidden=true"));
+        assert_eq!(
+            section(&connection.keyfile, "wifi-security"),
+            [
+                "key-mgmt=wpa-psk",
+                "psk=password",
+                "proto=rsn",
+                "pairwise=ccmp"
+            ]
+        );
+
+        let masked = profile
+            .to_nm_connection(Redaction::default())
+            .expect("Failed to convert profile");
+        assert_eq!(
+            section(&masked.keyfile, "wifi-security"),
+            [
+                "key-mgmt=wpa-psk",
+                "psk-flags=2",
+                "proto=rsn",
+                "pairwise=ccmp"
+            ]
+        );
+
+        // The example profile has a name which does not match its hex SSID
+        let mismatched = example_profile(3)
+            .to_nm_connection(Redaction::Reveal)
+            .expect("Failed to convert profile");
+        assert_eq!(mismatched.keyfile, connection.keyfile);
+        assert_eq!(
+            mismatched.unconverted[0].path,
+            "WLANProfile/SSIDConfig/SSID/name"
+        );
+    }
+
+    /// Checks that WEP passphrases are written as raw keys
+    #[test]
+    fn wep_profile() {
+        let mut profile = example_profile(3);
+        profile.ssid_config.ssid.name = None;
+        let security = &mut profile.msm.security;
+        security.auth_encryption.authentication = Authentication::Open;
+        security.auth_encryption.encryption = Encryption::Wep;
+        let shared_key = security.shared_key.as_mut().unwrap();
+        shared_key.key_type = KeyType::PassPhrase;
+        shared_key.key_material = "abcde".to_string();
+
+        let connection = profile
+            .to_nm_connection(Redaction::Reveal)
+            .expect("Failed to convert profile");
+
+        assert_eq!(
+            section(&connection.keyfile, "wifi-security"),
+            ["key-mgmt=none", "wep-key-type=1", "wep-key0=abcde"]
+        );
+    }
+
+    /// Checks the conversion of a WPA3-Personal profile
+    #[test]
+    fn sae_profile() {
+        let profile = crate::profiles::WlanProfileBuilder::new("SAE")
+            .wpa3_sae("passphrase")
+            .build()
+            .expect("Failed to build profile");
+
+        let connection = profile
+            .to_nm_connection(Redaction::Reveal)
+            .expect("Failed to convert profile");
+
+        assert_eq!(
+            section(&connection.keyfile, "wifi-security"),
+            ["key-mgmt=sae", "pmf=3", "psk=passphrase", "pairwise=ccmp"]
+        );
+    }
+
+    /// Checks the conversion of the example PEAP profile
+    #[test]
+    fn peap_profile() {
+        let connection = example_profile(0)
+            .to_nm_connection(Redaction::Mask)
+            .expect("Failed to convert profile");
+
+        assert_eq!(
+            section(&connection.keyfile, "wifi-security"),
+            ["key-mgmt=wpa-eap", "proto=rsn", "pairwise=ccmp"]
+        );
+        assert_eq!(
+            section(&connection.keyfile, "802-1x"),
+            [
+                "eap=peap",
+                "phase2-auth=mschapv2",
+                "system-ca-certs=true",
+                "password-flags=2"
+            ]
+        );
+        assert!(connection
+            .unconverted
+            .iter()
+            .any(|diagnostic| diagnostic.path == "WLANProfile/MSM/security/PMKCacheTTL"));
+        assert!(connection
+            .unconverted
+            .iter()
+            .any(|diagnostic| diagnostic.path == "WLANProfile/MSM/security/OneX/authMode"));
+    }
+
+    /// Checks that SSIDs which can not be written as strings are written as bytes
+    #[test]
+    fn ssid_bytes() {
+        assert_eq!(ssid_value(&Ssid::from("a;b")), "97;59;98;");
+        assert_eq!(ssid_value(&Ssid::new(vec![0xff, 0x01])), "255;1;");
+        assert_eq!(escape_value(" leading"), "\\sleading");
+    }
+}
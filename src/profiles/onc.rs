@@ -0,0 +1,893 @@
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::{
+    builder::WlanProfileBuilder,
+    convert::{export_ssid, stable_uuid, Unconverted},
+    eap::{
+        EapConfig, PeapConfig, PeapInnerMethod, ServerValidation, TlsConfig, TtlsConfig, TtlsPhase2,
+    },
+    info::{
+        Authentication, ConnectionMode, ConnectionType, Encryption, KeyType, SharedKey, WlanProfile,
+    },
+    redact::Redaction,
+    validate::{Diagnostic, Severity},
+};
+
+/// Type of ONC files which are not encrypted
+const UNENCRYPTED_CONFIGURATION: &str = "UnencryptedConfiguration";
+
+/// Type of ONC files which are encrypted with a passphrase
+const ENCRYPTED_CONFIGURATION: &str = "EncryptedConfiguration";
+
+/// A network configuration converted from a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OncExport {
+    /// The ONC file as JSON
+    pub onc: String,
+
+    /// Settings of the profile which have no ONC equivalent and were left out
+    pub unconverted: Vec<Diagnostic>,
+}
+
+/// A wireless profile converted from an ONC network configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OncImport {
+    /// The converted profile
+    pub profile: WlanProfile,
+
+    /// Settings of the network configuration which have no profile equivalent and were left out
+    pub unconverted: Vec<Diagnostic>,
+}
+
+/// Top level object of an ONC file
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OncFile {
+    #[serde(rename = "Type", default = "unencrypted_configuration")]
+    kind: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    network_configurations: Vec<NetworkConfiguration>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    certificates: Vec<Certificate>,
+}
+
+/// A `NetworkConfigurations` entry
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NetworkConfiguration {
+    #[serde(rename = "GUID")]
+    guid: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+
+    #[serde(rename = "Type", default)]
+    kind: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    remove: Option<bool>,
+
+    #[serde(rename = "WiFi", default, skip_serializing_if = "Option::is_none")]
+    wifi: Option<WiFi>,
+
+    /// Settings which are not converted
+    #[serde(flatten)]
+    other: BTreeMap<String, serde_json::Value>,
+}
+
+/// The `WiFi` object of a network configuration
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct WiFi {
+    #[serde(rename = "SSID", default, skip_serializing_if = "Option::is_none")]
+    ssid: Option<String>,
+
+    #[serde(rename = "HexSSID", default, skip_serializing_if = "Option::is_none")]
+    hex_ssid: Option<String>,
+
+    #[serde(
+        rename = "HiddenSSID",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    hidden_ssid: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auto_connect: Option<bool>,
+
+    #[serde(default)]
+    security: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    passphrase: Option<String>,
+
+    #[serde(rename = "EAP", default, skip_serializing_if = "Option::is_none")]
+    eap: Option<Eap>,
+
+    /// Settings which are not converted
+    #[serde(flatten)]
+    other: BTreeMap<String, serde_json::Value>,
+}
+
+/// The `EAP` object of a Wi-Fi network configuration
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Eap {
+    #[serde(default)]
+    outer: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inner: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    anonymous_identity: Option<String>,
+
+    #[serde(
+        rename = "ServerCARefs",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    server_ca_refs: Vec<String>,
+
+    #[serde(
+        rename = "ServerCARef",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    server_ca_ref: Option<String>,
+
+    #[serde(
+        rename = "UseSystemCAs",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    use_system_cas: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    domain_suffix_match: Vec<String>,
+
+    /// Settings which are not converted
+    #[serde(flatten)]
+    other: BTreeMap<String, serde_json::Value>,
+}
+
+/// A `Certificates` entry
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Certificate {
+    #[serde(rename = "GUID")]
+    guid: String,
+
+    #[serde(rename = "Type", default)]
+    kind: String,
+
+    #[serde(rename = "X509", default, skip_serializing_if = "Option::is_none")]
+    x509: Option<String>,
+}
+
+fn unencrypted_configuration() -> String {
+    UNENCRYPTED_CONFIGURATION.to_string()
+}
+
+/// Records the ONC settings which are not converted
+fn unconverted_other(
+    unconverted: &mut Unconverted,
+    path: &str,
+    other: &BTreeMap<String, serde_json::Value>,
+) {
+    for key in other.keys() {
+        unconverted.push(
+            format!("{path}/{key}"),
+            "the setting does not have a wireless profile equivalent",
+        );
+    }
+}
+
+impl WlanProfile {
+    /// Converts the profile to an ONC file with a single Wi-Fi network configuration. Settings
+    /// which have no ONC equivalent are reported in the result
+    pub fn to_onc(&self, redaction: Redaction) -> Result<OncExport, WinWifiError> {
+        let mut unconverted = Unconverted::default();
+
+        let ssid = export_ssid(self, &mut unconverted)?;
+
+        let profile = self.redacted(redaction);
+        let security = &profile.msm.security;
+        let auth_encryption = &security.auth_encryption;
+        let authentication = auth_encryption.authentication;
+        let use_one_x = auth_encryption.use_one_x == Some(true);
+        let wep = auth_encryption.encryption == Encryption::Wep;
+
+        if profile.connection_type == ConnectionType::Ibss {
+            unconverted.profile(
+                "connectionType",
+                "ONC only supports infrastructure networks",
+            );
+        }
+
+        if profile.auto_switch.is_some() {
+            unconverted.profile(
+                "autoSwitch",
+                "ChromeOS does not switch to more preferred networks based on the profile",
+            );
+        }
+
+        if profile.mac_randomization.is_some() {
+            unconverted.profile(
+                "MacRandomization",
+                "ONC does not have a per network MAC address randomization setting",
+            );
+        }
+
+        let mut wifi = WiFi {
+            ssid: ssid.to_str().map(str::to_string),
+            hex_ssid: Some(ssid.to_hex()),
+            hidden_ssid: Some(profile.ssid_config.non_broadcast == Some(true)),
+            auto_connect: Some(profile.connection_mode != Some(ConnectionMode::Manual)),
+            ..Default::default()
+        };
+
+        wifi.security = match authentication {
+            Authentication::Open | Authentication::Shared if wep && use_one_x => "WEP-8021X",
+            Authentication::Open | Authentication::Shared if wep => "WEP-PSK",
+            Authentication::Open | Authentication::Shared => "None",
+            Authentication::Owe => {
+                unconverted.profile(
+                    "MSM/security/authEncryption/authentication",
+                    "ONC does not support OWE. The network is added as an open network",
+                );
+                "None"
+            }
+            Authentication::WpaPsk | Authentication::Wpa2Psk | Authentication::Wpa3Sae => "WPA-PSK",
+            Authentication::Wpa
+            | Authentication::Wpa2
+            | Authentication::Wpa3
+            | Authentication::Wpa3Ent192
+            | Authentication::Wpa3Ent => "WPA-EAP",
+        }
+        .to_string();
+
+        if matches!(wifi.security.as_str(), "WEP-PSK" | "WPA-PSK") {
+            match &security.shared_key {
+                Some(shared_key) if shared_key.protected => unconverted.profile(
+                    "MSM/security/sharedKey/keyMaterial",
+                    "the key material is encrypted by Windows and has to be entered again",
+                ),
+                Some(shared_key) => wifi.passphrase = Some(shared_key.key_material.clone()),
+                None => (),
+            }
+        }
+
+        if auth_encryption.fips_mode == Some(true) {
+            unconverted.profile(
+                "MSM/security/authEncryption/FIPSMode",
+                "ONC does not have a FIPS mode setting",
+            );
+        }
+
+        unconverted.pmk_cache(security, "PMK caching is managed by ChromeOS");
+
+        if let Some(one_x) = security.one_x.as_ref().filter(|_| use_one_x) {
+            match &one_x.eap_config {
+                Some(eap_config) => wifi.eap = export_eap_config(eap_config, &mut unconverted),
+                None => unconverted.profile(
+                    "MSM/security/OneX",
+                    "the profile does not contain an EAP configuration",
+                ),
+            }
+
+            unconverted.one_x(one_x, "ONC does not have an equivalent 802.1X setting");
+        }
+
+        let onc = OncFile {
+            kind: unencrypted_configuration(),
+            network_configurations: vec![NetworkConfiguration {
+                guid: stable_uuid(&[profile.name.as_bytes(), ssid.as_bytes()]),
+                name: Some(profile.name.clone()),
+                kind: "WiFi".to_string(),
+                wifi: Some(wifi),
+                ..Default::default()
+            }],
+            certificates: Vec::new(),
+        };
+
+        Ok(OncExport {
+            onc: serde_json::to_string_pretty(&onc).map_err(WinWifiError::OncJsonError)?,
+            unconverted: unconverted.diagnostics,
+        })
+    }
+
+    /// Converts an ONC file with a single Wi-Fi network configuration to a wireless profile.
+    /// Settings which have no profile equivalent are reported in the result
+    pub fn from_onc(onc: &str) -> Result<OncImport, WinWifiError> {
+        let mut imports = import_networks(onc)?;
+
+        if imports.len() != 1 {
+            return Err(WinWifiError::InvalidProfile(format!(
+                "expected a single Wi-Fi network configuration but found {}",
+                imports.len()
+            )));
+        }
+
+        Ok(imports.remove(0))
+    }
+}
+
+/// Converts every Wi-Fi network configuration of an ONC file to a wireless profile. Network
+/// configurations of other types and the ones which remove a network are ignored
+pub fn import_networks(onc: &str) -> Result<Vec<OncImport>, WinWifiError> {
+    let onc = serde_json::from_str::<OncFile>(onc).map_err(WinWifiError::OncJsonError)?;
+
+    match onc.kind.as_str() {
+        UNENCRYPTED_CONFIGURATION => (),
+        ENCRYPTED_CONFIGURATION => {
+            return Err(invalid(
+                "Type",
+                "encrypted ONC files have to be decrypted first",
+            ))
+        }
+        kind => return Err(invalid("Type", &format!("'{kind}' is not supported"))),
+    }
+
+    onc.network_configurations
+        .into_iter()
+        .filter(|network| network.kind == "WiFi" && network.remove != Some(true))
+        .map(|network| import_network(network, &onc.certificates))
+        .collect()
+}
+
+/// Converts a Wi-Fi network configuration to a wireless profile
+fn import_network(
+    network: NetworkConfiguration,
+    certificates: &[Certificate],
+) -> Result<OncImport, WinWifiError> {
+    const PATH: &str = "NetworkConfiguration/WiFi";
+
+    let mut unconverted = Unconverted::default();
+    unconverted_other(&mut unconverted, "NetworkConfiguration", &network.other);
+
+    let Some(wifi) = network.wifi else {
+        return Err(invalid(
+            "WiFi",
+            "the network configuration does not have Wi-Fi settings",
+        ));
+    };
+    unconverted_other(&mut unconverted, PATH, &wifi.other);
+
+    let ssid = match (&wifi.hex_ssid, &wifi.ssid) {
+        (Some(hex), _) => Ssid::from_hex(hex)?,
+        (None, Some(ssid)) => Ssid::from(ssid.as_str()),
+        (None, None) => return Err(invalid("WiFi/SSID", "the network does not have an SSID")),
+    };
+
+    let name = network.name.unwrap_or_else(|| ssid.to_string());
+
+    let mut builder = WlanProfileBuilder::new(name)
+        .ssid(ssid)
+        .hidden(wifi.hidden_ssid == Some(true))
+        .auto_connect(wifi.auto_connect != Some(false));
+
+    let (authentication, encryption) = match wifi.security.as_str() {
+        "None" => (Authentication::Open, Encryption::None),
+        "WEP-PSK" | "WEP-8021X" => (Authentication::Open, Encryption::Wep),
+        "WPA-PSK" => (Authentication::Wpa2Psk, Encryption::Aes),
+        "WPA-EAP" => (Authentication::Wpa2, Encryption::Aes),
+        security => {
+            return Err(invalid(
+                "WiFi/Security",
+                &format!("'{security}' is not supported"),
+            ))
+        }
+    };
+
+    let enterprise = matches!(wifi.security.as_str(), "WEP-8021X" | "WPA-EAP");
+    if enterprise {
+        let Some(eap) = wifi.eap else {
+            return Err(invalid(
+                "WiFi/EAP",
+                "the network does not have EAP settings",
+            ));
+        };
+        builder = builder.wpa2_enterprise().eap_config(import_eap_config(
+            eap,
+            certificates,
+            &mut unconverted,
+        )?);
+    } else if wifi.eap.is_some() {
+        unconverted.push(
+            format!("{PATH}/EAP"),
+            "EAP settings can only be used with WPA-EAP or WEP-8021X security",
+        );
+    }
+
+    let mut profile = builder.build()?;
+    let security = &mut profile.msm.security;
+
+    if authentication != Authentication::Wpa2 {
+        security.pmk_cache_mode = None;
+        security.pmk_cache_ttl = None;
+        security.pmk_cache_size = None;
+        security.pre_auth_mode = None;
+    }
+
+    security.auth_encryption.authentication = authentication;
+    security.auth_encryption.encryption = encryption;
+
+    match wifi.passphrase {
+        Some(passphrase) if !enterprise && encryption != Encryption::None => {
+            let hex_key = matches!(passphrase.len(), 10 | 26 | 64)
+                && crate::hex::decode(&passphrase).is_some();
+
+            security.shared_key = Some(SharedKey {
+                key_type: if hex_key || encryption == Encryption::Wep {
+                    KeyType::NetworkKey
+                } else {
+                    KeyType::PassPhrase
+                },
+                protected: false,
+                key_material: passphrase,
+            });
+            if encryption == Encryption::Wep {
+                security.key_index = Some(0);
+            }
+        }
+        Some(_) => unconverted.push(
+            format!("{PATH}/Passphrase"),
+            "a passphrase can only be used with WEP-PSK or WPA-PSK security",
+        ),
+        None => (),
+    }
+
+    if let Some(error) = profile
+        .validate()
+        .into_iter()
+        .find(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        return Err(WinWifiError::InvalidProfile(error.to_string()));
+    }
+
+    Ok(OncImport {
+        profile,
+        unconverted: unconverted.diagnostics,
+    })
+}
+
+/// Creates the `EAP` object of an EAP configuration. Returns `None` if the EAP method is not
+/// supported
+fn export_eap_config(eap_config: &EapConfig, unconverted: &mut Unconverted) -> Option<Eap> {
+    const PATH: &str = "MSM/security/OneX/EAPConfig";
+
+    let mut eap = Eap::default();
+
+    match eap_config {
+        EapConfig::Peap(peap) => {
+            eap.outer = "PEAP".to_string();
+            match &peap.inner_method {
+                PeapInnerMethod::MsChapV2 {
+                    use_winlogon_credentials,
+                } => {
+                    eap.inner = Some("MSCHAPv2".to_string());
+                    if *use_winlogon_credentials {
+                        unconverted.profile(
+                            &format!("{PATH}/UseWinLogonCredentials"),
+                            "the Windows logon credentials can not be used",
+                        );
+                    }
+                }
+                PeapInnerMethod::Tls(_) => unconverted.profile(
+                    &format!("{PATH}/Eap"),
+                    "ONC does not support EAP-TLS inside PEAP",
+                ),
+                PeapInnerMethod::Other(_) => unconverted.profile(
+                    &format!("{PATH}/Eap"),
+                    "the inner EAP method is not supported",
+                ),
+            }
+
+            if peap.enable_quarantine_checks {
+                unconverted.profile(
+                    &format!("{PATH}/EnableQuarantineChecks"),
+                    "ChromeOS does not support network access protection",
+                );
+            }
+
+            export_server_validation(&peap.server_validation, &mut eap, unconverted);
+        }
+        EapConfig::Tls(tls) => {
+            eap.outer = "EAP-TLS".to_string();
+            unconverted.profile(
+                &format!("{PATH}/CredentialsSource"),
+                "the client certificate has to be provisioned separately and selected with ClientCertPattern or ClientCertRef",
+            );
+            export_server_validation(&tls.server_validation, &mut eap, unconverted);
+        }
+        EapConfig::Ttls(ttls) => {
+            eap.outer = "EAP-TTLS".to_string();
+            eap.inner = match &ttls.phase2 {
+                TtlsPhase2::Pap => Some("PAP".to_string()),
+                TtlsPhase2::Chap => Some("CHAP".to_string()),
+                TtlsPhase2::MsChap => Some("MSCHAP".to_string()),
+                TtlsPhase2::MsChapV2 {
+                    use_winlogon_credentials,
+                } => {
+                    if *use_winlogon_credentials {
+                        unconverted.profile(
+                            &format!("{PATH}/Phase2Authentication"),
+                            "the Windows logon credentials can not be used",
+                        );
+                    }
+                    Some("MSCHAPv2".to_string())
+                }
+                TtlsPhase2::Other(_) => {
+                    unconverted.profile(
+                        &format!("{PATH}/Phase2Authentication"),
+                        "the inner authentication method is not supported",
+                    );
+                    None
+                }
+            };
+            eap.anonymous_identity = ttls.anonymous_identity.clone();
+            export_server_validation(&ttls.server_validation, &mut eap, unconverted);
+        }
+        EapConfig::Other(other) => {
+            unconverted.profile(
+                PATH,
+                format!("EAP type {} is not supported", other.method.eap_type),
+            );
+            return None;
+        }
+    }
+
+    Some(eap)
+}
+
+/// Adds the server certificate validation settings to the `EAP` object
+fn export_server_validation(
+    server_validation: &ServerValidation,
+    eap: &mut Eap,
+    unconverted: &mut Unconverted,
+) {
+    const PATH: &str = "MSM/security/OneX/EAPConfig";
+
+    if server_validation.perform_server_validation == Some(false) {
+        eap.use_system_cas = Some(false);
+        return;
+    }
+
+    eap.use_system_cas = Some(true);
+    eap.domain_suffix_match = server_validation
+        .server_names
+        .split(';')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if !server_validation.trusted_root_ca.is_empty() {
+        unconverted.profile(
+            &format!("{PATH}/TrustedRootCA"),
+            "certificate thumbprints can not be used. The CA certificates have to be added to Certificates and referenced by ServerCARefs",
+        );
+    }
+}
+
+/// Converts the `EAP` object of a network configuration
+fn import_eap_config(
+    eap: Eap,
+    certificates: &[Certificate],
+    unconverted: &mut Unconverted,
+) -> Result<EapConfig, WinWifiError> {
+    const PATH: &str = "NetworkConfiguration/WiFi/EAP";
+
+    unconverted_other(unconverted, PATH, &eap.other);
+
+    let inner = eap.inner.as_deref().filter(|inner| *inner != "Automatic");
+
+    let mut eap_config = match eap.outer.as_str() {
+        "PEAP" => match inner {
+            Some("MSCHAPv2") | None => EapConfig::Peap(PeapConfig::default()),
+            Some(inner) => {
+                return Err(invalid(
+                    "WiFi/EAP/Inner",
+                    &format!("'{inner}' is not supported with PEAP"),
+                ))
+            }
+        },
+        "EAP-TLS" => EapConfig::Tls(TlsConfig::default()),
+        "EAP-TTLS" => EapConfig::Ttls(TtlsConfig {
+            phase2: match inner {
+                Some("PAP") => TtlsPhase2::Pap,
+                Some("CHAP") => TtlsPhase2::Chap,
+                Some("MSCHAP") => TtlsPhase2::MsChap,
+                Some("MSCHAPv2") | None => TtlsPhase2::MsChapV2 {
+                    use_winlogon_credentials: false,
+                },
+                Some(inner) => {
+                    return Err(invalid(
+                        "WiFi/EAP/Inner",
+                        &format!("'{inner}' is not supported with EAP-TTLS"),
+                    ))
+                }
+            },
+            identity_privacy: eap.anonymous_identity.as_ref().map(|_| true),
+            anonymous_identity: eap.anonymous_identity.clone(),
+            ..Default::default()
+        }),
+        outer => {
+            return Err(invalid(
+                "WiFi/EAP/Outer",
+                &format!("'{outer}' is not supported"),
+            ))
+        }
+    };
+
+    if eap.anonymous_identity.is_some() && !matches!(eap_config, EapConfig::Ttls(_)) {
+        unconverted.push(
+            format!("{PATH}/AnonymousIdentity"),
+            "Windows only supports an anonymous identity with EAP-TTLS",
+        );
+    }
+
+    let Some(server_validation) = eap_config.server_validation_mut() else {
+        return Ok(eap_config);
+    };
+
+    server_validation.server_names = eap.domain_suffix_match.join(";");
+
+    let server_ca_refs = eap
+        .server_ca_refs
+        .iter()
+        .chain(eap.server_ca_ref.iter())
+        .collect::<Vec<_>>();
+
+    for server_ca_ref in &server_ca_refs {
+        let thumbprint = certificates
+            .iter()
+            .find(|certificate| certificate.guid == **server_ca_ref)
+            .and_then(|certificate| certificate.x509.as_deref())
+            .and_then(certificate_thumbprint);
+
+        match thumbprint {
+            Some(thumbprint) => server_validation.trusted_root_ca.push(thumbprint),
+            None => unconverted.push(
+                format!("{PATH}/ServerCARefs"),
+                format!("certificate '{server_ca_ref}' is not included in the ONC file"),
+            ),
+        }
+    }
+
+    if eap.use_system_cas == Some(false) && server_ca_refs.is_empty() {
+        server_validation.perform_server_validation = Some(false);
+    }
+
+    Ok(eap_config)
+}
+
+/// Computes the SHA-1 thumbprint of a base64 encoded certificate the way Windows writes it to
+/// the `TrustedRootCA` element
+fn certificate_thumbprint(x509: &str) -> Option<String> {
+    let base64 = x509
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .flat_map(|line| line.split_whitespace())
+        .collect::<String>();
+
+    let der = STANDARD.decode(base64).ok()?;
+
+    Some(crate::hex::encode_separated(&Sha1::digest(der), " "))
+}
+
+fn invalid(key: &str, reason: &str) -> WinWifiError {
+    WinWifiError::InvalidProfile(format!("{key}: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_profile;
+
+    /// Returns the `WiFi` object of the first network configuration of an ONC file
+    fn wifi(onc: &str) -> serde_json::Value {
+        let onc = serde_json::from_str::<serde_json::Value>(onc).expect("Failed to parse ONC");
+        onc["NetworkConfigurations"][0]["WiFi"].clone()
+    }
+
+    /// Checks the conversion of a hidden WPA2-Personal profile
+    #[test]
+    fn export_psk_profile() {
+        let mut profile = example_profile(3);
+        profile.ssid_config.ssid.name = None;
+
+        let export = profile
+            .to_onc(Redaction::Reveal)
+            .expect("Failed to convert profile");
+
+        assert_eq!(
+            wifi(&export.onc),
+            serde_json::json!({
+                "SSID": "REDACTED WPA2PSK NONBROADCAST",
+                "HexSSID": "5245444143544544205750413250534B204E4F4E42524F414443415354",
+                "HiddenSSID": true,
+                "AutoConnect": true,
+                "Security": "WPA-PSK",
+                "Passphrase": "password"
+            })
+        );
+
+        let masked = profile
+            .to_onc(Redaction::Mask)
+            .expect("Failed to convert profile");
+        assert_eq!(wifi(&masked.onc)["Passphrase"], "********");
+    }
+
+    /// Checks the conversion of the example PEAP profile
+    #[test]
+    fn export_peap_profile() {
+        let export = example_profile(0)
+            .to_onc(Redaction::Mask)
+            .expect("Failed to convert profile");
+
+        let wifi = wifi(&export.onc);
+        assert_eq!(wifi["Security"], "WPA-EAP");
+        assert_eq!(
+            wifi["EAP"],
+            serde_json::json!({
+                "Outer": "PEAP",
+                "Inner": "MSCHAPv2",
+                "UseSystemCAs": true
+            })
+        );
+        assert!(export
+            .unconverted
+            .iter()
+            .any(|diagnostic| diagnostic.path
+                == "WLANProfile/MSM/security/OneX/EAPConfig/TrustedRootCA"));
+    }
+
+    /// Checks the conversion of an ONC file with several network configurations
+    #[test]
+    fn import_onc_file() {
+        const ONC: &str = r#"{
+            "Type": "UnencryptedConfiguration",
+            "NetworkConfigurations": [
+                {
+                    "GUID": "{home}",
+                    "Name": "Home",
+                    "Type": "WiFi",
+                    "WiFi": {
+                        "SSID": "Home",
+                        "Security": "WPA-PSK",
+                        "Passphrase": "passphrase",
+                        "AutoConnect": false
+                    }
+                },
+                {
+                    "GUID": "{ethernet}",
+                    "Type": "Ethernet",
+                    "Ethernet": { "Authentication": "None" }
+                },
+                {
+                    "GUID": "{corp}",
+                    "Name": "Corp",
+                    "Type": "WiFi",
+                    "ProxySettings": { "Type": "Direct" },
+                    "WiFi": {
+                        "HexSSID": "436F7270",
+                        "HiddenSSID": true,
+                        "Security": "WPA-EAP",
+                        "EAP": {
+                            "Outer": "EAP-TTLS",
+                            "Inner": "PAP",
+                            "AnonymousIdentity": "anonymous",
+                            "Identity": "user",
+                            "DomainSuffixMatch": ["radius.example.com"],
+                            "ServerCARefs": ["{ca}", "{missing}"]
+                        }
+                    }
+                }
+            ],
+            "Certificates": [
+                { "GUID": "{ca}", "Type": "Authority", "X509": "aGVsbG8=" }
+            ]
+        }"#;
+
+        let imports = import_networks(ONC).expect("Failed to import ONC file");
+        assert_eq!(imports.len(), 2);
+
+        let home = &imports[0].profile;
+        assert_eq!(home.name, "Home");
+        assert_eq!(home.connection_mode, Some(ConnectionMode::Manual));
+        assert_eq!(
+            home.msm.security.auth_encryption.authentication,
+            Authentication::Wpa2Psk
+        );
+        assert_eq!(
+            home.msm.security.shared_key.as_ref().unwrap().key_material,
+            "passphrase"
+        );
+        assert!(imports[0].unconverted.is_empty());
+
+        let corp = &imports[1].profile;
+        assert_eq!(corp.ssid_config.ssid.ssid().unwrap(), Ssid::from("Corp"));
+        assert_eq!(corp.ssid_config.non_broadcast, Some(true));
+
+        let Some(EapConfig::Ttls(ttls)) = corp
+            .msm
+            .security
+            .one_x
+            .as_ref()
+            .and_then(|one_x| one_x.eap_config.clone())
+        else {
+            panic!("Expected an EAP-TTLS configuration");
+        };
+        assert_eq!(ttls.phase2, TtlsPhase2::Pap);
+        assert_eq!(ttls.anonymous_identity.as_deref(), Some("anonymous"));
+        assert_eq!(ttls.server_validation.server_names, "radius.example.com");
+        // SHA-1 of "hello"
+        assert_eq!(
+            ttls.server_validation.trusted_root_ca,
+            ["aa f4 c6 1d dc c5 e8 a2 da be de 0f 3b 48 2c d9 ae a9 43 4d"]
+        );
+
+        assert_eq!(
+            imports[1]
+                .unconverted
+                .iter()
+                .map(|diagnostic| diagnostic.path.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "NetworkConfiguration/ProxySettings",
+                "NetworkConfiguration/WiFi/EAP/Identity",
+                "NetworkConfiguration/WiFi/EAP/ServerCARefs"
+            ]
+        );
+    }
+
+    /// Checks that an exported profile is imported again
+    #[test]
+    fn onc_round_trip() {
+        let profile = example_profile(0);
+
+        let export = profile
+            .to_onc(Redaction::Mask)
+            .expect("Failed to convert profile");
+        let import = WlanProfile::from_onc(&export.onc).expect("Failed to import ONC");
+
+        assert_eq!(import.profile.name, profile.name);
+        assert_eq!(
+            import.profile.msm.security.auth_encryption,
+            profile.msm.security.auth_encryption
+        );
+        assert!(matches!(
+            import
+                .profile
+                .msm
+                .security
+                .one_x
+                .and_then(|one_x| one_x.eap_config),
+            Some(EapConfig::Peap(_))
+        ));
+    }
+
+    /// Checks that unsupported ONC files are rejected
+    #[test]
+    fn invalid_onc() {
+        assert!(matches!(
+            WlanProfile::from_onc("{"),
+            Err(WinWifiError::OncJsonError(_))
+        ));
+        assert!(WlanProfile::from_onc(r#"{"Type": "EncryptedConfiguration"}"#).is_err());
+        assert!(WlanProfile::from_onc(
+            r#"{"NetworkConfigurations": [{"GUID": "a", "Type": "WiFi", "WiFi": {"SSID": "a", "Security": "WPA-PSK"}}]}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use sha1::Sha1;
+
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::info::{Authentication, Encryption, KeyType, WlanProfile};
+
+/// Length of a WPA pre-shared key in bytes
+pub const PSK_LENGTH: usize = 32;
+
+/// Number of PBKDF2 iterations used to derive a pre-shared key from a passphrase
+const PBKDF2_ITERATIONS: u32 = 4096;
+
+/// Derives the 256-bit WPA pre-shared key from a passphrase and the SSID of the network using
+/// PBKDF2-HMAC-SHA1 with 4096 iterations
+pub fn derive_psk(passphrase: &str, ssid: &Ssid) -> Result<[u8; PSK_LENGTH], WinWifiError> {
+    check_passphrase(passphrase)?;
+
+    let mut psk = [0u8; PSK_LENGTH];
+    pbkdf2::pbkdf2_hmac::<Sha1>(
+        passphrase.as_bytes(),
+        ssid.as_bytes(),
+        PBKDF2_ITERATIONS,
+        &mut psk,
+    );
+    Ok(psk)
+}
+
+/// Formats a pre-shared key as the 64 hex digits used in a `networkKey` shared key
+pub fn psk_to_hex(psk: &[u8; PSK_LENGTH]) -> String {
+    crate::hex::encode(psk)
+}
+
+/// Parses a network key of 64 hex digits
+pub(super) fn parse_network_key(key_material: &str) -> Result<[u8; PSK_LENGTH], WinWifiError> {
+    crate::hex::decode(key_material)
+        .and_then(|psk| psk.try_into().ok())
+        .ok_or_else(|| {
+            WinWifiError::InvalidProfile("the network key must be 64 hex digits".to_string())
+        })
+}
+
+/// Checks that a passphrase is between 8 and 63 printable ASCII characters long
+pub(super) fn check_passphrase(passphrase: &str) -> Result<(), WinWifiError> {
+    if !(8..=63).contains(&passphrase.len())
+        || !passphrase.bytes().all(|byte| (0x20..=0x7e).contains(&byte))
+    {
+        return Err(WinWifiError::InvalidProfile(
+            "the passphrase must be between 8 and 63 printable ASCII characters long".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+impl WlanProfile {
+    /// Returns the pre-shared key of a WPA-Personal profile whether the profile stores it as a
+    /// passphrase or as 64 hex digits. Profiles which store the same key in different forms
+    /// return the same value. WPA3-Personal profiles only have a pre-shared key in transition
+    /// mode, since SAE does not derive its keys from a PSK. Returns `None` for other profiles
+    /// and for key material which is protected by Windows
+    pub fn psk(&self) -> Result<Option<[u8; PSK_LENGTH]>, WinWifiError> {
+        let security = &self.msm.security;
+        let auth_encryption = &security.auth_encryption;
+
+        let personal = match auth_encryption.authentication {
+            Authentication::WpaPsk | Authentication::Wpa2Psk => true,
+            Authentication::Wpa3Sae => auth_encryption.transition_mode == Some(true),
+            _ => false,
+        } && auth_encryption.encryption != Encryption::Wep;
+
+        let Some(shared_key) = security
+            .shared_key
+            .as_ref()
+            .filter(|shared_key| personal && !shared_key.protected)
+        else {
+            return Ok(None);
+        };
+
+        match shared_key.key_type {
+            KeyType::PassPhrase => {
+                derive_psk(&shared_key.key_material, &self.ssid_config.ssid.ssid()?).map(Some)
+            }
+            KeyType::NetworkKey => parse_network_key(&shared_key.key_material).map(Some),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::WlanProfileBuilder;
+
+    /// Checks the test vectors of IEEE 802.11i
+    #[test]
+    fn derive_test_vectors() {
+        assert_eq!(
+            psk_to_hex(&derive_psk("password", &Ssid::from("IEEE")).unwrap()),
+            "f42c6fc52df0ebef9ebb4b90b38a5f902e83fe1b135a70e23aed762e9710a12e"
+        );
+        assert_eq!(
+            psk_to_hex(&derive_psk("ThisIsAPassword", &Ssid::from("ThisIsASSID")).unwrap()),
+            "0dc0d6eb90555ed6419756b9a15ec3e3209b63df707dd508d14581f8982721af"
+        );
+
+        assert!(derive_psk("short", &Ssid::from("IEEE")).is_err());
+    }
+
+    /// Checks that profiles storing the same key in different forms have the same PSK
+    #[test]
+    fn compare_psk() {
+        let passphrase = WlanProfileBuilder::new("IEEE")
+            .wpa2_psk("password")
+            .build()
+            .expect("Failed to build profile");
+        let network_key = WlanProfileBuilder::new("IEEE")
+            .wpa2_psk("password")
+            .derive_psk(true)
+            .build()
+            .expect("Failed to build profile");
+
+        let shared_key = network_key.msm.security.shared_key.as_ref().unwrap();
+        assert_eq!(shared_key.key_type, KeyType::NetworkKey);
+        assert_eq!(
+            shared_key.key_material,
+            "f42c6fc52df0ebef9ebb4b90b38a5f902e83fe1b135a70e23aed762e9710a12e"
+        );
+
+        assert!(passphrase.psk().unwrap().is_some());
+        assert_eq!(passphrase.psk().unwrap(), network_key.psk().unwrap());
+
+        let open = WlanProfileBuilder::new("IEEE")
+            .build()
+            .expect("Failed to build profile");
+        assert_eq!(open.psk().unwrap(), None);
+    }
+
+    /// Checks that WPA3-Personal profiles only have a PSK in transition mode
+    #[test]
+    fn sae_psk() {
+        let sae = WlanProfileBuilder::new("IEEE")
+            .wpa3_sae("password")
+            .build()
+            .expect("Failed to build profile");
+        assert_eq!(sae.psk().unwrap(), None);
+
+        let transition = WlanProfileBuilder::new("IEEE")
+            .wpa2_wpa3_transition("password")
+            .build()
+            .expect("Failed to build profile");
+        assert_eq!(
+            transition
+                .psk()
+                .unwrap()
+                .as_ref()
+                .map(psk_to_hex)
+                .as_deref(),
+            Some("f42c6fc52df0ebef9ebb4b90b38a5f902e83fe1b135a70e23aed762e9710a12e")
+        );
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::{
+    builder::WlanProfileBuilder,
+    info::{Authentication, Encryption, KeyType, SharedKey, WlanProfile},
+    redact::Redaction,
+    validate::Severity,
+};
+
+/// Scheme of the Wi-Fi network URI
+const WIFI_URI_SCHEME: &str = "WIFI:";
+
+/// Characters which are escaped in Wi-Fi network URI values
+const ESCAPED_CHARACTERS: &[char] = &['\\', ';', ',', ':', '"'];
+
+/// Size of a QR code module in pixels when rendered as an image
+#[cfg(feature = "qrcode")]
+const MODULE_PIXELS: usize = 8;
+
+/// Width of the quiet zone around a QR code in modules
+#[cfg(feature = "qrcode")]
+const QUIET_ZONE_MODULES: usize = 4;
+
+impl WlanProfile {
+    /// Creates the `WIFI:` URI used by phone cameras to join a network, like
+    /// `WIFI:T:WPA;S:Guest;P:passphrase;;`. Only open and personal networks are supported.
+    /// The `P` field is left out if the passphrase is removed
+    pub fn to_wifi_uri(&self, redaction: Redaction) -> Result<String, WinWifiError> {
+        let ssid = self.ssid_config.ssid.ssid()?;
+        let ssid = ssid.to_str().ok_or_else(|| {
+            WinWifiError::InvalidSsid(
+                "an SSID which is not valid UTF-8 can not be used in a Wi-Fi URI".to_string(),
+            )
+        })?;
+
+        let profile = self.redacted(redaction);
+        let security = &profile.msm.security;
+        let auth_encryption = &security.auth_encryption;
+
+        let network_type = match (auth_encryption.authentication, auth_encryption.encryption) {
+            (_, _) if auth_encryption.use_one_x == Some(true) => None,
+            (Authentication::Open, Encryption::None) => Some("nopass"),
+            (Authentication::Open | Authentication::Shared, Encryption::Wep) => Some("WEP"),
+            (Authentication::WpaPsk | Authentication::Wpa2Psk, _) => Some("WPA"),
+            (Authentication::Wpa3Sae, _) if auth_encryption.transition_mode == Some(true) => {
+                Some("WPA")
+            }
+            (Authentication::Wpa3Sae, _) => Some("SAE"),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            WinWifiError::InvalidProfile(format!(
+                "{:?} authentication can not be used in a Wi-Fi URI",
+                auth_encryption.authentication
+            ))
+        })?;
+
+        let mut uri = format!("{WIFI_URI_SCHEME}T:{network_type};S:{};", escape(ssid));
+
+        if network_type != "nopass" {
+            match &security.shared_key {
+                Some(shared_key) if shared_key.protected => {
+                    return Err(WinWifiError::InvalidProfile(
+                        "the key material is encrypted by Windows".to_string(),
+                    ))
+                }
+                Some(shared_key) => {
+                    uri.push_str(&format!("P:{};", escape(&shared_key.key_material)));
+                }
+                None => (),
+            }
+        }
+
+        if profile.ssid_config.non_broadcast == Some(true) {
+            uri.push_str("H:true;");
+        }
+
+        uri.push(';');
+        Ok(uri)
+    }
+
+    /// Parses a `WIFI:` URI into a profile named after the SSID of the network
+    pub fn from_wifi_uri(uri: &str) -> Result<WlanProfile, WinWifiError> {
+        let invalid = |reason: &str| WinWifiError::InvalidProfile(format!("Wi-Fi URI: {reason}"));
+
+        let fields = uri
+            .get(..WIFI_URI_SCHEME.len())
+            .filter(|scheme| scheme.eq_ignore_ascii_case(WIFI_URI_SCHEME))
+            .map(|_| &uri[WIFI_URI_SCHEME.len()..])
+            .ok_or_else(|| invalid("the URI does not start with 'WIFI:'"))?;
+
+        let mut network_type = None;
+        let mut ssid = None;
+        let mut password = None;
+        let mut hidden = false;
+
+        for field in split_unescaped(fields, ';') {
+            if field.is_empty() {
+                continue;
+            }
+
+            let (key, value) = field
+                .split_once(':')
+                .ok_or_else(|| invalid(&format!("'{field}' is not a 'key:value' field")))?;
+            let value = unescape(value);
+
+            match key {
+                "T" => network_type = Some(value),
+                "S" => ssid = Some(value),
+                "P" => password = Some(value),
+                "H" => hidden = value.eq_ignore_ascii_case("true"),
+                _ => (),
+            }
+        }
+
+        let ssid = ssid.ok_or_else(|| invalid("the URI does not contain an SSID"))?;
+        let password = password.filter(|password| !password.is_empty());
+        let builder = WlanProfileBuilder::new(ssid.clone())
+            .ssid(Ssid::from(ssid))
+            .hidden(hidden);
+
+        let network_type = network_type.unwrap_or_else(|| "nopass".to_string());
+        let profile = match (network_type.to_ascii_uppercase().as_str(), password) {
+            ("NOPASS", _) => builder.open().build()?,
+            ("WPA" | "WPA2", Some(password)) => builder.wpa2_psk(password).build()?,
+            ("SAE" | "WPA3", Some(password)) => builder.wpa3_sae(password).build()?,
+            ("WEP", Some(password)) => {
+                let mut profile = builder.open().build()?;
+                let security = &mut profile.msm.security;
+                security.auth_encryption.encryption = Encryption::Wep;
+                security.key_index = Some(0);
+                security.shared_key = Some(SharedKey {
+                    key_type: KeyType::NetworkKey,
+                    protected: false,
+                    key_material: password,
+                });
+                profile
+            }
+            ("WPA" | "WPA2" | "SAE" | "WPA3" | "WEP", None) => {
+                return Err(invalid("the URI does not contain a password"))
+            }
+            (network_type, _) => {
+                return Err(invalid(&format!(
+                    "network type '{network_type}' is not supported"
+                )))
+            }
+        };
+
+        if let Some(error) = profile
+            .validate()
+            .into_iter()
+            .find(|diagnostic| diagnostic.severity == Severity::Error)
+        {
+            return Err(WinWifiError::InvalidProfile(error.to_string()));
+        }
+
+        Ok(profile)
+    }
+}
+
+/// A QR code containing the `WIFI:` URI of a network
+#[cfg(feature = "qrcode")]
+pub struct WifiQrCode {
+    /// The encoded URI
+    uri: String,
+
+    /// The encoded QR code
+    code: qrcode::QrCode,
+}
+
+#[cfg(feature = "qrcode")]
+impl WifiQrCode {
+    /// Creates the QR code for the `WIFI:` URI of a profile
+    pub fn new(profile: &WlanProfile, redaction: Redaction) -> Result<WifiQrCode, WinWifiError> {
+        WifiQrCode::from_uri(profile.to_wifi_uri(redaction)?)
+    }
+
+    /// Creates the QR code for a `WIFI:` URI
+    pub fn from_uri(uri: impl Into<String>) -> Result<WifiQrCode, WinWifiError> {
+        let uri = uri.into();
+        let code = qrcode::QrCode::new(uri.as_bytes())?;
+        Ok(WifiQrCode { uri, code })
+    }
+
+    /// Returns the encoded URI
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Renders the QR code as text using Unicode block characters for display in a terminal
+    pub fn to_terminal(&self) -> String {
+        use qrcode::render::unicode::Dense1x2;
+
+        self.code
+            .render::<Dense1x2>()
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .build()
+    }
+
+    /// Renders the QR code as an SVG image
+    pub fn to_svg(&self) -> String {
+        use qrcode::render::svg::Color;
+
+        self.code
+            .render::<Color>()
+            .quiet_zone(true)
+            .module_dimensions(MODULE_PIXELS as u32, MODULE_PIXELS as u32)
+            .build()
+    }
+
+    /// Renders the QR code as a grayscale PNG image
+    pub fn to_png(&self) -> Result<Vec<u8>, WinWifiError> {
+        let modules = self.code.width();
+        let size = (modules + 2 * QUIET_ZONE_MODULES) * MODULE_PIXELS;
+        let colors = self.code.to_colors();
+
+        let mut pixels = vec![u8::MAX; size * size];
+        for (index, color) in colors.iter().enumerate() {
+            if *color != qrcode::Color::Dark {
+                continue;
+            }
+
+            let x = (index % modules + QUIET_ZONE_MODULES) * MODULE_PIXELS;
+            let y = (index / modules + QUIET_ZONE_MODULES) * MODULE_PIXELS;
+            for row in y..y + MODULE_PIXELS {
+                pixels[row * size + x..row * size + x + MODULE_PIXELS].fill(0);
+            }
+        }
+
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, size as u32, size as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&pixels)?;
+        writer.finish()?;
+
+        Ok(png)
+    }
+}
+
+/// Escapes the special characters of a Wi-Fi URI value
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if ESCAPED_CHARACTERS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Removes the escapes from a Wi-Fi URI value
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+/// Splits a string at each separator which is not escaped
+fn split_unescaped(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (index, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == separator => {
+                parts.push(&value[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => (),
+        }
+    }
+
+    parts.push(&value[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks the URI of a hidden WPA2-Personal network with characters which need escaping
+    #[test]
+    fn psk_uri() {
+        let profile = WlanProfileBuilder::new("Guest")
+            .ssid("Guest;\"Wi-Fi\"")
+            .wpa2_psk(r"pass:word,\;")
+            .hidden(true)
+            .build()
+            .expect("Failed to build profile");
+
+        let uri = profile
+            .to_wifi_uri(Redaction::Reveal)
+            .expect("Failed to create URI");
+        assert_eq!(
+            uri,
+            r#"WIFI:T:WPA;S:Guest\;\"Wi-Fi\";P:pass\:word\,\\\;;H:true;;"#
+        );
+
+        let parsed = WlanProfile::from_wifi_uri(&uri).expect("Failed to parse URI");
+        assert_eq!(parsed.name, "Guest;\"Wi-Fi\"");
+        assert_eq!(parsed.ssid_config, profile.ssid_config);
+        assert_eq!(parsed.msm, profile.msm);
+
+        assert_eq!(
+            profile.to_wifi_uri(Redaction::default()).unwrap(),
+            r#"WIFI:T:WPA;S:Guest\;\"Wi-Fi\";P:********;H:true;;"#
+        );
+    }
+
+    /// Checks the URIs of open and WPA3-Personal networks
+    #[test]
+    fn open_and_sae_uri() {
+        let open = WlanProfileBuilder::new("Open").open().build().unwrap();
+        assert_eq!(
+            open.to_wifi_uri(Redaction::Reveal).unwrap(),
+            "WIFI:T:nopass;S:Open;;"
+        );
+
+        let sae = WlanProfileBuilder::new("SAE")
+            .wpa3_sae("passphrase")
+            .build()
+            .unwrap();
+        let uri = sae.to_wifi_uri(Redaction::Reveal).unwrap();
+        assert_eq!(uri, "WIFI:T:SAE;S:SAE;P:passphrase;;");
+        assert_eq!(WlanProfile::from_wifi_uri(&uri).unwrap().msm, sae.msm);
+
+        let enterprise = WlanProfileBuilder::new("EAP")
+            .wpa2_enterprise()
+            .build()
+            .unwrap();
+        assert!(enterprise.to_wifi_uri(Redaction::Reveal).is_err());
+    }
+
+    /// Makes sure that invalid URIs are rejected
+    #[test]
+    fn invalid_uri() {
+        assert!(WlanProfile::from_wifi_uri("WIFI:T:WPA;P:passphrase;;").is_err());
+        assert!(WlanProfile::from_wifi_uri("WIFI:T:WPA;S:Guest;;").is_err());
+        assert!(WlanProfile::from_wifi_uri("WIFI:T:WPA;S:Guest;P:short;;").is_err());
+        assert!(WlanProfile::from_wifi_uri("http://example.com").is_err());
+        assert!(WlanProfile::from_wifi_uri("wifi:S:Guest;;").is_ok());
+    }
+
+    /// Checks that the QR code renders in each format
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn render_qr_code() {
+        let code =
+            WifiQrCode::from_uri("WIFI:T:nopass;S:Guest;;").expect("Failed to create QR code");
+
+        assert!(code.to_terminal().contains('█'));
+        assert!(code.to_svg().starts_with("<?xml"));
+
+        let png = code.to_png().expect("Failed to render PNG");
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use crate::errors::WinWifiError;
+
+use super::info::WlanProfile;
+
+/// Marker which starts every XML document in a profile export. It has to be followed by
+/// whitespace to not match processing instructions like `<?xml-stylesheet`
+const DECLARATION_MARKER: &[u8] = b"<?xml";
+
+/// Marker which starts a comment
+const COMMENT_START: &[u8] = b"<!--";
+
+/// Marker which ends a comment
+const COMMENT_END: &[u8] = b"-->";
+
+/// Marker which starts a CDATA section
+const CDATA_START: &[u8] = b"<![CDATA[";
+
+/// Marker which ends a CDATA section
+const CDATA_END: &[u8] = b"]]>";
+
+/// UTF-8 byte order mark which may be at the start of the input
+const BYTE_ORDER_MARK: &[u8] = b"\xef\xbb\xbf";
+
+/// A `WLANProfile` document read from a file containing multiple profiles
+#[derive(Debug)]
+pub struct ProfileDocument {
+    /// Byte offset of the start of the document in the input
+    pub offset: u64,
+
+    /// Line number of the start of the document in the input, starting at 1
+    pub line: u64,
+
+    /// The parsed profile or the error which occurred while parsing the document
+    pub profile: Result<WlanProfile, WinWifiError>,
+}
+
+/// Reads every `WLANProfile` document from a file or stream containing concatenated XML
+/// documents, like the ones created when exporting multiple profiles. A new document starts at
+/// each `<?xml` declaration outside of comments and CDATA sections. A document which fails to
+/// parse is reported and reading continues with the next document
+pub struct ProfileReader<R: BufRead> {
+    /// The underlying input
+    reader: R,
+
+    /// Contents of the document currently being read
+    document: Vec<u8>,
+
+    /// Byte offset and line number of the first non-whitespace byte of the current document
+    document_start: Option<(u64, u64)>,
+
+    /// Byte offset of the next line read from the input
+    offset: u64,
+
+    /// Line number of the next line read from the input
+    line: u64,
+
+    /// Section of the input the end of the last scanned line is in
+    section: Section,
+
+    /// Documents which were completed but not yet returned
+    completed: VecDeque<ProfileDocument>,
+
+    /// Whether the end of the input was reached or reading the input failed
+    finished: bool,
+}
+
+impl<R: BufRead> ProfileReader<R> {
+    /// Creates a new reader over buffered input
+    pub fn new(reader: R) -> ProfileReader<R> {
+        ProfileReader {
+            reader,
+            document: Vec::new(),
+            document_start: None,
+            offset: 0,
+            line: 1,
+            section: Section::Markup,
+            completed: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Appends a line of input to the documents being read
+    fn push_line(&mut self, line: &[u8]) {
+        let mut cursor = 0;
+
+        // Skip the byte order mark at the start of the input
+        if self.offset == 0 && line.starts_with(BYTE_ORDER_MARK) {
+            cursor = BYTE_ORDER_MARK.len();
+        }
+
+        let mut search_start = 0;
+        while let Some(index) = self.find_declaration(&line[cursor..], search_start) {
+            self.push_bytes(&line[cursor..cursor + index], cursor);
+            self.finish_document();
+            cursor += index;
+            search_start = DECLARATION_MARKER.len();
+        }
+
+        self.push_bytes(&line[cursor..], cursor);
+
+        self.offset += line.len() as u64;
+        self.line += 1;
+    }
+
+    /// Returns the index of the first XML declaration at or after `start`, skipping comments and
+    /// CDATA sections. The scanned bytes are expected to continue the previously scanned ones
+    fn find_declaration(&mut self, bytes: &[u8], start: usize) -> Option<usize> {
+        let mut index = start;
+
+        while index < bytes.len() {
+            let rest = &bytes[index..];
+            let (section, marker) = match self.section {
+                Section::Markup
+                    if rest.starts_with(DECLARATION_MARKER)
+                        && rest
+                            .get(DECLARATION_MARKER.len())
+                            .is_some_and(|byte| is_whitespace(*byte)) =>
+                {
+                    return Some(index);
+                }
+                Section::Markup if rest.starts_with(COMMENT_START) => {
+                    (Section::Comment, COMMENT_START)
+                }
+                Section::Markup if rest.starts_with(CDATA_START) => (Section::CData, CDATA_START),
+                Section::Comment if rest.starts_with(COMMENT_END) => (Section::Markup, COMMENT_END),
+                Section::CData if rest.starts_with(CDATA_END) => (Section::Markup, CDATA_END),
+                _ => {
+                    index += 1;
+                    continue;
+                }
+            };
+
+            self.section = section;
+            index += marker.len();
+        }
+
+        None
+    }
+
+    /// Appends bytes starting at the specified column of the current line to the document
+    fn push_bytes(&mut self, bytes: &[u8], column: usize) {
+        if self.document_start.is_none() {
+            let Some(index) = bytes.iter().position(|byte| !is_whitespace(*byte)) else {
+                return;
+            };
+
+            self.document_start = Some((self.offset + (column + index) as u64, self.line));
+            self.document.extend_from_slice(&bytes[index..]);
+        } else {
+            self.document.extend_from_slice(bytes);
+        }
+    }
+
+    /// Parses the current document and queues it to be returned
+    fn finish_document(&mut self) {
+        let Some((offset, line)) = self.document_start.take() else {
+            return;
+        };
+
+        let document = std::mem::take(&mut self.document);
+        self.completed.push_back(ProfileDocument {
+            offset,
+            line,
+            profile: parse_document(&document),
+        });
+    }
+}
+
+impl ProfileReader<BufReader<File>> {
+    /// Opens a file for reading the profiles it contains
+    pub fn open(path: impl AsRef<Path>) -> Result<ProfileReader<BufReader<File>>, WinWifiError> {
+        Ok(ProfileReader::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: BufRead> Iterator for ProfileReader<R> {
+    type Item = Result<ProfileDocument, WinWifiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(document) = self.completed.pop_front() {
+                return Some(Ok(document));
+            }
+
+            if self.finished {
+                return None;
+            }
+
+            let mut line = Vec::new();
+            match self.reader.read_until(b'\n', &mut line) {
+                Ok(0) => {
+                    self.finished = true;
+                    self.finish_document();
+                }
+                Ok(_) => self.push_line(&line),
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+    }
+}
+
+/// Parses a single profile document
+fn parse_document(document: &[u8]) -> Result<WlanProfile, WinWifiError> {
+    let document = std::str::from_utf8(document)
+        .map_err(|e| quick_xml::DeError::from(quick_xml::Error::from(e)))?;
+
+    WlanProfile::from_xml(document)
+}
+
+/// Section of the input which can contain an XML declaration or text which looks like one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    /// Elements and text where a declaration starts a new document
+    Markup,
+
+    /// Inside of a comment
+    Comment,
+
+    /// Inside of a CDATA section
+    CData,
+}
+
+/// Checks if a byte is XML whitespace
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{example_document, TEST_PROFILES};
+
+    /// Checks that every profile in the example file is read along with its location
+    #[test]
+    fn read_example_profiles() {
+        let documents = ProfileReader::new(TEST_PROFILES.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to read example profiles");
+
+        let locations = documents
+            .iter()
+            .map(|document| (document.offset, document.line))
+            .collect::<Vec<_>>();
+
+        let expected = TEST_PROFILES
+            .match_indices("<?xml")
+            .map(|(offset, _)| {
+                let line = TEST_PROFILES[..offset].matches('\n').count() + 1;
+                (offset as u64, line as u64)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(locations, expected);
+        assert_eq!(
+            locations.iter().map(|(_, line)| *line).collect::<Vec<_>>(),
+            [1, 74, 101, 174]
+        );
+
+        let names = documents
+            .into_iter()
+            .map(|document| document.profile.expect("Failed to parse profile").name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            [
+                "REDACTED EAP",
+                "REDACTED OPEN",
+                "REDACTED EAP2",
+                "REDACTED WPA2PSK NONBROADCAST"
+            ]
+        );
+    }
+
+    /// Checks that reading continues after a document which fails to parse
+    #[test]
+    fn malformed_document() {
+        let input = TEST_PROFILES.replacen(
+            "<authentication>open</authentication>",
+            "<authentication>open</authentication",
+            1,
+        );
+
+        let results = ProfileReader::new(input.as_bytes())
+            .map(|document| document.expect("Failed to read profile").profile.is_ok())
+            .collect::<Vec<_>>();
+
+        assert_eq!(results, [true, false, true, true]);
+    }
+
+    /// Checks that documents which share a line are split
+    #[test]
+    fn documents_on_one_line() {
+        let profile = example_document(1).replace('\n', "");
+        let input = format!("\n  {profile}{profile}");
+
+        let documents = ProfileReader::new(input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to read profiles");
+
+        assert_eq!(documents.len(), 2);
+        assert_eq!((documents[0].offset, documents[0].line), (3, 2));
+        assert_eq!(
+            (documents[1].offset, documents[1].line),
+            (profile.len() as u64 + 3, 2)
+        );
+        assert!(documents.iter().all(|document| document.profile.is_ok()));
+    }
+
+    /// Checks that text which looks like a declaration does not split a document
+    #[test]
+    fn declaration_lookalikes() {
+        let input = TEST_PROFILES
+            .replacen(
+                "<WLANProfile",
+                "<?xml-stylesheet type=\"text/xsl\" href=\"profile.xsl\"?>\n<!-- exported from\n<?xml version=\"1.0\"?> -->\n<WLANProfile",
+                1,
+            )
+            .replacen(
+                "</WLANProfile>",
+                "<note><![CDATA[<?xml version=\"1.0\"?>]]></note></WLANProfile>",
+                1,
+            );
+
+        let documents = ProfileReader::new(input.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to read profiles");
+
+        assert_eq!(
+            documents
+                .iter()
+                .map(|document| document.line)
+                .collect::<Vec<_>>(),
+            [1, 77, 104, 177]
+        );
+        assert!(documents.iter().all(|document| document.profile.is_ok()));
+    }
+}
@@ -0,0 +1,330 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use super::{
+    eap::{EapConfig, PeapInnerMethod, TtlsPhase2},
+    info::WlanProfile,
+    xml::XmlElement,
+};
+
+/// Placeholder which replaces masked secrets
+pub const MASK_PLACEHOLDER: &str = "********";
+
+/// Prefix of the placeholder which replaces hashed secrets
+pub const HASH_PLACEHOLDER_PREFIX: &str = "hmac-sha256:";
+
+/// Parts of element names in unmodeled EAP settings which hold user credentials
+const CREDENTIAL_ELEMENT_NAMES: &[&str] = &["password", "username", "credential", "identity"];
+
+/// How secrets are handled when a profile is exported. Every export to another format takes a
+/// redaction which is applied to the network key and user credentials before they are written.
+/// Only [`Redaction::Reveal`] produces an export which can be used to join the network.
+/// Serializing a profile with serde or [`WlanProfile::to_xml`] is lossless, use
+/// [`WlanProfile::redacted`] or [`WlanProfile::to_xml_with`] to strip the secrets first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Redaction {
+    /// Secrets are removed from the profile
+    Remove,
+
+    /// Secrets are replaced with a fixed placeholder
+    #[default]
+    Mask,
+
+    /// Secrets are replaced with their HMAC-SHA256 using the specified key. Profiles exported
+    /// with the same key can be matched by their secrets without revealing them
+    Hash(HashKey),
+
+    /// Secrets are kept as is. Has to be explicitly selected
+    Reveal,
+}
+
+/// Secret key of [`Redaction::Hash`]. Without the key, the hashed secrets can not be recovered by
+/// hashing guessed passphrases. Use a random key for every export unless hashes have to be
+/// compared across exports
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct HashKey([u8; 32]);
+
+impl HashKey {
+    /// Creates a key from random bytes
+    pub const fn new(key: [u8; 32]) -> HashKey {
+        HashKey(key)
+    }
+}
+
+impl std::fmt::Debug for HashKey {
+    /// Hides the key so it is not shown in logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HashKey(..)")
+    }
+}
+
+impl Redaction {
+    /// Returns the placeholder which replaces a secret, or `None` if the secret is removed
+    fn replace(self, secret: &str) -> Option<String> {
+        match self {
+            Redaction::Remove => None,
+            Redaction::Mask => Some(MASK_PLACEHOLDER.to_string()),
+            Redaction::Hash(key) => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(&key.0)
+                    .expect("HMAC accepts keys of any length");
+                mac.update(secret.as_bytes());
+                let hex = crate::hex::encode(&mac.finalize().into_bytes());
+                Some(format!("{HASH_PLACEHOLDER_PREFIX}{hex}"))
+            }
+            Redaction::Reveal => Some(secret.to_string()),
+        }
+    }
+
+    /// Redacts the credentials in an unmodeled EAP element and its children
+    fn redact_element(self, element: &mut XmlElement) {
+        if is_credential_element(&element.name) {
+            element.text = element.text.as_deref().and_then(|text| self.replace(text));
+            element
+                .children
+                .iter_mut()
+                .for_each(|child| self.redact_all(child));
+        } else {
+            element
+                .children
+                .iter_mut()
+                .for_each(|child| self.redact_element(child));
+        }
+
+        if self == Redaction::Remove {
+            element
+                .children
+                .retain(|child| !is_credential_element(&child.name));
+        }
+    }
+
+    /// Redacts the text of an element and all of its children
+    fn redact_all(self, element: &mut XmlElement) {
+        element.text = element.text.as_deref().and_then(|text| self.replace(text));
+        element
+            .children
+            .iter_mut()
+            .for_each(|child| self.redact_all(child));
+    }
+}
+
+impl WlanProfile {
+    /// Redacts the secrets of the profile. This includes the shared key material, whether it is
+    /// protected or not, the user credentials in the EAP configuration and credentials in
+    /// unknown elements
+    pub fn redact(&mut self, redaction: Redaction) {
+        if redaction == Redaction::Reveal {
+            return;
+        }
+
+        self.unknown
+            .for_each_element_mut(&mut |element| redaction.redact_element(element));
+
+        let security = &mut self.msm.security;
+
+        if let Some(shared_key) = &mut security.shared_key {
+            match redaction.replace(&shared_key.key_material) {
+                Some(key_material) => shared_key.key_material = key_material,
+                None => security.shared_key = None,
+            }
+        }
+
+        if let Some(eap_config) = security
+            .one_x
+            .as_mut()
+            .and_then(|one_x| one_x.eap_config.as_mut())
+        {
+            redact_eap_config(eap_config, redaction);
+        }
+    }
+
+    /// Returns a copy of the profile with its secrets redacted
+    pub fn redacted(&self, redaction: Redaction) -> WlanProfile {
+        let mut profile = self.clone();
+        profile.redact(redaction);
+        profile
+    }
+}
+
+/// Redacts the user credentials in an EAP configuration
+fn redact_eap_config(eap_config: &mut EapConfig, redaction: Redaction) {
+    match eap_config {
+        EapConfig::Peap(peap) => {
+            if let PeapInnerMethod::Other(element) = &mut peap.inner_method {
+                redaction.redact_element(element);
+            }
+        }
+        EapConfig::Tls(_) => (),
+        EapConfig::Ttls(ttls) => {
+            ttls.anonymous_identity = ttls
+                .anonymous_identity
+                .as_deref()
+                .and_then(|identity| redaction.replace(identity));
+
+            if let TtlsPhase2::Other(element) = &mut ttls.phase2 {
+                redaction.redact_element(element);
+            }
+        }
+        EapConfig::Other(other) => redaction.redact_element(&mut other.config),
+    }
+}
+
+/// Checks if an element name in unmodeled EAP settings refers to user credentials
+fn is_credential_element(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    CREDENTIAL_ELEMENT_NAMES
+        .iter()
+        .any(|credential| name.contains(credential))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::{
+        eap::{EapMethod, OtherEapConfig, TtlsConfig},
+        WlanProfileBuilder,
+    };
+
+    /// Returns a WPA2-Personal profile with a known passphrase
+    fn psk_profile() -> WlanProfile {
+        WlanProfileBuilder::new("Redact")
+            .wpa2_psk("passphrase")
+            .build()
+            .expect("Failed to build profile")
+    }
+
+    /// Returns the key material of a profile
+    fn key_material(profile: &WlanProfile) -> Option<&str> {
+        profile
+            .msm
+            .security
+            .shared_key
+            .as_ref()
+            .map(|shared_key| shared_key.key_material.as_str())
+    }
+
+    /// Checks each way of redacting the shared key
+    #[test]
+    fn redact_shared_key() {
+        let profile = psk_profile();
+
+        assert_eq!(
+            key_material(&profile.redacted(Redaction::Mask)),
+            Some(MASK_PLACEHOLDER)
+        );
+        assert_eq!(
+            key_material(&profile.redacted(Redaction::Hash(HashKey::new([0x42; 32])))),
+            Some("hmac-sha256:6e6cca08cce8ac482b632597aa8ad57ed2edcf880acee71a0e2aaa5687c949ab")
+        );
+        assert_ne!(
+            key_material(&profile.redacted(Redaction::Hash(HashKey::new([0x43; 32])))),
+            key_material(&profile.redacted(Redaction::Hash(HashKey::new([0x42; 32]))))
+        );
+        assert_eq!(key_material(&profile.redacted(Redaction::Remove)), None);
+        assert_eq!(
+            key_material(&profile.redacted(Redaction::Reveal)),
+            Some("passphrase")
+        );
+    }
+
+    /// Checks that serializing a profile keeps its secrets unless it is redacted first
+    #[test]
+    fn serialize_secrets() {
+        let profile = psk_profile();
+
+        let json = serde_json::to_string(&profile).expect("Failed to serialize profile");
+        assert!(json.contains(r#""keyMaterial":"passphrase""#));
+
+        let json = serde_json::to_string(&profile.redacted(Redaction::Mask))
+            .expect("Failed to serialize profile");
+        assert!(json.contains(r#""keyMaterial":"********""#));
+        assert!(!json.contains("passphrase"));
+
+        let key = HashKey::new([0x42; 32]);
+        let hashed = profile
+            .to_xml_with(Redaction::Hash(key))
+            .expect("Failed to serialize profile");
+        assert!(hashed.contains(&format!(
+            "<keyMaterial>{}</keyMaterial>",
+            key_material(&profile.redacted(Redaction::Hash(key))).unwrap()
+        )));
+
+        let serialized = profile.to_xml().expect("Failed to serialize profile");
+        assert!(serialized.contains("<keyMaterial>passphrase</keyMaterial>"));
+        assert_eq!(
+            WlanProfile::from_xml(&serialized).expect("Failed to parse profile"),
+            profile
+        );
+    }
+
+    /// Checks that the user credentials in EAP settings are redacted
+    #[test]
+    fn redact_eap_credentials() {
+        let mut profile = WlanProfileBuilder::new("Redact")
+            .wpa2_enterprise()
+            .eap_config(EapConfig::Ttls(TtlsConfig {
+                anonymous_identity: Some("anonymous".to_string()),
+                ..Default::default()
+            }))
+            .build()
+            .expect("Failed to build profile");
+
+        let eap_config = |profile: &WlanProfile| {
+            profile
+                .msm
+                .security
+                .one_x
+                .as_ref()
+                .and_then(|one_x| one_x.eap_config.clone())
+                .expect("Missing EAPConfig element")
+        };
+
+        let EapConfig::Ttls(ttls) = eap_config(&profile.redacted(Redaction::Mask)) else {
+            panic!("Expected an EAP-TTLS configuration");
+        };
+        assert_eq!(ttls.anonymous_identity.as_deref(), Some(MASK_PLACEHOLDER));
+
+        let config = XmlElement::new("Config").with_child(
+            XmlElement::new("Vendor")
+                .with_child(XmlElement::new("UserName").with_text("user"))
+                .with_child(XmlElement::new("Password").with_text("secret"))
+                .with_child(XmlElement::new("Server").with_text("radius")),
+        );
+        profile.msm.security.one_x.as_mut().unwrap().eap_config =
+            Some(EapConfig::Other(OtherEapConfig {
+                method: EapMethod {
+                    eap_type: 254,
+                    vendor_id: 1,
+                    vendor_type: 0,
+                    author_id: 0,
+                },
+                config,
+            }));
+
+        let EapConfig::Other(other) = eap_config(&profile.redacted(Redaction::Mask)) else {
+            panic!("Expected an unmodeled EAP configuration");
+        };
+        let vendor = other
+            .config
+            .child("Vendor")
+            .expect("Missing Vendor element");
+        assert_eq!(vendor.child_text("UserName"), Some(MASK_PLACEHOLDER));
+        assert_eq!(vendor.child_text("Password"), Some(MASK_PLACEHOLDER));
+        assert_eq!(vendor.child_text("Server"), Some("radius"));
+
+        let EapConfig::Other(other) = eap_config(&profile.redacted(Redaction::Remove)) else {
+            panic!("Expected an unmodeled EAP configuration");
+        };
+        let vendor = other
+            .config
+            .child("Vendor")
+            .expect("Missing Vendor element");
+        assert_eq!(
+            vendor
+                .children
+                .iter()
+                .map(|child| child.name.as_str())
+                .collect::<Vec<_>>(),
+            ["Server"]
+        );
+    }
+}
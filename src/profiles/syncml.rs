@@ -0,0 +1,359 @@
+use std::fmt::Write;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use quick_xml::{escape::escape, events::Event, Reader};
+
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::{info::WlanProfile, redact::Redaction};
+
+/// Namespace of SyncML messages
+pub const SYNCML_NAMESPACE: &str = "SYNCML:SYNCML1.2";
+
+/// Namespace of the SyncML meta information elements
+pub const METINF_NAMESPACE: &str = "syncml:metinf";
+
+/// Path of the profile nodes of the WiFi configuration service provider
+pub const WIFI_PROFILE_PATH: &str = "./Vendor/MSFT/WiFi/Profile";
+
+/// Name of the node which holds the profile XML
+const WLAN_XML_NODE: &str = "WlanXml";
+
+/// SyncML command which delivers a profile to a device
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyncMlCommand {
+    /// Adds a profile which is not on the device yet
+    #[default]
+    Add,
+
+    /// Replaces a profile which is already on the device
+    Replace,
+}
+
+impl SyncMlCommand {
+    /// Name of the command element
+    fn element_name(self) -> &'static str {
+        match self {
+            SyncMlCommand::Add => "Add",
+            SyncMlCommand::Replace => "Replace",
+        }
+    }
+
+    /// Returns the command of an element name
+    fn from_element_name(name: &str) -> Option<SyncMlCommand> {
+        match name {
+            "Add" => Some(SyncMlCommand::Add),
+            "Replace" => Some(SyncMlCommand::Replace),
+            _ => None,
+        }
+    }
+}
+
+/// A wireless profile extracted from a SyncML payload
+#[derive(Debug)]
+pub struct SyncMlProfile {
+    /// Command which delivers the profile
+    pub command: SyncMlCommand,
+
+    /// OMA-URI of the `WlanXml` node the profile is written to
+    pub uri: String,
+
+    /// The parsed profile or the error which occurred while parsing it
+    pub profile: Result<WlanProfile, WinWifiError>,
+}
+
+/// An `Item` element of a command which is being read
+#[derive(Default)]
+struct Item {
+    uri: String,
+    format: String,
+    data: String,
+}
+
+impl WlanProfile {
+    /// Returns the OMA-URI of the WiFi CSP node which holds the XML of the profile. The SSID is
+    /// percent-encoded since it is a node name of the URI
+    pub fn oma_uri(&self) -> Result<String, WinWifiError> {
+        let ssid = self.ssid_config.ssid.ssid()?;
+        Ok(format!(
+            "{WIFI_PROFILE_PATH}/{}/{WLAN_XML_NODE}",
+            encode_node(&ssid)
+        ))
+    }
+
+    /// Wraps the profile in a SyncML message for the WiFi configuration service provider. The
+    /// profile XML is escaped as the data of the `WlanXml` node
+    pub fn to_syncml(
+        &self,
+        command: SyncMlCommand,
+        redaction: Redaction,
+    ) -> Result<String, WinWifiError> {
+        export_profiles(std::slice::from_ref(self), command, redaction)
+    }
+}
+
+/// Wraps several profiles in a single SyncML message with one command for each profile
+pub fn export_profiles(
+    profiles: &[WlanProfile],
+    command: SyncMlCommand,
+    redaction: Redaction,
+) -> Result<String, WinWifiError> {
+    let command = command.element_name();
+
+    let mut syncml = format!("<SyncML xmlns=\"{SYNCML_NAMESPACE}\">\n  <SyncBody>\n");
+
+    for (index, profile) in profiles.iter().enumerate() {
+        let uri = profile.oma_uri()?;
+        let xml = profile.to_xml_with(redaction)?;
+
+        let _ = write!(
+            syncml,
+            "    <{command}>\n      \
+             <CmdID>{}</CmdID>\n      \
+             <Item>\n        \
+             <Target>\n          \
+             <LocURI>{}</LocURI>\n        \
+             </Target>\n        \
+             <Meta>\n          \
+             <Format xmlns=\"{METINF_NAMESPACE}\">chr</Format>\n        \
+             </Meta>\n        \
+             <Data>{}</Data>\n      \
+             </Item>\n    \
+             </{command}>\n",
+            index + 1,
+            escape(&uri),
+            escape(xml.trim_end()),
+        );
+    }
+
+    syncml.push_str("    <Final/>\n  </SyncBody>\n</SyncML>\n");
+    Ok(syncml)
+}
+
+/// Extracts the wireless profiles written to `WlanXml` nodes of the WiFi configuration service
+/// provider from a SyncML message. Items which target other nodes are ignored. A profile which
+/// can not be parsed is returned with its error so that the other profiles can still be checked
+pub fn import_profiles(syncml: &str) -> Result<Vec<SyncMlProfile>, WinWifiError> {
+    let mut reader = Reader::from_str(syncml);
+
+    let mut profiles = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut command = None;
+    let mut item: Option<Item> = None;
+
+    loop {
+        let event = reader.read_event().map_err(quick_xml::DeError::from)?;
+
+        match event {
+            Event::Start(element) => {
+                let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+                if let Some(element_command) = SyncMlCommand::from_element_name(&name) {
+                    command = Some(element_command);
+                } else if name == "Item" && command.is_some() {
+                    item = Some(Item::default());
+                }
+                path.push(name);
+            }
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                if SyncMlCommand::from_element_name(&name).is_some() {
+                    command = None;
+                } else if name == "Item" {
+                    if let (Some(command), Some(item)) = (command, item.take()) {
+                        if is_wlan_xml_uri(item.uri.trim()) {
+                            profiles.push(SyncMlProfile {
+                                command,
+                                uri: item.uri.trim().to_string(),
+                                profile: parse_data(&item),
+                            });
+                        }
+                    }
+                }
+            }
+            Event::Text(text) => {
+                if let Some(field) = item.as_mut().and_then(|item| item_field(item, &path)) {
+                    field.push_str(&text.unescape().map_err(quick_xml::DeError::from)?);
+                }
+            }
+            Event::CData(cdata) => {
+                if let Some(field) = item.as_mut().and_then(|item| item_field(item, &path)) {
+                    field.push_str(&String::from_utf8_lossy(&cdata));
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+
+    Ok(profiles)
+}
+
+/// Returns the field of an item which holds the text of the current element, if any
+fn item_field<'a>(item: &'a mut Item, path: &[String]) -> Option<&'a mut String> {
+    match path {
+        [.., parent, name] if parent == "Target" && name == "LocURI" => Some(&mut item.uri),
+        [.., parent, name] if parent == "Meta" && name == "Format" => Some(&mut item.format),
+        [.., parent, name] if parent == "Item" && name == "Data" => Some(&mut item.data),
+        _ => None,
+    }
+}
+
+/// Parses the profile in the data of an item
+fn parse_data(item: &Item) -> Result<WlanProfile, WinWifiError> {
+    match item.format.trim() {
+        "b64" => {
+            let data = item.data.split_whitespace().collect::<String>();
+            let xml = STANDARD.decode(data).map_err(|e| {
+                WinWifiError::InvalidProfile(format!("the item data is not valid base64: {e}"))
+            })?;
+            let xml = String::from_utf8(xml).map_err(|_| {
+                WinWifiError::InvalidProfile("the item data is not valid UTF-8".to_string())
+            })?;
+            WlanProfile::from_xml(xml.trim())
+        }
+        _ => WlanProfile::from_xml(item.data.trim()),
+    }
+}
+
+/// Checks if an OMA-URI refers to the `WlanXml` node of a WiFi CSP profile. Device and user
+/// scoped URIs are accepted
+fn is_wlan_xml_uri(uri: &str) -> bool {
+    let uri = uri
+        .strip_prefix("./Device")
+        .or_else(|| uri.strip_prefix("./User"))
+        .map(|uri| format!(".{uri}"))
+        .unwrap_or_else(|| uri.to_string());
+
+    uri.strip_prefix(WIFI_PROFILE_PATH)
+        .and_then(|node| node.strip_prefix('/'))
+        .and_then(|node| node.strip_suffix(WLAN_XML_NODE))
+        .and_then(|node| node.strip_suffix('/'))
+        .is_some_and(|node| !node.is_empty() && !node.contains('/'))
+}
+
+/// Percent-encodes an SSID for use as a node name of an OMA-URI
+fn encode_node(ssid: &Ssid) -> String {
+    ssid.as_bytes()
+        .iter()
+        .fold(String::new(), |mut node, byte| {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+                node.push(*byte as char);
+            } else {
+                let _ = write!(node, "%{byte:02X}");
+            }
+            node
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::WlanProfileBuilder;
+
+    /// Checks that exported profiles are escaped and extracted again
+    #[test]
+    fn syncml_round_trip() {
+        let profiles = [
+            WlanProfileBuilder::new("Home & <Office>")
+                .ssid("Home & Office")
+                .wpa2_psk("pass\"word'")
+                .build()
+                .expect("Failed to build profile"),
+            WlanProfileBuilder::new("Guest")
+                .open()
+                .build()
+                .expect("Failed to build profile"),
+        ];
+
+        let syncml = export_profiles(&profiles, SyncMlCommand::Replace, Redaction::Reveal)
+            .expect("Failed to export profiles");
+
+        assert!(syncml
+            .contains("<LocURI>./Vendor/MSFT/WiFi/Profile/Home%20%26%20Office/WlanXml</LocURI>"));
+        assert!(syncml.contains("<Data>&lt;?xml version=&quot;1.0&quot;?&gt;"));
+        assert!(syncml.contains("<CmdID>2</CmdID>"));
+
+        let imported = import_profiles(&syncml).expect("Failed to import profiles");
+        assert_eq!(imported.len(), 2);
+
+        for (import, profile) in imported.iter().zip(&profiles) {
+            assert_eq!(import.command, SyncMlCommand::Replace);
+            assert_eq!(import.uri, profile.oma_uri().unwrap());
+            assert_eq!(import.profile.as_ref().unwrap(), profile);
+        }
+    }
+
+    /// Checks that profiles in CDATA sections and base64 data are extracted and other items are
+    /// ignored
+    #[test]
+    fn import_payload() {
+        let profile = WlanProfileBuilder::new("Office")
+            .wpa2_psk("passphrase")
+            .build()
+            .expect("Failed to build profile");
+        let xml = profile
+            .to_xml_with(Redaction::Reveal)
+            .expect("Failed to serialize profile");
+
+        let syncml = format!(
+            r#"<SyncML xmlns="SYNCML:SYNCML1.2">
+  <SyncBody>
+    <Atomic>
+      <CmdID>1</CmdID>
+      <Add>
+        <CmdID>2</CmdID>
+        <Item>
+          <Target><LocURI>./Device/Vendor/MSFT/WiFi/Profile/Office/WlanXml</LocURI></Target>
+          <Data><![CDATA[{xml}]]></Data>
+        </Item>
+        <Item>
+          <Target><LocURI>./Vendor/MSFT/WiFi/Profile/Office/ProxyPacUrl</LocURI></Target>
+          <Data>http://proxy/proxy.pac</Data>
+        </Item>
+      </Add>
+    </Atomic>
+    <Replace>
+      <CmdID>3</CmdID>
+      <Item>
+        <Target><LocURI>./User/Vendor/MSFT/WiFi/Profile/Office/WlanXml</LocURI></Target>
+        <Meta><Format xmlns="syncml:metinf">b64</Format></Meta>
+        <Data>{}</Data>
+      </Item>
+      <Item>
+        <Target><LocURI>./Vendor/MSFT/WiFi/Profile/Broken/WlanXml</LocURI></Target>
+        <Data>&lt;WLANProfile&gt;</Data>
+      </Item>
+    </Replace>
+    <Final/>
+  </SyncBody>
+</SyncML>"#,
+            STANDARD.encode(&xml)
+        );
+
+        let imported = import_profiles(&syncml).expect("Failed to import profiles");
+
+        assert_eq!(
+            imported
+                .iter()
+                .map(|import| (import.command, import.uri.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    SyncMlCommand::Add,
+                    "./Device/Vendor/MSFT/WiFi/Profile/Office/WlanXml"
+                ),
+                (
+                    SyncMlCommand::Replace,
+                    "./User/Vendor/MSFT/WiFi/Profile/Office/WlanXml"
+                ),
+                (
+                    SyncMlCommand::Replace,
+                    "./Vendor/MSFT/WiFi/Profile/Broken/WlanXml"
+                ),
+            ]
+        );
+        assert_eq!(imported[0].profile.as_ref().unwrap(), &profile);
+        assert_eq!(imported[1].profile.as_ref().unwrap(), &profile);
+        assert!(imported[2].profile.is_err());
+    }
+}
@@ -0,0 +1,418 @@
+use quick_xml::{
+    events::{BytesStart, BytesText, Event},
+    DeError, Reader, Writer,
+};
+
+use super::xml::{format_profile_document, XmlElement};
+
+/// Elements and attributes of a parsed profile which are not part of the profile model, e.g.
+/// newer schema extensions or IHV settings. They are written back out unchanged when the
+/// profile is serialized so that editing a profile does not drop them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnknownXml {
+    /// Unknown attributes of the element including namespace declarations
+    attributes: Vec<(String, String)>,
+
+    /// Unknown child elements and the known sibling which precedes them in the document
+    elements: Vec<(Option<ElementKey>, XmlElement)>,
+
+    /// Unknown XML nested inside known child elements
+    children: Vec<(ElementKey, UnknownXml)>,
+}
+
+/// Identifies a child element by its local name and its position among the siblings with the
+/// same local name
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ElementKey {
+    /// Local name of the element
+    name: String,
+
+    /// Number of preceding siblings with the same local name
+    occurrence: usize,
+}
+
+impl UnknownXml {
+    /// Returns `true` if the profile does not have any unknown elements or attributes
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.elements.is_empty() && self.children.is_empty()
+    }
+
+    /// Discards the unknown elements and attributes so only the modeled settings are written
+    pub fn clear(&mut self) {
+        *self = UnknownXml::default();
+    }
+
+    /// Returns the unknown elements in document order. Nested unknown elements are part of
+    /// the returned elements
+    pub fn elements(&self) -> Vec<&XmlElement> {
+        let mut elements = Vec::new();
+        self.collect_elements(&mut elements);
+        elements
+    }
+
+    fn collect_elements<'a>(&'a self, elements: &mut Vec<&'a XmlElement>) {
+        elements.extend(self.elements.iter().map(|(_, element)| element));
+        for (_, child) in &self.children {
+            child.collect_elements(elements);
+        }
+    }
+
+    /// Calls a function with each unknown element
+    pub(super) fn for_each_element_mut(&mut self, f: &mut impl FnMut(&mut XmlElement)) {
+        self.elements.iter_mut().for_each(|(_, element)| f(element));
+        for (_, child) in &mut self.children {
+            child.for_each_element_mut(f);
+        }
+    }
+
+    /// Finds the elements and attributes of an original profile document which are missing
+    /// from the document serialized from the parsed profile
+    pub(super) fn from_documents(original: &str, modeled: &str) -> Result<UnknownXml, DeError> {
+        Ok(UnknownXml::diff(
+            &parse_element(original)?,
+            &parse_element(modeled)?,
+        ))
+    }
+
+    fn diff(original: &XmlElement, modeled: &XmlElement) -> UnknownXml {
+        let mut unknown = UnknownXml {
+            attributes: original
+                .attributes
+                .iter()
+                .filter(|(name, _)| modeled.attribute(name).is_none())
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+
+        let modeled_children = keyed_children(modeled);
+        let mut anchor = None;
+
+        for (key, child) in keyed_children(original) {
+            match modeled_children
+                .iter()
+                .find(|(modeled_key, _)| *modeled_key == key)
+            {
+                Some((_, modeled_child)) => {
+                    let nested = UnknownXml::diff(child, modeled_child);
+                    if !nested.is_empty() {
+                        unknown.children.push((key.clone(), nested));
+                    }
+                    anchor = Some(key);
+                }
+                None => unknown.elements.push((anchor.clone(), child.clone())),
+            }
+        }
+
+        unknown
+    }
+
+    /// Adds the unknown elements and attributes to a serialized profile document. The document
+    /// is returned as is if there are none
+    pub(super) fn merge_document(&self, document: String) -> Result<String, DeError> {
+        if self.is_empty() {
+            return Ok(document);
+        }
+
+        let mut root = parse_element(&document)?;
+        self.merge(&mut root);
+
+        let mut writer = Writer::new(Vec::new());
+        write_element(&mut writer, &root)?;
+        format_profile_document(&String::from_utf8(writer.into_inner())?)
+    }
+
+    fn merge(&self, element: &mut XmlElement) {
+        for (name, value) in &self.attributes {
+            if element.attribute(name).is_none() {
+                element.attributes.push((name.clone(), value.clone()));
+            }
+        }
+
+        let keys = keyed_children(element)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+
+        for (key, nested) in &self.children {
+            if let Some(index) = keys.iter().position(|child_key| child_key == key) {
+                nested.merge(&mut element.children[index]);
+            }
+        }
+
+        let known = std::mem::take(&mut element.children);
+        self.push_elements_after(None, &mut element.children);
+        for (key, child) in keys.iter().zip(known) {
+            element.children.push(child);
+            self.push_elements_after(Some(key), &mut element.children);
+        }
+
+        // Elements whose preceding sibling was removed from the profile are kept at the end
+        element.children.extend(
+            self.elements
+                .iter()
+                .filter(|(anchor, _)| anchor.as_ref().is_some_and(|anchor| !keys.contains(anchor)))
+                .map(|(_, element)| element.clone()),
+        );
+    }
+
+    /// Appends the unknown elements which follow a known sibling, or which come first if there
+    /// is none
+    fn push_elements_after(&self, anchor: Option<&ElementKey>, children: &mut Vec<XmlElement>) {
+        children.extend(
+            self.elements
+                .iter()
+                .filter(|(element_anchor, _)| element_anchor.as_ref() == anchor)
+                .map(|(_, element)| element.clone()),
+        );
+    }
+}
+
+/// Returns the child elements of an element with their keys
+fn keyed_children(element: &XmlElement) -> Vec<(ElementKey, &XmlElement)> {
+    let mut keyed: Vec<(ElementKey, &XmlElement)> = Vec::with_capacity(element.children.len());
+
+    for child in &element.children {
+        let name = local_name(&child.name);
+        let occurrence = keyed.iter().filter(|(key, _)| key.name == name).count();
+        keyed.push((
+            ElementKey {
+                name: name.to_string(),
+                occurrence,
+            },
+            child,
+        ));
+    }
+
+    keyed
+}
+
+/// Returns the name of an element without its namespace prefix
+fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(_, local)| local)
+}
+
+/// Parses the root element of an XML document. Unlike deserializing an [`XmlElement`], the
+/// namespace prefixes of element names are kept. The text of elements without children is
+/// kept as is while whitespace between elements is dropped
+fn parse_element(xml: &str) -> Result<XmlElement, DeError> {
+    let mut reader = Reader::from_str(xml);
+
+    let mut stack: Vec<XmlElement> = Vec::new();
+
+    loop {
+        let element = match reader.read_event()? {
+            Event::Start(start) => {
+                stack.push(start_element(&start)?);
+                continue;
+            }
+            Event::Empty(start) => start_element(&start)?,
+            Event::End(_) => {
+                let mut element = stack.pop().ok_or(DeError::ExpectedStart)?;
+                if element.children.is_empty() {
+                    element.text.get_or_insert_with(String::new);
+                } else if element
+                    .text
+                    .as_deref()
+                    .is_some_and(|text| text.trim().is_empty())
+                {
+                    element.text = None;
+                }
+                element
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element
+                        .text
+                        .get_or_insert_with(String::new)
+                        .push_str(&text.unescape()?);
+                }
+                continue;
+            }
+            Event::CData(cdata) => {
+                if let Some(element) = stack.last_mut() {
+                    element
+                        .text
+                        .get_or_insert_with(String::new)
+                        .push_str(&String::from_utf8_lossy(&cdata.into_inner()));
+                }
+                continue;
+            }
+            Event::Eof => return Err(DeError::UnexpectedEof),
+            _ => continue,
+        };
+
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(element),
+            None => return Ok(element),
+        }
+    }
+}
+
+/// Creates an element with the name and attributes of a start tag
+fn start_element(start: &BytesStart) -> Result<XmlElement, DeError> {
+    let mut element = XmlElement::new(String::from_utf8_lossy(start.name().as_ref()));
+
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::InvalidAttr)?;
+        element.attributes.push((
+            String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+            attribute.unescape_value()?.into_owned(),
+        ));
+    }
+
+    Ok(element)
+}
+
+/// Writes an element and its children. Elements without text or children are written as empty
+/// elements
+fn write_element(writer: &mut Writer<Vec<u8>>, element: &XmlElement) -> Result<(), DeError> {
+    let mut start = BytesStart::new(element.name.as_str());
+    for (name, value) in &element.attributes {
+        start.push_attribute((name.as_str(), value.as_str()));
+    }
+
+    if element.text.is_none() && element.children.is_empty() {
+        writer.write_event(Event::Empty(start))?;
+        return Ok(());
+    }
+
+    writer.write_event(Event::Start(start.borrow()))?;
+
+    if let Some(text) = element.text.as_deref().filter(|text| !text.is_empty()) {
+        writer.write_event(Event::Text(BytesText::new(text)))?;
+    }
+
+    for child in &element.children {
+        write_element(writer, child)?;
+    }
+
+    writer.write_event(Event::End(start.to_end()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        profiles::{info::WlanProfile, redact::Redaction},
+        testing::example_profile,
+    };
+
+    const EXTENDED_PROFILE: &str = r#"<?xml version="1.0"?>
+<WLANProfile xmlns="http://www.microsoft.com/networking/WLAN/profile/v1" xmlns:ihv="urn:example:ihv">
+  <name>Extended</name>
+  <SSIDConfig>
+    <SSID>
+      <hex>457874656E646564</hex>
+      <name>Extended</name>
+    </SSID>
+    <nonBroadcast>false</nonBroadcast>
+  </SSIDConfig>
+  <connectionType>ESS</connectionType>
+  <connectionMode>auto</connectionMode>
+  <autoConnectOnRoam xmlns="http://www.microsoft.com/networking/WLAN/profile/v4">true</autoConnectOnRoam>
+  <MSM>
+    <security>
+      <authEncryption>
+        <authentication>WPA2PSK</authentication>
+        <encryption>AES</encryption>
+        <useOneX>false</useOneX>
+      </authEncryption>
+      <sharedKey>
+        <keyType>passPhrase</keyType>
+        <protected>false</protected>
+        <keyMaterial>passphrase</keyMaterial>
+      </sharedKey>
+    </security>
+  </MSM>
+  <ihv:IHV>
+    <ihv:OUIHeader>
+      <ihv:OUI>000000</ihv:OUI>
+      <ihv:type>0</ihv:type>
+    </ihv:OUIHeader>
+    <ihv:password>secret</ihv:password>
+  </ihv:IHV>
+  <Hotspot2 xmlns="http://www.microsoft.com/networking/WLAN/profile/v2">
+    <DomainName>example.com</DomainName>
+    <NAIRealm/>
+  </Hotspot2>
+</WLANProfile>
+"#;
+
+    /// Makes sure that the example profiles do not have unknown XML
+    #[test]
+    fn example_profiles_known() {
+        for index in 0..4 {
+            assert!(example_profile(index).unknown.is_empty());
+        }
+    }
+
+    /// Checks that unknown elements and attributes are written back out where they were
+    #[test]
+    fn unknown_round_trip() {
+        let profile = WlanProfile::from_xml(EXTENDED_PROFILE).expect("Failed to parse profile");
+
+        assert_eq!(
+            profile
+                .unknown
+                .elements()
+                .iter()
+                .map(|element| element.name.as_str())
+                .collect::<Vec<_>>(),
+            ["autoConnectOnRoam", "ihv:IHV", "Hotspot2"]
+        );
+
+        assert_eq!(
+            profile
+                .to_xml_with(Redaction::Reveal)
+                .expect("Failed to serialize profile"),
+            EXTENDED_PROFILE
+        );
+    }
+
+    /// Checks that whitespace at the edges of unknown element text is kept
+    #[test]
+    fn unknown_text_whitespace() {
+        let xml = EXTENDED_PROFILE.replace(
+            "<ihv:password>secret</ihv:password>",
+            "<ihv:password>  two words </ihv:password>",
+        );
+        let profile = WlanProfile::from_xml(&xml).expect("Failed to parse profile");
+
+        assert_eq!(
+            profile
+                .to_xml_with(Redaction::Reveal)
+                .expect("Failed to serialize profile"),
+            xml
+        );
+    }
+
+    /// Makes sure that unknown elements are kept when the profile is edited
+    #[test]
+    fn unknown_edit() {
+        let mut profile = WlanProfile::from_xml(EXTENDED_PROFILE).expect("Failed to parse profile");
+        profile.auto_switch = Some(true);
+
+        let edited = WlanProfile::from_xml(
+            &profile
+                .to_xml_with(Redaction::Reveal)
+                .expect("Failed to serialize profile"),
+        )
+        .expect("Failed to parse edited profile");
+
+        assert_eq!(edited.auto_switch, Some(true));
+        assert_eq!(edited.unknown, profile.unknown);
+
+        let masked = profile
+            .to_xml_with(Redaction::Mask)
+            .expect("Failed to serialize profile");
+        assert!(masked.contains("xmlns:ihv=\"urn:example:ihv\""));
+        assert!(!masked.contains("secret"));
+
+        profile.unknown.clear();
+        assert!(!profile
+            .to_xml_with(Redaction::Reveal)
+            .expect("Failed to serialize profile")
+            .contains("Hotspot2"));
+    }
+}
@@ -0,0 +1,452 @@
+use std::fmt::Display;
+
+use crate::ssid::Ssid;
+
+use super::{
+    info::{Authentication, Encryption, KeyType, WlanProfile},
+    psk::{check_passphrase, parse_network_key},
+};
+
+/// Severity of a validation diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Windows accepts the profile but the setting is likely a mistake
+    Warning,
+
+    /// Windows rejects the profile
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// A problem found while validating a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Severity of the problem
+    pub severity: Severity,
+
+    /// Path of the element with the problem, e.g. `WLANProfile/MSM/security/sharedKey`
+    pub path: String,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.path, self.message)
+    }
+}
+
+/// Collects the diagnostics found while validating a profile
+#[derive(Default)]
+struct Validator {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator {
+    fn error(&mut self, path: &str, message: impl Into<String>) {
+        self.push(Severity::Error, path, message);
+    }
+
+    fn warning(&mut self, path: &str, message: impl Into<String>) {
+        self.push(Severity::Warning, path, message);
+    }
+
+    fn push(&mut self, severity: Severity, path: &str, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            path: format!("WLANProfile/{path}"),
+            message: message.into(),
+        });
+    }
+
+    /// Checks that an optional value is inside of an inclusive range
+    fn range(&mut self, path: &str, value: Option<u32>, min: u32, max: u32) {
+        if let Some(value) = value.filter(|value| !(min..=max).contains(value)) {
+            self.error(
+                path,
+                format!("{value} is outside of the allowed range of {min} to {max}"),
+            );
+        }
+    }
+}
+
+impl WlanProfile {
+    /// Checks the profile against the rules Windows enforces when a profile is set. Returns
+    /// every problem found, or an empty list if the profile is valid
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut validator = Validator::default();
+
+        if self.name.is_empty() {
+            validator.error("name", "the profile name is empty");
+        }
+
+        self.validate_ssid(&mut validator);
+        self.validate_security(&mut validator);
+
+        validator.diagnostics
+    }
+
+    /// Checks the SSID settings of the profile
+    fn validate_ssid(&self, validator: &mut Validator) {
+        let ssid = &self.ssid_config.ssid;
+
+        if ssid.hex.is_none() && ssid.name.is_none() {
+            validator.error(
+                "SSIDConfig/SSID",
+                "the SSID must contain a hex or name element",
+            );
+        }
+
+        if let Some(hex) = &ssid.hex {
+            if hex.is_empty() || hex.len() > Ssid::MAX_LENGTH {
+                validator.error(
+                    "SSIDConfig/SSID/hex",
+                    format!(
+                        "the SSID is {} bytes long but must be between 1 and {} bytes",
+                        hex.len(),
+                        Ssid::MAX_LENGTH
+                    ),
+                );
+            }
+        }
+
+        if let Some(name) = &ssid.name {
+            if name.is_empty() || name.len() > Ssid::MAX_LENGTH {
+                validator.error(
+                    "SSIDConfig/SSID/name",
+                    format!(
+                        "the SSID is {} bytes long but must be between 1 and {} bytes",
+                        name.len(),
+                        Ssid::MAX_LENGTH
+                    ),
+                );
+            }
+
+            if ssid
+                .hex
+                .as_ref()
+                .is_some_and(|hex| hex.as_bytes() != name.as_bytes())
+            {
+                validator.warning(
+                    "SSIDConfig/SSID/name",
+                    "the SSID name does not match the hex SSID. Windows uses the hex SSID",
+                );
+            }
+        }
+    }
+
+    /// Checks the security settings of the profile
+    fn validate_security(&self, validator: &mut Validator) {
+        let security = &self.msm.security;
+        let authentication = security.auth_encryption.authentication;
+        let encryption = security.auth_encryption.encryption;
+
+        if !allowed_encryption(authentication).contains(&encryption) {
+            validator.error(
+                "MSM/security/authEncryption/encryption",
+                format!("{encryption:?} encryption can not be used with {authentication:?} authentication"),
+            );
+        }
+
+        let use_one_x = security.auth_encryption.use_one_x.unwrap_or(false);
+        match (use_one_x, security.one_x.is_some()) {
+            (true, false) => validator.error(
+                "MSM/security/authEncryption/useOneX",
+                "useOneX is enabled but the OneX element is missing",
+            ),
+            (false, true) => validator.error(
+                "MSM/security/OneX",
+                "the OneX element is present but useOneX is not enabled",
+            ),
+            _ => (),
+        }
+
+        let enterprise = matches!(
+            authentication,
+            Authentication::Wpa
+                | Authentication::Wpa2
+                | Authentication::Wpa3
+                | Authentication::Wpa3Ent192
+                | Authentication::Wpa3Ent
+        );
+
+        if enterprise && !use_one_x {
+            validator.error(
+                "MSM/security/authEncryption/useOneX",
+                format!("{authentication:?} authentication requires useOneX to be enabled"),
+            );
+        }
+
+        if use_one_x
+            && !enterprise
+            && !(authentication == Authentication::Open && encryption == Encryption::Wep)
+        {
+            validator.error(
+                "MSM/security/authEncryption/useOneX",
+                format!("802.1X can not be used with {authentication:?} authentication"),
+            );
+        }
+
+        let requires_key = matches!(
+            authentication,
+            Authentication::WpaPsk | Authentication::Wpa2Psk | Authentication::Wpa3Sae
+        ) || (encryption == Encryption::Wep && !use_one_x);
+
+        match &security.shared_key {
+            Some(shared_key) => {
+                if !requires_key {
+                    validator.error(
+                        "MSM/security/sharedKey",
+                        format!(
+                            "a shared key can not be used with {authentication:?} authentication and {encryption:?} encryption"
+                        ),
+                    );
+                } else if !shared_key.protected {
+                    validate_key_material(
+                        validator,
+                        encryption,
+                        shared_key.key_type,
+                        &shared_key.key_material,
+                    );
+                }
+            }
+            None if requires_key => validator.error(
+                "MSM/security/sharedKey",
+                format!("{authentication:?} authentication requires a shared key"),
+            ),
+            None => (),
+        }
+
+        if let Some(key_index) = security.key_index {
+            if encryption != Encryption::Wep {
+                validator.error(
+                    "MSM/security/keyIndex",
+                    "a key index can only be used with WEP encryption",
+                );
+            } else if key_index > 3 {
+                validator.error(
+                    "MSM/security/keyIndex",
+                    format!("{key_index} is outside of the allowed range of 0 to 3"),
+                );
+            }
+        }
+
+        let pmk_settings = security.pmk_cache_mode.is_some()
+            || security.pmk_cache_ttl.is_some()
+            || security.pmk_cache_size.is_some()
+            || security.pre_auth_mode.is_some()
+            || security.pre_auth_throttle.is_some();
+
+        if pmk_settings && authentication != Authentication::Wpa2 {
+            validator.error(
+                "MSM/security",
+                "PMK caching and pre-authentication settings can only be used with WPA2 authentication",
+            );
+        }
+
+        validator.range("MSM/security/PMKCacheTTL", security.pmk_cache_ttl, 5, 1440);
+        validator.range("MSM/security/PMKCacheSize", security.pmk_cache_size, 1, 255);
+        validator.range(
+            "MSM/security/preAuthThrottle",
+            security.pre_auth_throttle,
+            1,
+            16,
+        );
+    }
+}
+
+/// Returns the encryption methods which can be used with an authentication method
+fn allowed_encryption(authentication: Authentication) -> &'static [Encryption] {
+    match authentication {
+        Authentication::Open => &[Encryption::None, Encryption::Wep],
+        Authentication::Shared => &[Encryption::Wep],
+        Authentication::Wpa
+        | Authentication::WpaPsk
+        | Authentication::Wpa2
+        | Authentication::Wpa2Psk => &[Encryption::Tkip, Encryption::Aes],
+        Authentication::Wpa3Sae | Authentication::Owe => &[Encryption::Aes, Encryption::Gcmp256],
+        Authentication::Wpa3Ent => &[Encryption::Aes, Encryption::Gcmp, Encryption::Gcmp256],
+        Authentication::Wpa3 | Authentication::Wpa3Ent192 => &[Encryption::Gcmp256],
+    }
+}
+
+/// Checks that unprotected key material has a valid length and format
+fn validate_key_material(
+    validator: &mut Validator,
+    encryption: Encryption,
+    key_type: KeyType,
+    key_material: &str,
+) {
+    const PATH: &str = "MSM/security/sharedKey/keyMaterial";
+
+    if encryption == Encryption::Wep {
+        let valid = match key_material.len() {
+            5 | 13 => true,
+            10 | 26 => crate::hex::decode(key_material).is_some(),
+            _ => false,
+        };
+
+        if !valid {
+            validator.error(
+                PATH,
+                "a WEP key must be 5 or 13 ASCII characters or 10 or 26 hex digits",
+            );
+        }
+
+        return;
+    }
+
+    match key_type {
+        KeyType::PassPhrase => {
+            if check_passphrase(key_material).is_err() {
+                validator.error(
+                    PATH,
+                    format!(
+                        "a passphrase must be between 8 and 63 printable ASCII characters but is {} characters long",
+                        key_material.len()
+                    ),
+                );
+            }
+        }
+        KeyType::NetworkKey => {
+            if parse_network_key(key_material).is_err() {
+                validator.error(PATH, "a network key must be 64 hex digits");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{profiles::WlanProfileBuilder, testing::example_profile};
+
+    /// Returns the paths of the diagnostics with the specified severity
+    fn paths(diagnostics: &[Diagnostic], severity: Severity) -> Vec<&str> {
+        diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .map(|diagnostic| diagnostic.path.as_str())
+            .collect()
+    }
+
+    /// Checks that the example profiles do not have any errors
+    #[test]
+    fn valid_example_profiles() {
+        for index in 0..3 {
+            assert_eq!(example_profile(index).validate(), []);
+        }
+    }
+
+    /// Checks that the mismatched hex and name SSID in the example profile is detected
+    #[test]
+    fn mismatched_ssid() {
+        let diagnostics = example_profile(3).validate();
+
+        assert_eq!(
+            paths(&diagnostics, Severity::Warning),
+            ["WLANProfile/SSIDConfig/SSID/name"]
+        );
+        assert_eq!(paths(&diagnostics, Severity::Error), Vec::<&str>::new());
+    }
+
+    /// Checks the passphrase length rules
+    #[test]
+    fn invalid_passphrase() {
+        let mut profile = WlanProfileBuilder::new("Passphrase")
+            .wpa2_psk("passphrase")
+            .build()
+            .expect("Failed to build profile");
+
+        let shared_key = profile.msm.security.shared_key.as_mut().unwrap();
+        shared_key.key_material = "short".to_string();
+        assert_eq!(
+            paths(&profile.validate(), Severity::Error),
+            ["WLANProfile/MSM/security/sharedKey/keyMaterial"]
+        );
+
+        let shared_key = profile.msm.security.shared_key.as_mut().unwrap();
+        shared_key.key_type = KeyType::NetworkKey;
+        shared_key.key_material = "0123456789abcdef".repeat(4);
+        assert_eq!(profile.validate(), []);
+
+        // Protected key material is encrypted so its length can not be checked
+        let shared_key = profile.msm.security.shared_key.as_mut().unwrap();
+        shared_key.protected = true;
+        shared_key.key_material = "01000000D08C9DDF".to_string();
+        assert_eq!(profile.validate(), []);
+    }
+
+    /// Checks that incompatible authentication and encryption methods are detected
+    #[test]
+    fn incompatible_encryption() {
+        let mut profile = example_profile(1);
+        profile.msm.security.auth_encryption.encryption = Encryption::Aes;
+
+        assert_eq!(
+            paths(&profile.validate(), Severity::Error),
+            ["WLANProfile/MSM/security/authEncryption/encryption"]
+        );
+    }
+
+    /// Checks that useOneX has to match the presence of the OneX element
+    #[test]
+    fn inconsistent_one_x() {
+        let mut profile = example_profile(0);
+        profile.msm.security.auth_encryption.use_one_x = Some(false);
+
+        assert_eq!(
+            paths(&profile.validate(), Severity::Error),
+            [
+                "WLANProfile/MSM/security/OneX",
+                "WLANProfile/MSM/security/authEncryption/useOneX"
+            ]
+        );
+    }
+
+    /// Checks the SSID length limit
+    #[test]
+    fn long_ssid() {
+        let mut profile = example_profile(1);
+        profile.ssid_config.ssid.name = Some("a".repeat(33));
+        profile.ssid_config.ssid.hex = Some(Ssid::new(vec![b'a'; 33]));
+
+        assert_eq!(
+            paths(&profile.validate(), Severity::Error),
+            [
+                "WLANProfile/SSIDConfig/SSID/hex",
+                "WLANProfile/SSIDConfig/SSID/name"
+            ]
+        );
+    }
+
+    /// Checks the PMK cache ranges
+    #[test]
+    fn pmk_cache_ranges() {
+        let mut profile = example_profile(0);
+        profile.msm.security.pmk_cache_ttl = Some(1);
+        profile.msm.security.pmk_cache_size = Some(256);
+
+        let diagnostics = profile.validate();
+        assert_eq!(
+            paths(&diagnostics, Severity::Error),
+            [
+                "WLANProfile/MSM/security/PMKCacheTTL",
+                "WLANProfile/MSM/security/PMKCacheSize"
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "error: WLANProfile/MSM/security/PMKCacheTTL: 1 is outside of the allowed range of 5 to 1440"
+        );
+    }
+}
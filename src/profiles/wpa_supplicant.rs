@@ -0,0 +1,988 @@
+use std::fmt::Write;
+
+use crate::{errors::WinWifiError, ssid::Ssid};
+
+use super::{
+    builder::WlanProfileBuilder,
+    convert::{export_ssid, Unconverted},
+    eap::{EapConfig, PeapConfig, PeapInnerMethod, TlsConfig, TtlsConfig, TtlsPhase2},
+    info::{
+        Authentication, ConnectionMode, ConnectionType, Encryption, KeyType, SharedKey, WlanProfile,
+    },
+    redact::Redaction,
+    validate::{Diagnostic, Severity},
+};
+
+/// CA certificate bundle used for server validation against the system CAs
+pub const SYSTEM_CA_CERT: &str = "/etc/ssl/certs/ca-certificates.crt";
+
+/// A `network={...}` block converted from a wireless profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WpaSupplicantExport {
+    /// The `network={...}` block
+    pub network: String,
+
+    /// Settings of the profile which have no wpa_supplicant equivalent and were left out
+    pub unconverted: Vec<Diagnostic>,
+}
+
+/// A wireless profile converted from a `network={...}` block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WpaSupplicantImport {
+    /// The converted profile
+    pub profile: WlanProfile,
+
+    /// Settings of the network block which have no profile equivalent and were left out
+    pub unconverted: Vec<Diagnostic>,
+}
+
+/// A value of a network block setting
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    /// A quoted string
+    Quoted(Vec<u8>),
+
+    /// An unquoted value like a number, a list of flags or a hex string
+    Raw(String),
+}
+
+/// Settings of a network block in the order they appear
+struct NetworkBlock {
+    entries: Vec<(String, Value)>,
+}
+
+impl NetworkBlock {
+    /// Returns the value of a setting and marks it as converted
+    fn take(&mut self, key: &str) -> Option<Value> {
+        let index = self.entries.iter().position(|(name, _)| name == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Returns the value of a setting as a string and marks it as converted
+    fn take_string(&mut self, key: &str) -> Result<Option<String>, WinWifiError> {
+        match self.take(key) {
+            Some(Value::Quoted(bytes)) => String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|_| invalid(key, "the value is not valid UTF-8")),
+            Some(Value::Raw(value)) => Ok(Some(value)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the value of a numeric setting and marks it as converted
+    fn take_number(&mut self, key: &str) -> Result<Option<u32>, WinWifiError> {
+        self.take_string(key)?
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| invalid(key, &format!("'{value}' is not a number")))
+            })
+            .transpose()
+    }
+}
+
+impl WlanProfile {
+    /// Converts the profile to a wpa_supplicant `network={...}` block. Passphrases are written
+    /// as quoted strings and raw network keys as hex. Settings which have no wpa_supplicant
+    /// equivalent are reported in the result
+    pub fn to_wpa_supplicant(
+        &self,
+        redaction: Redaction,
+    ) -> Result<WpaSupplicantExport, WinWifiError> {
+        let mut unconverted = Unconverted::default();
+
+        let ssid = export_ssid(self, &mut unconverted)?;
+
+        let profile = self.redacted(redaction);
+        let security = &profile.msm.security;
+        let auth_encryption = &security.auth_encryption;
+        let authentication = auth_encryption.authentication;
+        let encryption = auth_encryption.encryption;
+        let use_one_x = auth_encryption.use_one_x == Some(true);
+
+        let mut entries: Vec<(&str, String)> = vec![
+            ("id_str", quote(&profile.name)),
+            ("ssid", ssid_value(&ssid)),
+        ];
+
+        if profile.ssid_config.non_broadcast == Some(true) {
+            entries.push(("scan_ssid", "1".to_string()));
+        }
+
+        if profile.connection_type == ConnectionType::Ibss {
+            entries.push(("mode", "1".to_string()));
+        }
+
+        if profile.connection_mode == Some(ConnectionMode::Manual) {
+            entries.push(("disabled", "1".to_string()));
+        }
+
+        let key_mgmt = match authentication {
+            Authentication::Open | Authentication::Shared if use_one_x => "IEEE8021X",
+            Authentication::Open | Authentication::Shared => "NONE",
+            Authentication::WpaPsk | Authentication::Wpa2Psk => "WPA-PSK",
+            Authentication::Wpa3Sae if auth_encryption.transition_mode == Some(true) => {
+                "WPA-PSK SAE"
+            }
+            Authentication::Wpa3Sae => "SAE",
+            Authentication::Owe => "OWE",
+            Authentication::Wpa | Authentication::Wpa2 => "WPA-EAP",
+            Authentication::Wpa3Ent => "WPA-EAP WPA-EAP-SHA256",
+            Authentication::Wpa3 | Authentication::Wpa3Ent192 => "WPA-EAP-SUITE-B-192",
+        };
+        entries.push(("key_mgmt", key_mgmt.to_string()));
+
+        match authentication {
+            Authentication::Wpa | Authentication::WpaPsk => {
+                entries.push(("proto", "WPA".to_string()))
+            }
+            Authentication::Wpa2 | Authentication::Wpa2Psk => {
+                entries.push(("proto", "RSN".to_string()))
+            }
+            _ => (),
+        }
+
+        let pairwise = match encryption {
+            Encryption::None | Encryption::Wep => None,
+            Encryption::Tkip => Some("TKIP"),
+            Encryption::Aes => Some("CCMP"),
+            Encryption::Gcmp => Some("GCMP"),
+            Encryption::Gcmp256 => Some("GCMP-256"),
+        };
+        if let Some(pairwise) = pairwise {
+            entries.push(("pairwise", pairwise.to_string()));
+        }
+
+        match authentication {
+            Authentication::Wpa3Sae if auth_encryption.transition_mode == Some(true) => {
+                entries.push(("ieee80211w", "1".to_string()))
+            }
+            Authentication::Wpa3Sae
+            | Authentication::Owe
+            | Authentication::Wpa3
+            | Authentication::Wpa3Ent192
+            | Authentication::Wpa3Ent => entries.push(("ieee80211w", "2".to_string())),
+            _ => (),
+        }
+
+        if authentication == Authentication::Shared {
+            entries.push(("auth_alg", "SHARED".to_string()));
+        }
+
+        match &security.shared_key {
+            Some(shared_key) if shared_key.protected => unconverted.profile(
+                "MSM/security/sharedKey/keyMaterial",
+                "the key material is encrypted by Windows and has to be entered again",
+            ),
+            Some(shared_key) if encryption == Encryption::Wep => {
+                let key_index = security.key_index.unwrap_or_default();
+                let key = if matches!(shared_key.key_material.len(), 10 | 26) {
+                    shared_key.key_material.clone()
+                } else {
+                    quote(&shared_key.key_material)
+                };
+                entries.push((wep_key_name(key_index), key));
+                entries.push(("wep_tx_keyidx", key_index.to_string()));
+            }
+            Some(shared_key) => entries.push((
+                "psk",
+                match shared_key.key_type {
+                    KeyType::NetworkKey => shared_key.key_material.clone(),
+                    // The psk field does not support the escaped P"..." format and a
+                    // passphrase can not contain a newline
+                    KeyType::PassPhrase => format!("\"{}\"", shared_key.key_material),
+                },
+            )),
+            None => (),
+        }
+
+        if let Some(one_x) = security.one_x.as_ref().filter(|_| use_one_x) {
+            match &one_x.eap_config {
+                Some(eap_config) => convert_eap_config(eap_config, &mut entries, &mut unconverted),
+                None => unconverted.profile(
+                    "MSM/security/OneX",
+                    "the profile does not contain an EAP configuration",
+                ),
+            }
+
+            unconverted.one_x(
+                one_x,
+                "wpa_supplicant does not have an equivalent 802.1X setting",
+            );
+        }
+
+        if let Some(mac_randomization) = &profile.mac_randomization {
+            entries.push((
+                "mac_addr",
+                if mac_randomization.enable_randomization {
+                    "1"
+                } else {
+                    "0"
+                }
+                .to_string(),
+            ));
+            if mac_randomization.randomization_seed.is_some() {
+                unconverted.profile(
+                    "MacRandomization/randomizationSeed",
+                    "wpa_supplicant generates the randomized MAC address itself",
+                );
+            }
+        }
+
+        const NO_EQUIVALENT: &str = "wpa_supplicant does not have an equivalent setting";
+        for (path, present) in [
+            ("autoSwitch", profile.auto_switch.is_some()),
+            (
+                "MSM/security/authEncryption/FIPSMode",
+                auth_encryption.fips_mode == Some(true),
+            ),
+        ] {
+            if present {
+                unconverted.profile(path, NO_EQUIVALENT);
+            }
+        }
+        unconverted.pmk_cache(security, NO_EQUIVALENT);
+
+        let mut network = String::from("network={\n");
+        for (key, value) in entries {
+            let _ = writeln!(network, "\t{key}={value}");
+        }
+        network.push_str("}\n");
+
+        Ok(WpaSupplicantExport {
+            network,
+            unconverted: unconverted.diagnostics,
+        })
+    }
+
+    /// Converts a single wpa_supplicant `network={...}` block to a wireless profile. Settings
+    /// which have no profile equivalent are reported in the result
+    pub fn from_wpa_supplicant(network: &str) -> Result<WpaSupplicantImport, WinWifiError> {
+        let mut imports = import_networks(network)?;
+
+        if imports.len() != 1 {
+            return Err(WinWifiError::InvalidProfile(format!(
+                "expected a single network block but found {}",
+                imports.len()
+            )));
+        }
+
+        Ok(imports.remove(0))
+    }
+}
+
+/// Converts every `network={...}` block of a wpa_supplicant configuration file to a wireless
+/// profile. Settings outside of the network blocks are ignored
+pub fn import_networks(config: &str) -> Result<Vec<WpaSupplicantImport>, WinWifiError> {
+    parse_networks(config)?
+        .into_iter()
+        .map(import_network)
+        .collect()
+}
+
+/// Converts the settings of a network block to a wireless profile
+fn import_network(mut network: NetworkBlock) -> Result<WpaSupplicantImport, WinWifiError> {
+    let mut unconverted = Unconverted::default();
+
+    let ssid = match network.take("ssid") {
+        Some(Value::Quoted(bytes)) => Ssid::new(bytes),
+        Some(Value::Raw(hex)) => Ssid::from_hex(&hex)?,
+        None => return Err(invalid("ssid", "the network block does not have an SSID")),
+    };
+
+    let name = network
+        .take_string("id_str")?
+        .unwrap_or_else(|| ssid.to_string());
+
+    let hidden = network.take_number("scan_ssid")?.unwrap_or_default() != 0;
+    let disabled = network.take_number("disabled")?.unwrap_or_default() != 0;
+    let connection_type = match network.take_number("mode")?.unwrap_or_default() {
+        0 => ConnectionType::Ess,
+        1 => ConnectionType::Ibss,
+        mode => return Err(invalid("mode", &format!("mode {mode} is not supported"))),
+    };
+
+    let key_mgmt = network.take_string("key_mgmt")?;
+    let proto = network.take_string("proto")?.unwrap_or_default();
+    let pairwise = network.take_string("pairwise")?;
+    let ieee80211w = network.take_number("ieee80211w")?.unwrap_or_default();
+    let auth_alg = network.take_string("auth_alg")?;
+    let psk = network.take("psk");
+    let eap = network.take_string("eap")?;
+
+    let key_mgmt = key_mgmt.unwrap_or_else(|| {
+        if psk.is_some() {
+            "WPA-PSK".to_string()
+        } else if eap.is_some() {
+            "WPA-EAP".to_string()
+        } else {
+            "NONE".to_string()
+        }
+    });
+    let key_mgmt = key_mgmt.split_whitespace().collect::<Vec<_>>();
+    let wpa1_only = proto.split_whitespace().eq(["WPA"]);
+
+    let (authentication, transition_mode) = if key_mgmt.contains(&"SAE") {
+        (Authentication::Wpa3Sae, key_mgmt.contains(&"WPA-PSK"))
+    } else if key_mgmt.contains(&"WPA-PSK") || key_mgmt.contains(&"WPA-PSK-SHA256") {
+        if wpa1_only {
+            (Authentication::WpaPsk, false)
+        } else {
+            (Authentication::Wpa2Psk, false)
+        }
+    } else if key_mgmt.contains(&"WPA-EAP-SUITE-B-192") {
+        (Authentication::Wpa3Ent192, false)
+    } else if key_mgmt.contains(&"WPA-EAP-SHA256")
+        || (key_mgmt.contains(&"WPA-EAP") && ieee80211w == 2)
+    {
+        (Authentication::Wpa3Ent, false)
+    } else if key_mgmt.contains(&"WPA-EAP") {
+        if wpa1_only {
+            (Authentication::Wpa, false)
+        } else {
+            (Authentication::Wpa2, false)
+        }
+    } else if key_mgmt.contains(&"OWE") {
+        (Authentication::Owe, false)
+    } else if key_mgmt.contains(&"IEEE8021X") || key_mgmt.contains(&"NONE") {
+        if auth_alg.as_deref() == Some("SHARED") {
+            (Authentication::Shared, false)
+        } else {
+            (Authentication::Open, false)
+        }
+    } else {
+        return Err(invalid(
+            "key_mgmt",
+            &format!("'{}' is not supported", key_mgmt.join(" ")),
+        ));
+    };
+
+    let mut builder = WlanProfileBuilder::new(name)
+        .ssid(ssid)
+        .connection_type(connection_type)
+        .hidden(hidden)
+        .auto_connect(!disabled);
+
+    let enterprise = eap.is_some() || key_mgmt.contains(&"IEEE8021X");
+    if enterprise {
+        builder = builder
+            .wpa2_enterprise()
+            .eap_config(import_eap_config(&mut network, eap.as_deref())?);
+    }
+
+    if let Some(mac_addr) = network.take_number("mac_addr")? {
+        builder = builder.mac_randomization(mac_addr != 0);
+    }
+
+    let mut profile = builder.build()?;
+    let security = &mut profile.msm.security;
+
+    if !matches!(authentication, Authentication::Wpa2) {
+        security.pmk_cache_mode = None;
+        security.pmk_cache_ttl = None;
+        security.pmk_cache_size = None;
+        security.pre_auth_mode = None;
+    }
+
+    let encryption = match pairwise
+        .as_deref()
+        .and_then(|pairwise| pairwise.split_whitespace().next())
+    {
+        Some("CCMP") => Encryption::Aes,
+        Some("TKIP") => Encryption::Tkip,
+        Some("GCMP") => Encryption::Gcmp,
+        Some("GCMP-256") => Encryption::Gcmp256,
+        Some(pairwise) => {
+            return Err(invalid(
+                "pairwise",
+                &format!("'{pairwise}' is not supported"),
+            ))
+        }
+        None => match authentication {
+            Authentication::Open | Authentication::Shared => Encryption::None,
+            Authentication::Wpa | Authentication::WpaPsk => Encryption::Tkip,
+            Authentication::Wpa3 | Authentication::Wpa3Ent192 => Encryption::Gcmp256,
+            _ => Encryption::Aes,
+        },
+    };
+
+    security.auth_encryption.authentication = authentication;
+    security.auth_encryption.encryption = encryption;
+    security.auth_encryption.transition_mode = transition_mode.then_some(true);
+
+    if matches!(
+        authentication,
+        Authentication::Open | Authentication::Shared
+    ) {
+        let wep_key =
+            (0..4u8).find_map(|index| network.take(wep_key_name(index)).map(|key| (index, key)));
+
+        if let Some((index, key)) = wep_key {
+            security.auth_encryption.encryption = Encryption::Wep;
+            let key_index = network
+                .take_number("wep_tx_keyidx")?
+                .unwrap_or(index as u32);
+            security.key_index = Some(key_index as u8);
+            security.shared_key = Some(SharedKey {
+                key_type: KeyType::NetworkKey,
+                protected: false,
+                key_material: match key {
+                    Value::Quoted(bytes) => String::from_utf8(bytes)
+                        .map_err(|_| invalid("wep_key", "the value is not valid UTF-8"))?,
+                    Value::Raw(hex) => hex,
+                },
+            });
+        } else if key_mgmt.contains(&"IEEE8021X") {
+            security.auth_encryption.encryption = Encryption::Wep;
+        }
+    }
+
+    match psk {
+        Some(psk) if !enterprise => {
+            security.shared_key = Some(match psk {
+                Value::Quoted(bytes) => SharedKey {
+                    key_type: KeyType::PassPhrase,
+                    protected: false,
+                    key_material: String::from_utf8(bytes)
+                        .map_err(|_| invalid("psk", "the value is not valid UTF-8"))?,
+                },
+                Value::Raw(hex) => SharedKey {
+                    key_type: KeyType::NetworkKey,
+                    protected: false,
+                    key_material: hex,
+                },
+            });
+        }
+        Some(_) => unconverted.push(
+            "network/psk",
+            "a pre-shared key can not be used with 802.1X authentication",
+        ),
+        None => (),
+    }
+
+    for (key, _) in network.entries {
+        unconverted.push(
+            format!("network/{key}"),
+            "the setting does not have a wireless profile equivalent",
+        );
+    }
+
+    if let Some(error) = profile
+        .validate()
+        .into_iter()
+        .find(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        return Err(WinWifiError::InvalidProfile(error.to_string()));
+    }
+
+    Ok(WpaSupplicantImport {
+        profile,
+        unconverted: unconverted.diagnostics,
+    })
+}
+
+/// Converts the EAP settings of a network block
+fn import_eap_config(
+    network: &mut NetworkBlock,
+    eap: Option<&str>,
+) -> Result<EapConfig, WinWifiError> {
+    let phase2 = network.take_string("phase2")?;
+    let phase2 = phase2.as_deref().map(|phase2| {
+        phase2
+            .trim_start_matches("autheap=")
+            .trim_start_matches("auth=")
+    });
+
+    let mut eap_config = match eap.map(|eap| eap.split_whitespace().next().unwrap_or_default()) {
+        Some("PEAP") | None => match phase2 {
+            Some("MSCHAPV2") | None => EapConfig::Peap(PeapConfig::default()),
+            Some("TLS") => EapConfig::Peap(PeapConfig {
+                inner_method: PeapInnerMethod::Tls(Box::default()),
+                ..Default::default()
+            }),
+            Some(phase2) => {
+                return Err(invalid(
+                    "phase2",
+                    &format!("'{phase2}' is not supported with PEAP"),
+                ))
+            }
+        },
+        Some("TLS") => EapConfig::Tls(TlsConfig::default()),
+        Some("TTLS") => EapConfig::Ttls(TtlsConfig {
+            phase2: match phase2 {
+                Some("PAP") => TtlsPhase2::Pap,
+                Some("CHAP") => TtlsPhase2::Chap,
+                Some("MSCHAP") => TtlsPhase2::MsChap,
+                Some("MSCHAPV2") | None => TtlsPhase2::MsChapV2 {
+                    use_winlogon_credentials: false,
+                },
+                Some(phase2) => {
+                    return Err(invalid(
+                        "phase2",
+                        &format!("'{phase2}' is not supported with EAP-TTLS"),
+                    ))
+                }
+            },
+            anonymous_identity: network.take_string("anonymous_identity")?,
+            ..Default::default()
+        }),
+        Some(eap) => {
+            return Err(invalid(
+                "eap",
+                &format!("EAP method '{eap}' is not supported"),
+            ))
+        }
+    };
+
+    let domain_suffix_match = network.take_string("domain_suffix_match")?;
+    let ca_cert = network.take_string("ca_cert")?;
+    let validated = ca_cert.is_some() || domain_suffix_match.is_some();
+
+    if let Some(server_validation) = eap_config.server_validation_mut() {
+        server_validation.server_names = domain_suffix_match.unwrap_or_default();
+        server_validation.perform_server_validation = Some(validated);
+    }
+
+    // Any other CA certificate has to be imported into the Windows certificate store
+    if let Some(ca_cert) = ca_cert.filter(|ca_cert| ca_cert != SYSTEM_CA_CERT) {
+        network
+            .entries
+            .push(("ca_cert".to_string(), Value::Quoted(ca_cert.into_bytes())));
+    }
+
+    Ok(eap_config)
+}
+
+/// Adds the EAP settings of a profile to the network block
+fn convert_eap_config(
+    eap_config: &EapConfig,
+    entries: &mut Vec<(&str, String)>,
+    unconverted: &mut Unconverted,
+) {
+    const PATH: &str = "MSM/security/OneX/EAPConfig";
+
+    match eap_config {
+        EapConfig::Peap(peap) => {
+            entries.push(("eap", "PEAP".to_string()));
+            match &peap.inner_method {
+                PeapInnerMethod::MsChapV2 {
+                    use_winlogon_credentials,
+                } => {
+                    entries.push(("phase2", quote("auth=MSCHAPV2")));
+                    if *use_winlogon_credentials {
+                        unconverted.profile(
+                            &format!("{PATH}/UseWinLogonCredentials"),
+                            "the Windows logon credentials can not be used",
+                        );
+                    }
+                }
+                PeapInnerMethod::Tls(_) => {
+                    entries.push(("phase2", quote("auth=TLS")));
+                    unconverted.profile(
+                        &format!("{PATH}/CredentialsSource"),
+                        "certificates from the Windows certificate store have to be exported and set as client_cert2",
+                    );
+                }
+                PeapInnerMethod::Other(_) => unconverted.profile(
+                    &format!("{PATH}/Eap"),
+                    "the inner EAP method is not supported",
+                ),
+            }
+        }
+        EapConfig::Tls(_) => {
+            entries.push(("eap", "TLS".to_string()));
+            unconverted.profile(
+                &format!("{PATH}/CredentialsSource"),
+                "certificates from the Windows certificate store have to be exported and set as client_cert",
+            );
+        }
+        EapConfig::Ttls(ttls) => {
+            entries.push(("eap", "TTLS".to_string()));
+            let phase2 = match &ttls.phase2 {
+                TtlsPhase2::Pap => Some("auth=PAP"),
+                TtlsPhase2::Chap => Some("auth=CHAP"),
+                TtlsPhase2::MsChap => Some("auth=MSCHAP"),
+                TtlsPhase2::MsChapV2 { .. } => Some("auth=MSCHAPV2"),
+                TtlsPhase2::Other(_) => None,
+            };
+            match phase2 {
+                Some(phase2) => entries.push(("phase2", quote(phase2))),
+                None => unconverted.profile(
+                    &format!("{PATH}/Phase2Authentication"),
+                    "the inner authentication method is not supported",
+                ),
+            }
+            if let Some(anonymous_identity) = &ttls.anonymous_identity {
+                entries.push(("anonymous_identity", quote(anonymous_identity)));
+            }
+        }
+        EapConfig::Other(other) => {
+            unconverted.profile(
+                PATH,
+                format!("EAP type {} is not supported", other.method.eap_type),
+            );
+            return;
+        }
+    }
+
+    let Some(server_validation) = eap_config.server_validation() else {
+        return;
+    };
+
+    if server_validation.perform_server_validation == Some(false) {
+        return;
+    }
+
+    entries.push(("ca_cert", quote(SYSTEM_CA_CERT)));
+
+    if !server_validation.server_names.is_empty() {
+        entries.push((
+            "domain_suffix_match",
+            quote(&server_validation.server_names),
+        ));
+    }
+
+    if !server_validation.trusted_root_ca.is_empty() {
+        unconverted.profile(
+            &format!("{PATH}/TrustedRootCA"),
+            "certificate thumbprints can not be used. The CA certificates have to be exported and set as ca_cert",
+        );
+    }
+}
+
+/// Splits a configuration file into its network blocks
+fn parse_networks(config: &str) -> Result<Vec<NetworkBlock>, WinWifiError> {
+    let mut networks = Vec::new();
+    let mut current: Option<NetworkBlock> = None;
+
+    for (index, line) in config.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let syntax_error =
+            |message: &str| WinWifiError::InvalidProfile(format!("line {}: {message}", index + 1));
+
+        match &mut current {
+            None => {
+                if line.replace(' ', "") == "network={" {
+                    current = Some(NetworkBlock {
+                        entries: Vec::new(),
+                    });
+                }
+            }
+            Some(network) => {
+                if line == "}" {
+                    networks.extend(current.take());
+                    continue;
+                }
+
+                let (key, value) = line
+                    .split_once('=')
+                    .ok_or_else(|| syntax_error("expected a 'key=value' setting"))?;
+                let value = parse_value(value.trim()).ok_or_else(|| {
+                    syntax_error(&format!("the value of '{}' is not valid", key.trim()))
+                })?;
+                network.entries.push((key.trim().to_string(), value));
+            }
+        }
+    }
+
+    if current.is_some() {
+        return Err(WinWifiError::InvalidProfile(
+            "a network block is not closed".to_string(),
+        ));
+    }
+
+    Ok(networks)
+}
+
+/// Parses a setting value. Quoted strings may use the `P"..."` format with escape sequences
+fn parse_value(value: &str) -> Option<Value> {
+    if let Some(quoted) = value.strip_prefix("P\"") {
+        return unescape(quoted.strip_suffix('"')?).map(Value::Quoted);
+    }
+
+    if let Some(quoted) = value.strip_prefix('"') {
+        return Some(Value::Quoted(quoted.strip_suffix('"')?.as_bytes().to_vec()));
+    }
+
+    Some(Value::Raw(value.to_string()))
+}
+
+/// Decodes the escape sequences of a `P"..."` string
+fn unescape(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut chars = value.bytes();
+
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+
+        match chars.next()? {
+            b'n' => bytes.push(b'\n'),
+            b'r' => bytes.push(b'\r'),
+            b't' => bytes.push(b'\t'),
+            b'e' => bytes.push(0x1b),
+            b'x' => {
+                let hex = [chars.next()?, chars.next()?];
+                bytes.extend(crate::hex::decode(std::str::from_utf8(&hex).ok()?)?);
+            }
+            byte => bytes.push(byte),
+        }
+    }
+
+    Some(bytes)
+}
+
+/// Quotes a string value, using the `P"..."` format if it needs escaping
+fn quote(value: &str) -> String {
+    if value
+        .bytes()
+        .all(|byte| (0x20..=0x7e).contains(&byte) && byte != b'"' && byte != b'\\')
+    {
+        return format!("\"{value}\"");
+    }
+
+    let mut quoted = String::from("P\"");
+    for byte in value.bytes() {
+        match byte {
+            b'"' => quoted.push_str("\\\""),
+            b'\\' => quoted.push_str("\\\\"),
+            b'\n' => quoted.push_str("\\n"),
+            b'\r' => quoted.push_str("\\r"),
+            b'\t' => quoted.push_str("\\t"),
+            0x20..=0x7e => quoted.push(byte as char),
+            byte => {
+                let _ = write!(quoted, "\\x{byte:02x}");
+            }
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats the SSID as a quoted string, or as hex if it is not printable
+fn ssid_value(ssid: &Ssid) -> String {
+    match ssid.to_str() {
+        Some(name) if name.bytes().all(|byte| (0x20..=0x7e).contains(&byte)) => quote(name),
+        _ => ssid.to_hex().to_ascii_lowercase(),
+    }
+}
+
+/// Returns the name of the WEP key setting with the specified index
+fn wep_key_name(index: u8) -> &'static str {
+    match index {
+        1 => "wep_key1",
+        2 => "wep_key2",
+        3 => "wep_key3",
+        _ => "wep_key0",
+    }
+}
+
+/// Creates an error for an invalid network block setting
+fn invalid(key: &str, reason: &str) -> WinWifiError {
+    WinWifiError::InvalidProfile(format!("{key}: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::example_profile;
+
+    /// Checks the conversion of a hidden WPA2-Personal profile
+    #[test]
+    fn export_psk_profile() {
+        let mut profile = example_profile(3);
+        profile.ssid_config.ssid.name = None;
+
+        let export = profile
+            .to_wpa_supplicant(Redaction::Reveal)
+            .expect("Failed to convert profile");
+
+        assert_eq!(
+            export.network,
+            "network={
+\tid_str=\"REDACTED WPA2PSK NONBROADCAST\"
+\tssid=\"REDACTED WPA2PSK NONBROADCAST\"
+\tscan_ssid=1
+\tkey_mgmt=WPA-PSK
+\tproto=RSN
+\tpairwise=CCMP
+\tpsk=\"password\"
+\tmac_addr=0
+}
+"
+        );
+
+        let masked = profile
+            .to_wpa_supplicant(Redaction::default())
+            .expect("Failed to convert profile");
+        assert!(masked.network.contains("\tpsk=\"********\"\n"));
+    }
+
+    /// Checks that passphrases with quotes and backslashes are written without escaping
+    #[test]
+    fn export_passphrase_unescaped() {
+        let profile = WlanProfileBuilder::new("Quoted")
+            .wpa2_psk(r#"pass"word\x"#)
+            .build()
+            .expect("Failed to build profile");
+
+        let export = profile
+            .to_wpa_supplicant(Redaction::Reveal)
+            .expect("Failed to convert profile");
+        assert!(export.network.contains("\tpsk=\"pass\"word\\x\"\n"));
+
+        let import = WlanProfile::from_wpa_supplicant(&export.network)
+            .expect("Failed to convert network block");
+        assert_eq!(import.profile, profile);
+    }
+
+    /// Checks the conversion of the example PEAP profile
+    #[test]
+    fn export_peap_profile() {
+        let export = example_profile(0)
+            .to_wpa_supplicant(Redaction::Mask)
+            .expect("Failed to convert profile");
+
+        for entry in [
+            "\tkey_mgmt=WPA-EAP\n",
+            "\teap=PEAP\n",
+            "\tphase2=\"auth=MSCHAPV2\"\n",
+            "\tca_cert=\"/etc/ssl/certs/ca-certificates.crt\"\n",
+        ] {
+            assert!(export.network.contains(entry), "missing {entry}");
+        }
+
+        assert!(export
+            .unconverted
+            .iter()
+            .any(|diagnostic| diagnostic.path == "WLANProfile/MSM/security/PMKCacheTTL"));
+    }
+
+    /// Checks that converting profiles to network blocks and back keeps their settings
+    #[test]
+    fn round_trip() {
+        let profiles = [
+            WlanProfileBuilder::new("Open").open().build(),
+            WlanProfileBuilder::new("Hidden")
+                .ssid("Hidden Network")
+                .wpa2_psk("passphrase")
+                .hidden(true)
+                .auto_connect(false)
+                .build(),
+            WlanProfileBuilder::new("SAE")
+                .wpa3_sae("passphrase")
+                .build(),
+            WlanProfileBuilder::new("Transition")
+                .wpa2_wpa3_transition("passphrase")
+                .build(),
+            WlanProfileBuilder::new("PEAP").wpa2_enterprise().build(),
+            WlanProfileBuilder::new("TTLS")
+                .wpa2_enterprise()
+                .eap_config(EapConfig::Ttls(TtlsConfig {
+                    phase2: TtlsPhase2::Pap,
+                    anonymous_identity: Some("anonymous".to_string()),
+                    ..Default::default()
+                }))
+                .build(),
+        ];
+
+        for profile in profiles {
+            let mut profile = profile.expect("Failed to build profile");
+            if let Some(server_validation) = profile
+                .msm
+                .security
+                .one_x
+                .as_mut()
+                .and_then(|one_x| one_x.eap_config.as_mut())
+                .and_then(|eap_config| eap_config.server_validation_mut())
+            {
+                server_validation.server_names = "radius.example.com".to_string();
+                server_validation.perform_server_validation = Some(true);
+            }
+
+            let export = profile
+                .to_wpa_supplicant(Redaction::Reveal)
+                .expect("Failed to convert profile");
+            let import = WlanProfile::from_wpa_supplicant(&export.network)
+                .expect("Failed to convert network block");
+
+            assert_eq!(import.profile, profile, "{}", export.network);
+            assert_eq!(import.unconverted, []);
+        }
+    }
+
+    /// Checks the conversion of a hand written configuration file
+    #[test]
+    fn import_config_file() {
+        const CONFIG: &str = r#"ctrl_interface=/run/wpa_supplicant
+update_config=1
+
+network={
+    ssid=P"caf\xc3\xa9 \"guest\""
+    key_mgmt=NONE
+    priority=5
+}
+
+# Hex SSID and raw network key
+network={
+	ssid=0102ff
+	psk=0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef
+	ca_cert="/etc/ca.pem"
+}
+"#;
+
+        let imports = import_networks(CONFIG).expect("Failed to convert configuration file");
+        assert_eq!(imports.len(), 2);
+
+        let open = &imports[0];
+        assert_eq!(open.profile.name, "café \"guest\"");
+        assert_eq!(
+            open.profile.msm.security.auth_encryption.authentication,
+            Authentication::Open
+        );
+        assert_eq!(
+            open.unconverted
+                .iter()
+                .map(|diagnostic| diagnostic.path.as_str())
+                .collect::<Vec<_>>(),
+            ["network/priority"]
+        );
+
+        let psk = &imports[1];
+        assert_eq!(
+            psk.profile.ssid_config.ssid.hex,
+            Some(Ssid::new(vec![1, 2, 0xff]))
+        );
+        assert_eq!(psk.profile.ssid_config.ssid.name, None);
+        let shared_key = psk.profile.msm.security.shared_key.as_ref().unwrap();
+        assert_eq!(shared_key.key_type, KeyType::NetworkKey);
+        assert_eq!(
+            psk.unconverted
+                .iter()
+                .map(|diagnostic| diagnostic.path.as_str())
+                .collect::<Vec<_>>(),
+            ["network/ca_cert"]
+        );
+    }
+
+    /// Makes sure that invalid network blocks are rejected
+    #[test]
+    fn invalid_network() {
+        assert!(WlanProfile::from_wpa_supplicant("network={\n\tssid=\"a\"\n").is_err());
+        assert!(WlanProfile::from_wpa_supplicant("network={\n\tkey_mgmt=NONE\n}").is_err());
+        assert!(
+            WlanProfile::from_wpa_supplicant("network={\n\tssid=\"a\"\n\tpsk=\"short\"\n}")
+                .is_err()
+        );
+        assert!(WlanProfile::from_wpa_supplicant(
+            "network={\n\tssid=\"a\"\n\tkey_mgmt=WPA-EAP\n\teap=FAST\n}"
+        )
+        .is_err());
+    }
+}
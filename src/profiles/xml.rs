@@ -0,0 +1,383 @@
+use std::fmt;
+
+use quick_xml::{
+    events::{attributes::Attribute, BytesDecl, BytesText, Event},
+    DeError, Reader, Writer,
+};
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// Namespace of the base wireless profile schema
+pub const WLAN_PROFILE_NAMESPACE: &str = "http://www.microsoft.com/networking/WLAN/profile/v1";
+
+/// Namespace of the version 2 wireless profile schema extensions
+pub const WLAN_PROFILE_V2_NAMESPACE: &str = "http://www.microsoft.com/networking/WLAN/profile/v2";
+
+/// Namespace of the version 3 wireless profile schema extensions
+pub const WLAN_PROFILE_V3_NAMESPACE: &str = "http://www.microsoft.com/networking/WLAN/profile/v3";
+
+/// Namespace of the version 4 wireless profile schema extensions
+pub const WLAN_PROFILE_V4_NAMESPACE: &str = "http://www.microsoft.com/networking/WLAN/profile/v4";
+
+/// Namespace of the 802.1X profile schema
+pub const ONEX_NAMESPACE: &str = "http://www.microsoft.com/networking/OneX/v1";
+
+/// Namespaces which Windows declares on the elements of a wireless profile
+const ELEMENT_NAMESPACES: &[(&[&str], &str)] = &[
+    (&["WLANProfile"], WLAN_PROFILE_NAMESPACE),
+    (
+        &[
+            "WLANProfile",
+            "MSM",
+            "security",
+            "authEncryption",
+            "FIPSMode",
+        ],
+        WLAN_PROFILE_V2_NAMESPACE,
+    ),
+    (
+        &[
+            "WLANProfile",
+            "MSM",
+            "security",
+            "authEncryption",
+            "transitionMode",
+        ],
+        WLAN_PROFILE_V4_NAMESPACE,
+    ),
+    (&["WLANProfile", "MSM", "security", "OneX"], ONEX_NAMESPACE),
+    (
+        &["WLANProfile", "MacRandomization"],
+        WLAN_PROFILE_V3_NAMESPACE,
+    ),
+];
+
+/// An XML element which is kept as is, including its namespace declarations
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XmlElement {
+    /// Local name of the element. The name of the field is used instead when the element is
+    /// stored in a named field
+    pub name: String,
+
+    /// Attributes of the element including any `xmlns` declarations
+    pub attributes: Vec<(String, String)>,
+
+    /// Text content of the element
+    pub text: Option<String>,
+
+    /// Child elements in document order
+    pub children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    /// Creates a new empty element
+    pub fn new(name: impl Into<String>) -> XmlElement {
+        XmlElement {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the value of an attribute
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the first child element with the specified name
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Returns an iterator over the child elements with the specified name
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Returns the text content of the first child element with the specified name
+    pub fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).and_then(|child| child.text.as_deref())
+    }
+
+    /// Declares a default namespace on the element
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.attributes
+            .push(("xmlns".to_string(), namespace.to_string()));
+        self
+    }
+
+    /// Sets the text content of the element
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Appends a child element
+    pub fn with_child(mut self, child: XmlElement) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+impl<'de> Deserialize<'de> for XmlElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct XmlElementVisitor;
+
+        impl<'de> Visitor<'de> for XmlElementVisitor {
+            type Value = XmlElement;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an XML element")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(XmlElement {
+                    text: Some(v.to_string()),
+                    ..Default::default()
+                })
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut element = XmlElement::default();
+
+                while let Some(key) = map.next_key::<String>()? {
+                    if let Some(attribute) = key.strip_prefix('@') {
+                        element
+                            .attributes
+                            .push((attribute.to_string(), map.next_value()?));
+                    } else if key == "$text" {
+                        element.text = Some(map.next_value()?);
+                    } else {
+                        let mut child: XmlElement = map.next_value()?;
+                        child.name = key;
+                        element.children.push(child);
+                    }
+                }
+
+                Ok(element)
+            }
+        }
+
+        deserializer.deserialize_map(XmlElementVisitor)
+    }
+}
+
+impl Serialize for XmlElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+
+        for (name, value) in &self.attributes {
+            map.serialize_entry(&format!("@{name}"), value)?;
+        }
+
+        if let Some(text) = &self.text {
+            map.serialize_entry("$text", text)?;
+        }
+
+        for child in &self.children {
+            map.serialize_entry(&child.name, child)?;
+        }
+
+        map.end()
+    }
+}
+
+/// Formats a serialized wireless profile the way Windows does. Adds the XML declaration, the
+/// schema namespaces and indentation. Whitespace between elements is replaced with indentation,
+/// the text of leaf elements is kept as is
+pub(crate) fn format_profile_document(xml: &str) -> Result<String, DeError> {
+    let mut reader = Reader::from_str(xml);
+
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", None, None)))?;
+
+    let mut path: Vec<String> = Vec::new();
+
+    // Whitespace-only text directly after a start tag, which is only written if the element ends
+    // right after it
+    let mut leaf_whitespace = None;
+
+    loop {
+        let event = reader.read_event()?;
+        let whitespace = leaf_whitespace.take();
+
+        match event {
+            Event::Start(mut element) => {
+                path.push(String::from_utf8_lossy(element.local_name().as_ref()).into_owned());
+                add_namespace(&path, &mut element)?;
+                writer.write_event(Event::Start(element))?;
+                leaf_whitespace = Some(BytesText::new(""));
+            }
+            Event::Empty(mut element) => {
+                path.push(String::from_utf8_lossy(element.local_name().as_ref()).into_owned());
+                add_namespace(&path, &mut element)?;
+                writer.write_event(Event::Empty(element))?;
+                path.pop();
+            }
+            Event::End(element) => {
+                if let Some(text) = whitespace.filter(|text| !text.is_empty()) {
+                    writer.write_event(Event::Text(text))?;
+                }
+                path.pop();
+                writer.write_event(Event::End(element))?;
+            }
+            Event::Text(text) if text.iter().all(u8::is_ascii_whitespace) => {
+                leaf_whitespace = whitespace.map(|_| text.into_owned());
+            }
+            Event::Decl(_) => (),
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+    }
+
+    let mut document = String::from_utf8(writer.into_inner())?;
+    document.push('\n');
+    Ok(document)
+}
+
+/// Replaces the leading and trailing whitespace in the text of leaf elements with character
+/// references. The deserializer trims text, which would change secrets like passphrases that
+/// start or end with spaces
+pub(crate) fn protect_leaf_whitespace(xml: &str) -> Result<String, DeError> {
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::new());
+
+    // Text directly after a start tag, which belongs to a leaf element if the element ends
+    // right after it
+    let mut leaf_text: Option<BytesText> = None;
+    let mut after_start = false;
+
+    loop {
+        let event = reader.read_event()?;
+
+        if let Some(text) = leaf_text.take() {
+            let text = match event {
+                Event::End(_) => escape_edge_whitespace(&text),
+                _ => text,
+            };
+            writer.write_event(Event::Text(text))?;
+        }
+
+        let start = matches!(event, Event::Start(_));
+        match event {
+            Event::Text(text) if after_start => leaf_text = Some(text.into_owned()),
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+        after_start = start;
+    }
+
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
+/// Escapes the leading and trailing whitespace of raw text as character references
+fn escape_edge_whitespace(text: &[u8]) -> BytesText<'static> {
+    let start = text
+        .iter()
+        .position(|c| !c.is_ascii_whitespace())
+        .unwrap_or(text.len());
+    let end = text
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(start, |index| index + 1);
+
+    let reference = |c: &u8| format!("&#{c};");
+    let escaped = text[..start]
+        .iter()
+        .map(reference)
+        .chain([String::from_utf8_lossy(&text[start..end]).into_owned()])
+        .chain(text[end..].iter().map(reference))
+        .collect::<String>();
+
+    BytesText::from_escaped(escaped)
+}
+
+/// Declares the schema namespace on an element if Windows expects one at its location
+fn add_namespace(
+    path: &[String],
+    element: &mut quick_xml::events::BytesStart,
+) -> Result<(), DeError> {
+    let Some((_, namespace)) = ELEMENT_NAMESPACES
+        .iter()
+        .find(|(namespace_path, _)| namespace_path.iter().eq(path.iter()))
+    else {
+        return Ok(());
+    };
+
+    for attribute in element.attributes() {
+        if attribute?.key.as_ref() == b"xmlns" {
+            return Ok(());
+        }
+    }
+
+    element.push_attribute(Attribute::from(("xmlns", *namespace)));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that raw elements keep their namespaces, attributes and text
+    #[test]
+    fn raw_element_round_trip() {
+        const TEST_XML: &str = r#"<Config xmlns="urn:a"><Eap xmlns="urn:b"><Type>25</Type><Empty/><Text key="value">a &amp; b</Text></Eap></Config>"#;
+
+        let element: XmlElement =
+            quick_xml::de::from_str(TEST_XML).expect("Failed to parse raw element");
+
+        assert_eq!(element.attribute("xmlns"), Some("urn:a"));
+
+        let eap = element.child("Eap").expect("Missing Eap element");
+        assert_eq!(eap.attribute("xmlns"), Some("urn:b"));
+        assert_eq!(
+            eap.child("Type").and_then(|child| child.text.as_deref()),
+            Some("25")
+        );
+
+        let text = eap.child("Text").expect("Missing Text element");
+        assert_eq!(text.attribute("key"), Some("value"));
+        assert_eq!(text.text.as_deref(), Some("a & b"));
+
+        let mut serialized = String::new();
+        element
+            .serialize(
+                quick_xml::se::Serializer::with_root(&mut serialized, Some("Config")).unwrap(),
+            )
+            .expect("Failed to serialize raw element");
+
+        assert_eq!(serialized, TEST_XML);
+    }
+
+    /// Checks that the schema namespaces are only added where Windows expects them
+    #[test]
+    fn profile_document_namespaces() {
+        let document = format_profile_document(
+            "<WLANProfile><name>test</name><MSM><security><OneX><authMode>user</authMode></OneX></security></MSM><MacRandomization/></WLANProfile>",
+        )
+        .expect("Failed to format profile document");
+
+        let expected = format!(
+            r#"<?xml version="1.0"?>
+<WLANProfile xmlns="{WLAN_PROFILE_NAMESPACE}">
+  <name>test</name>
+  <MSM>
+    <security>
+      <OneX xmlns="{ONEX_NAMESPACE}">
+        <authMode>user</authMode>
+      </OneX>
+    </security>
+  </MSM>
+  <MacRandomization xmlns="{WLAN_PROFILE_V3_NAMESPACE}"/>
+</WLANProfile>
+"#
+        );
+
+        assert_eq!(document, expected);
+    }
+}
@@ -0,0 +1,377 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use windows::Win32::NetworkManagement::WiFi::*;
+
+/// Component of the WLAN service which reported a reason code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReasonCategory {
+    /// Codes which are not specific to a component
+    General,
+
+    /// Auto configuration module, which decides which networks are connected to
+    Acm,
+
+    /// Media specific module, which performs the association
+    Msm,
+
+    /// Security module of the media specific module
+    Security,
+
+    /// 802.1X authentication
+    OneX,
+
+    /// Profile validation
+    Profile,
+
+    /// Independent hardware vendor extensions
+    Ihv,
+
+    /// Codes of other components
+    Other,
+}
+
+impl ReasonCategory {
+    /// Returns the category of the range a reason code is in
+    fn of(code: u32) -> ReasonCategory {
+        match code {
+            0 => ReasonCategory::General,
+            L2_REASON_CODE_GEN_BASE..WLAN_REASON_CODE_AC_BASE => ReasonCategory::General,
+            WLAN_REASON_CODE_AC_BASE..WLAN_REASON_CODE_MSM_BASE => ReasonCategory::Acm,
+            WLAN_REASON_CODE_MSM_BASE..WLAN_REASON_CODE_MSMSEC_BASE => ReasonCategory::Msm,
+            WLAN_REASON_CODE_MSMSEC_BASE..L2_REASON_CODE_ONEX_BASE => ReasonCategory::Security,
+            L2_REASON_CODE_ONEX_BASE..L2_REASON_CODE_DOT3_AC_BASE => ReasonCategory::OneX,
+            L2_REASON_CODE_PROFILE_BASE..L2_REASON_CODE_IHV_BASE => ReasonCategory::Profile,
+            L2_REASON_CODE_IHV_BASE..L2_REASON_CODE_WIMAX_BASE => ReasonCategory::Ihv,
+            _ => ReasonCategory::Other,
+        }
+    }
+}
+
+impl Display for ReasonCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReasonCategory::General => "general",
+            ReasonCategory::Acm => "ACM",
+            ReasonCategory::Msm => "MSM",
+            ReasonCategory::Security => "security",
+            ReasonCategory::OneX => "802.1X",
+            ReasonCategory::Profile => "profile",
+            ReasonCategory::Ihv => "IHV",
+            ReasonCategory::Other => "other",
+        })
+    }
+}
+
+/// A `WLAN_REASON_CODE` value reported by wlanapi. Known codes are explained with a built-in
+/// table so they can be decoded without `WlanReasonCodeToString`.
+///
+/// Serialized as the raw value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ReasonCode(u32);
+
+impl ReasonCode {
+    /// Creates a reason code from the raw value
+    pub const fn new(code: u32) -> ReasonCode {
+        ReasonCode(code)
+    }
+
+    /// Returns the raw value
+    pub const fn code(self) -> u32 {
+        self.0
+    }
+
+    /// Whether the code reports success
+    pub const fn is_success(self) -> bool {
+        self.0 == WLAN_REASON_CODE_SUCCESS
+    }
+
+    /// Returns the component which reported the code
+    pub fn category(self) -> ReasonCategory {
+        ReasonCategory::of(self.0)
+    }
+
+    /// Returns the symbolic name from the Windows headers of a known code
+    pub fn name(self) -> Option<&'static str> {
+        self.entry().map(|entry| entry.name)
+    }
+
+    /// Returns a description of a known code
+    pub fn description(self) -> Option<&'static str> {
+        self.entry().map(|entry| entry.description)
+    }
+
+    fn entry(self) -> Option<&'static ReasonEntry> {
+        REASON_CODES.iter().find(|entry| entry.code == self.0)
+    }
+}
+
+impl From<u32> for ReasonCode {
+    fn from(value: u32) -> Self {
+        ReasonCode(value)
+    }
+}
+
+impl From<ReasonCode> for u32 {
+    fn from(value: ReasonCode) -> Self {
+        value.0
+    }
+}
+
+/// Displays the description and symbolic name of known codes and the raw value otherwise
+impl Display for ReasonCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.entry() {
+            Some(entry) => write!(f, "{} ({})", entry.description, entry.name),
+            None => write!(f, "unknown {} reason code {}", self.category(), self.0),
+        }
+    }
+}
+
+/// Entry of the reason code table
+struct ReasonEntry {
+    code: u32,
+    name: &'static str,
+    description: &'static str,
+}
+
+/// Creates the table of known reason codes from the constants of the Windows headers
+macro_rules! reason_codes {
+    (
+        wlan { $($wlan:ident => $wlan_description:literal,)* }
+        onex { $($onex:ident => $onex_description:literal,)* }
+    ) => {
+        /// Known reason codes
+        const REASON_CODES: &[ReasonEntry] = &[
+            $(ReasonEntry {
+                code: $wlan,
+                name: stringify!($wlan),
+                description: $wlan_description,
+            },)*
+            $(ReasonEntry {
+                code: $onex.0 as u32,
+                name: stringify!($onex),
+                description: $onex_description,
+            },)*
+        ];
+    };
+}
+
+reason_codes! {
+    wlan {
+        WLAN_REASON_CODE_SUCCESS => "The operation succeeded",
+        WLAN_REASON_CODE_UNKNOWN => "The reason is unknown",
+        WLAN_REASON_CODE_NETWORK_NOT_COMPATIBLE => "The network is not compatible",
+        WLAN_REASON_CODE_PROFILE_NOT_COMPATIBLE => "The profile is not compatible",
+        WLAN_REASON_CODE_NO_AUTO_CONNECTION => "Automatic connection is disabled for the profile",
+        WLAN_REASON_CODE_NOT_VISIBLE => "The network is not visible",
+        WLAN_REASON_CODE_GP_DENIED => "The network is blocked by group policy",
+        WLAN_REASON_CODE_USER_DENIED => "The network is blocked by the user",
+        WLAN_REASON_CODE_BSS_TYPE_NOT_ALLOWED => "The BSS type is not allowed",
+        WLAN_REASON_CODE_IN_FAILED_LIST => "The network is in the list of failed networks",
+        WLAN_REASON_CODE_IN_BLOCKED_LIST => "The network is in the list of blocked networks",
+        WLAN_REASON_CODE_SSID_LIST_TOO_LONG => "The profile contains too many SSIDs",
+        WLAN_REASON_CODE_CONNECT_CALL_FAIL => "The connect call to the media specific module failed",
+        WLAN_REASON_CODE_SCAN_CALL_FAIL => "The scan call to the media specific module failed",
+        WLAN_REASON_CODE_NETWORK_NOT_AVAILABLE => "The network is not available",
+        WLAN_REASON_CODE_PROFILE_CHANGED_OR_DELETED => "The profile was changed or deleted before the connection completed",
+        WLAN_REASON_CODE_KEY_MISMATCH => "The key in the profile does not match the network",
+        WLAN_REASON_CODE_USER_NOT_RESPOND => "The user did not respond",
+        WLAN_REASON_CODE_AP_PROFILE_NOT_ALLOWED_FOR_CLIENT => "The access point profile is not allowed for client connections",
+        WLAN_REASON_CODE_AP_PROFILE_NOT_ALLOWED => "Access point profiles are not allowed",
+        WLAN_REASON_CODE_HOTSPOT2_PROFILE_DENIED => "The Hotspot 2.0 profile is denied",
+        WLAN_REASON_CODE_UNSUPPORTED_SECURITY_SET_BY_OS => "The security settings are not supported by the operating system",
+        WLAN_REASON_CODE_UNSUPPORTED_SECURITY_SET => "The security settings are not supported",
+        WLAN_REASON_CODE_BSS_TYPE_UNMATCH => "The BSS type of the network does not match the profile",
+        WLAN_REASON_CODE_PHY_TYPE_UNMATCH => "The PHY type of the network does not match the profile",
+        WLAN_REASON_CODE_DATARATE_UNMATCH => "The data rates of the network do not match",
+        WLAN_REASON_CODE_USER_CANCELLED => "The connection was cancelled by the user",
+        WLAN_REASON_CODE_ASSOCIATION_FAILURE => "The association with the access point failed",
+        WLAN_REASON_CODE_ASSOCIATION_TIMEOUT => "The association with the access point timed out",
+        WLAN_REASON_CODE_PRE_SECURITY_FAILURE => "The security checks before association failed",
+        WLAN_REASON_CODE_START_SECURITY_FAILURE => "The security negotiation could not be started",
+        WLAN_REASON_CODE_SECURITY_FAILURE => "The security negotiation failed",
+        WLAN_REASON_CODE_SECURITY_TIMEOUT => "The security negotiation timed out",
+        WLAN_REASON_CODE_ROAMING_FAILURE => "Roaming to another access point failed",
+        WLAN_REASON_CODE_ROAMING_SECURITY_FAILURE => "The security negotiation failed while roaming",
+        WLAN_REASON_CODE_ADHOC_SECURITY_FAILURE => "The security negotiation of the ad hoc network failed",
+        WLAN_REASON_CODE_DRIVER_DISCONNECTED => "The driver disconnected",
+        WLAN_REASON_CODE_DRIVER_OPERATION_FAILURE => "A driver operation failed",
+        WLAN_REASON_CODE_IHV_NOT_AVAILABLE => "The IHV service is not available",
+        WLAN_REASON_CODE_IHV_NOT_RESPONDING => "The IHV service is not responding",
+        WLAN_REASON_CODE_DISCONNECT_TIMEOUT => "The driver did not disconnect in time",
+        WLAN_REASON_CODE_INTERNAL_FAILURE => "An internal error occurred",
+        WLAN_REASON_CODE_UI_REQUEST_TIMEOUT => "The user interface request timed out",
+        WLAN_REASON_CODE_TOO_MANY_SECURITY_ATTEMPTS => "Too many security attempts were made",
+        WLAN_REASON_CODE_AP_STARTING_FAILURE => "The access point could not be started",
+        WLAN_REASON_CODE_NO_VISIBLE_AP => "No access point of the network is visible",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_INVALID_KEY_INDEX => "The key index in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_PSK_PRESENT => "A pre-shared key is present but not used by the authentication",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_KEY_LENGTH => "The key length in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_PSK_LENGTH => "The pre-shared key length in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_NO_AUTH_CIPHER_SPECIFIED => "The profile does not specify an authentication and cipher pair",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_TOO_MANY_AUTH_CIPHER_SPECIFIED => "The profile specifies too many authentication and cipher pairs",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_DUPLICATE_AUTH_CIPHER => "The profile specifies a duplicate authentication and cipher pair",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_RAWDATA_INVALID => "The raw security data in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_INVALID_AUTH_CIPHER => "The authentication and cipher pair in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_ONEX_DISABLED => "802.1X must be enabled for the security settings of the profile",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_ONEX_ENABLED => "802.1X must be disabled for the security settings of the profile",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_INVALID_PMKCACHE_MODE => "The PMK cache mode in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_INVALID_PMKCACHE_SIZE => "The PMK cache size in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_INVALID_PMKCACHE_TTL => "The PMK cache time to live in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_INVALID_PREAUTH_MODE => "The pre-authentication mode in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_INVALID_PREAUTH_THROTTLE => "The pre-authentication throttle in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_PREAUTH_ONLY_ENABLED => "Pre-authentication is enabled without the PMK cache",
+        WLAN_REASON_CODE_MSMSEC_CAPABILITY_NETWORK => "The network does not support the security settings of the profile",
+        WLAN_REASON_CODE_MSMSEC_CAPABILITY_NIC => "The network adapter does not support the security settings of the profile",
+        WLAN_REASON_CODE_MSMSEC_CAPABILITY_PROFILE => "The security settings of the profile are not supported",
+        WLAN_REASON_CODE_MSMSEC_CAPABILITY_DISCOVERY => "The network does not support the security settings used for discovery",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_PASSPHRASE_CHAR => "The passphrase in the profile contains an invalid character",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_KEYMATERIAL_CHAR => "The key material in the profile contains an invalid character",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_WRONG_KEYTYPE => "The key type in the profile is wrong",
+        WLAN_REASON_CODE_MSMSEC_MIXED_CELL => "The network is a mixed cell which is not allowed by the profile",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_AUTH_TIMERS_INVALID => "The authentication timers in the profile are invalid",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_INVALID_GKEY_INTV => "The group key update interval in the profile is invalid",
+        WLAN_REASON_CODE_MSMSEC_TRANSITION_NETWORK => "The network is a transition network which is not supported",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_KEY_UNMAPPED_CHAR => "The key in the profile contains characters which can not be mapped",
+        WLAN_REASON_CODE_MSMSEC_CAPABILITY_PROFILE_AUTH => "The authentication of the profile is not supported",
+        WLAN_REASON_CODE_MSMSEC_CAPABILITY_PROFILE_CIPHER => "The cipher of the profile is not supported",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_SAFE_MODE => "The profile requires FIPS mode which is not supported",
+        WLAN_REASON_CODE_MSMSEC_CAPABILITY_PROFILE_SAFE_MODE_NIC => "The network adapter does not support FIPS mode",
+        WLAN_REASON_CODE_MSMSEC_CAPABILITY_PROFILE_SAFE_MODE_NW => "The network does not support FIPS mode",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_UNSUPPORTED_AUTH => "The authentication of the profile is not supported",
+        WLAN_REASON_CODE_MSMSEC_PROFILE_UNSUPPORTED_CIPHER => "The cipher of the profile is not supported",
+        WLAN_REASON_CODE_MSMSEC_CAPABILITY_MFP_NW_NIC => "Management frame protection is not supported by the network or the network adapter",
+        WLAN_REASON_CODE_MSMSEC_UI_REQUEST_FAILURE => "The request for user credentials failed",
+        WLAN_REASON_CODE_MSMSEC_AUTH_START_TIMEOUT => "The authentication did not start in time",
+        WLAN_REASON_CODE_MSMSEC_AUTH_SUCCESS_TIMEOUT => "The authentication did not succeed in time",
+        WLAN_REASON_CODE_MSMSEC_KEY_START_TIMEOUT => "The key exchange did not start in time",
+        WLAN_REASON_CODE_MSMSEC_KEY_SUCCESS_TIMEOUT => "The key exchange did not succeed in time",
+        WLAN_REASON_CODE_MSMSEC_M3_MISSING_KEY_DATA => "Message 3 of the 4-way handshake is missing the key data",
+        WLAN_REASON_CODE_MSMSEC_M3_MISSING_IE => "Message 3 of the 4-way handshake is missing the information element",
+        WLAN_REASON_CODE_MSMSEC_M3_MISSING_GRP_KEY => "Message 3 of the 4-way handshake is missing the group key",
+        WLAN_REASON_CODE_MSMSEC_PR_IE_MATCHING => "The security information element of the probe response does not match",
+        WLAN_REASON_CODE_MSMSEC_SEC_IE_MATCHING => "The security information element of the handshake does not match",
+        WLAN_REASON_CODE_MSMSEC_NO_PAIRWISE_KEY => "No pairwise key was received",
+        WLAN_REASON_CODE_MSMSEC_G1_MISSING_KEY_DATA => "Message 1 of the group key handshake is missing the key data",
+        WLAN_REASON_CODE_MSMSEC_G1_MISSING_GRP_KEY => "Message 1 of the group key handshake is missing the group key",
+        WLAN_REASON_CODE_MSMSEC_PEER_INDICATED_INSECURE => "The access point indicated that the connection is insecure",
+        WLAN_REASON_CODE_MSMSEC_NO_AUTHENTICATOR => "No authenticator responded",
+        WLAN_REASON_CODE_MSMSEC_NIC_FAILURE => "The network adapter failed",
+        WLAN_REASON_CODE_MSMSEC_CANCELLED => "The security negotiation was cancelled",
+        WLAN_REASON_CODE_MSMSEC_KEY_FORMAT => "The key has an invalid format",
+        WLAN_REASON_CODE_MSMSEC_DOWNGRADE_DETECTED => "A security downgrade was detected",
+        WLAN_REASON_CODE_MSMSEC_PSK_MISMATCH_SUSPECTED => "The pre-shared key probably does not match the network",
+        WLAN_REASON_CODE_MSMSEC_FORCED_FAILURE => "The security negotiation was forced to fail",
+        WLAN_REASON_CODE_MSMSEC_M3_TOO_MANY_RSNIE => "Message 3 of the 4-way handshake contains too many RSN information elements",
+        WLAN_REASON_CODE_MSMSEC_M2_MISSING_KEY_DATA => "Message 2 of the 4-way handshake is missing the key data",
+        WLAN_REASON_CODE_MSMSEC_M2_MISSING_IE => "Message 2 of the 4-way handshake is missing the information element",
+        WLAN_REASON_CODE_MSMSEC_AUTH_WCN_COMPLETED => "The Windows Connect Now authentication completed",
+        WLAN_REASON_CODE_MSMSEC_M3_MISSING_MGMT_GRP_KEY => "Message 3 of the 4-way handshake is missing the management group key",
+        WLAN_REASON_CODE_MSMSEC_G1_MISSING_MGMT_GRP_KEY => "Message 1 of the group key handshake is missing the management group key",
+        WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA => "The profile XML does not match the schema",
+        WLAN_REASON_CODE_PROFILE_MISSING => "The profile is missing",
+        WLAN_REASON_CODE_INVALID_PROFILE_NAME => "The profile name is invalid",
+        WLAN_REASON_CODE_INVALID_PROFILE_TYPE => "The profile type is invalid",
+        WLAN_REASON_CODE_INVALID_PHY_TYPE => "The PHY type in the profile is invalid",
+        WLAN_REASON_CODE_MSM_SECURITY_MISSING => "The profile is missing the security settings",
+        WLAN_REASON_CODE_IHV_SECURITY_NOT_SUPPORTED => "The IHV security settings are not supported",
+        WLAN_REASON_CODE_IHV_OUI_MISMATCH => "The IHV OUI in the profile does not match the network adapter",
+        WLAN_REASON_CODE_IHV_OUI_MISSING => "The profile is missing the IHV OUI",
+        WLAN_REASON_CODE_IHV_SETTINGS_MISSING => "The profile is missing the IHV settings",
+        WLAN_REASON_CODE_CONFLICT_SECURITY => "The profile contains conflicting security settings",
+        WLAN_REASON_CODE_SECURITY_MISSING => "The profile is missing security settings",
+        WLAN_REASON_CODE_INVALID_BSS_TYPE => "The BSS type in the profile is invalid",
+        WLAN_REASON_CODE_INVALID_ADHOC_CONNECTION_MODE => "Automatic connection is not allowed for ad hoc networks",
+        WLAN_REASON_CODE_NON_BROADCAST_SET_FOR_ADHOC => "Non-broadcast is not allowed for ad hoc networks",
+        WLAN_REASON_CODE_AUTO_SWITCH_SET_FOR_ADHOC => "Automatic switching is not allowed for ad hoc networks",
+        WLAN_REASON_CODE_AUTO_SWITCH_SET_FOR_MANUAL_CONNECTION => "Automatic switching is not allowed for manual connections",
+        WLAN_REASON_CODE_IHV_SECURITY_ONEX_MISSING => "The IHV security settings are missing the 802.1X settings",
+        WLAN_REASON_CODE_PROFILE_SSID_INVALID => "The SSID in the profile is invalid",
+        WLAN_REASON_CODE_TOO_MANY_SSID => "The profile contains too many SSIDs",
+        WLAN_REASON_CODE_IHV_CONNECTIVITY_NOT_SUPPORTED => "The IHV connectivity settings are not supported",
+        WLAN_REASON_CODE_BAD_MAX_NUMBER_OF_CLIENTS_FOR_AP => "The maximum number of clients for the access point is invalid",
+        WLAN_REASON_CODE_INVALID_CHANNEL => "The channel in the profile is invalid",
+        WLAN_REASON_CODE_OPERATION_MODE_NOT_SUPPORTED => "The operation mode is not supported",
+        WLAN_REASON_CODE_AUTO_AP_PROFILE_NOT_ALLOWED => "Automatic access point profiles are not allowed",
+        WLAN_REASON_CODE_AUTO_CONNECTION_NOT_ALLOWED => "Automatic connection is not allowed",
+        WLAN_REASON_CODE_HOTSPOT2_PROFILE_NOT_ALLOWED => "Hotspot 2.0 profiles are not allowed",
+    }
+    onex {
+        ONEX_UNABLE_TO_IDENTIFY_USER => "The user could not be identified",
+        ONEX_IDENTITY_NOT_FOUND => "No identity was found for the user",
+        ONEX_UI_DISABLED => "The user interface for credentials is disabled",
+        ONEX_UI_FAILURE => "The user interface for credentials failed",
+        ONEX_EAP_FAILURE_RECEIVED => "The authenticator sent an EAP failure",
+        ONEX_AUTHENTICATOR_NO_LONGER_PRESENT => "The authenticator is no longer present",
+        ONEX_NO_RESPONSE_TO_IDENTITY => "The authenticator did not respond to the identity",
+        ONEX_PROFILE_VERSION_NOT_SUPPORTED => "The 802.1X profile version is not supported",
+        ONEX_PROFILE_INVALID_LENGTH => "The 802.1X profile has an invalid length",
+        ONEX_PROFILE_DISALLOWED_EAP_TYPE => "The EAP type in the profile is not allowed",
+        ONEX_PROFILE_INVALID_EAP_TYPE_OR_FLAG => "The EAP type or flags in the profile are invalid",
+        ONEX_PROFILE_INVALID_ONEX_FLAGS => "The 802.1X flags in the profile are invalid",
+        ONEX_PROFILE_INVALID_TIMER_VALUE => "A timer value in the 802.1X profile is invalid",
+        ONEX_PROFILE_INVALID_SUPPLICANT_MODE => "The supplicant mode in the profile is invalid",
+        ONEX_PROFILE_INVALID_AUTH_MODE => "The authentication mode in the profile is invalid",
+        ONEX_PROFILE_INVALID_EAP_CONNECTION_PROPERTIES => "The EAP connection properties in the profile are invalid",
+        ONEX_UI_CANCELLED => "The user cancelled the credentials prompt",
+        ONEX_PROFILE_INVALID_EXPLICIT_CREDENTIALS => "The credentials saved in the profile are invalid",
+        ONEX_PROFILE_EXPIRED_EXPLICIT_CREDENTIALS => "The credentials saved in the profile expired",
+        ONEX_UI_NOT_PERMITTED => "The user interface for credentials is not permitted",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that every code is in the table once
+    #[test]
+    fn unique_codes() {
+        let mut codes = REASON_CODES
+            .iter()
+            .map(|entry| entry.code)
+            .collect::<Vec<_>>();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), REASON_CODES.len());
+    }
+
+    /// Checks the decoding of known and unknown codes
+    #[test]
+    fn decode_reason_codes() {
+        let code = ReasonCode::from(WLAN_REASON_CODE_MSMSEC_PSK_MISMATCH_SUSPECTED);
+        assert_eq!(code.category(), ReasonCategory::Security);
+        assert_eq!(
+            code.name(),
+            Some("WLAN_REASON_CODE_MSMSEC_PSK_MISMATCH_SUSPECTED")
+        );
+        assert_eq!(
+            code.to_string(),
+            "The pre-shared key probably does not match the network (WLAN_REASON_CODE_MSMSEC_PSK_MISMATCH_SUSPECTED)"
+        );
+
+        for (code, category) in [
+            (WLAN_REASON_CODE_SUCCESS, ReasonCategory::General),
+            (WLAN_REASON_CODE_NETWORK_NOT_AVAILABLE, ReasonCategory::Acm),
+            (WLAN_REASON_CODE_ASSOCIATION_FAILURE, ReasonCategory::Msm),
+            (ONEX_UI_CANCELLED.0 as u32, ReasonCategory::OneX),
+            (
+                WLAN_REASON_CODE_INVALID_PROFILE_SCHEMA,
+                ReasonCategory::Profile,
+            ),
+        ] {
+            let code = ReasonCode::new(code);
+            assert_eq!(code.category(), category);
+            assert!(code.description().is_some(), "{code:?} is not known");
+        }
+
+        assert!(ReasonCode::new(0).is_success());
+
+        let unknown = ReasonCode::new(L2_REASON_CODE_IHV_BASE + 1);
+        assert_eq!(unknown.category(), ReasonCategory::Ihv);
+        assert_eq!(unknown.name(), None);
+        assert_eq!(unknown.to_string(), "unknown IHV reason code 589825");
+    }
+}
@@ -0,0 +1,332 @@
+use std::sync::Arc;
+
+use windows::{core::GUID, Win32::Foundation::ERROR_NOT_FOUND};
+
+use crate::{
+    backend::{WlanBackend, WlanCall},
+    bss::WlanBssEntry,
+    errors::{WinWifiError, WlanErrorContext},
+    guid::InterfaceGuid,
+    handle::{WlanApiVersion, WlanHandle},
+    interfaces::WlanInterfaceState,
+    profiles::info::WlanProfile,
+    snapshot::{InterfaceInfo, ProfileInfo},
+};
+
+/// Reference counted connection to the WLAN service. Clones share the same client handle, which
+/// is closed when the last clone and the interfaces and profiles created from it are dropped.
+///
+/// Interfaces and profiles returned by a session own their data and a clone of the session, so
+/// they can be stored and sent across threads. Use [`WlanHandle`] and
+/// [`WlanInterfaces`](crate::WlanInterfaces) to access the lists returned by wlanapi without
+/// copying them
+#[derive(Clone)]
+pub struct WlanSession {
+    handle: Arc<WlanHandle>,
+}
+
+impl WlanSession {
+    /// Opens a session with the Windows WLAN api
+    #[cfg(windows)]
+    pub fn new() -> Result<WlanSession, WinWifiError> {
+        WlanHandle::new().map(WlanSession::from)
+    }
+
+    /// Opens a session with the Windows WLAN api using the specified api version
+    #[cfg(windows)]
+    pub fn with_api_version(version: WlanApiVersion) -> Result<WlanSession, WinWifiError> {
+        WlanHandle::with_api_version(version).map(WlanSession::from)
+    }
+
+    /// Opens a session using the specified backend instead of the Windows WLAN api
+    pub fn with_backend(
+        backend: Arc<dyn WlanBackend>,
+        version: WlanApiVersion,
+    ) -> Result<WlanSession, WinWifiError> {
+        WlanHandle::with_backend(backend, version).map(WlanSession::from)
+    }
+
+    /// Returns the handle of the session
+    pub fn handle(&self) -> &WlanHandle {
+        &self.handle
+    }
+
+    /// Returns the wireless interfaces on the system
+    pub fn interfaces(&self) -> Result<Vec<SessionInterface>, WinWifiError> {
+        Ok(self
+            .handle
+            .interface_infos()?
+            .into_iter()
+            .map(|info| SessionInterface {
+                session: self.clone(),
+                info,
+            })
+            .collect())
+    }
+
+    /// Returns the wireless interface with the specified GUID. Fails with
+    /// [`WinWifiError::NotFound`] if there is no such interface
+    pub fn interface(
+        &self,
+        guid: impl Into<InterfaceGuid>,
+    ) -> Result<SessionInterface, WinWifiError> {
+        let guid = GUID::from(guid.into());
+
+        self.handle
+            .interface_infos()?
+            .into_iter()
+            .find(|info| info.guid == guid)
+            .map(|info| SessionInterface {
+                session: self.clone(),
+                info,
+            })
+            .ok_or_else(|| {
+                WlanErrorContext::new(WlanCall::EnumInterfaces)
+                    .with_interface(&guid)
+                    .error(ERROR_NOT_FOUND)
+            })
+    }
+}
+
+impl From<WlanHandle> for WlanSession {
+    fn from(value: WlanHandle) -> Self {
+        WlanSession {
+            handle: Arc::new(value),
+        }
+    }
+}
+
+impl std::fmt::Debug for WlanSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WlanSession")
+            .field("handle", &self.handle.raw().0)
+            .finish()
+    }
+}
+
+/// A wireless interface of a [`WlanSession`]
+#[derive(Debug, Clone)]
+pub struct SessionInterface {
+    /// Session the interface was enumerated with
+    session: WlanSession,
+
+    /// Snapshot of the interface
+    info: InterfaceInfo,
+}
+
+impl SessionInterface {
+    /// Returns the session of the interface
+    pub fn session(&self) -> &WlanSession {
+        &self.session
+    }
+
+    /// Returns the snapshot of the interface
+    pub fn info(&self) -> &InterfaceInfo {
+        &self.info
+    }
+
+    /// Returns the interface GUID
+    pub fn guid(&self) -> GUID {
+        self.info.guid
+    }
+
+    /// Returns the description of the interface
+    pub fn description(&self) -> &str {
+        &self.info.description
+    }
+
+    /// Returns the state of the interface when it was enumerated or last refreshed
+    pub fn if_state(&self) -> WlanInterfaceState {
+        self.info.state
+    }
+
+    /// Updates the snapshot of the interface. Fails with [`WinWifiError::NotFound`] if the
+    /// interface was removed from the system
+    pub fn refresh(&mut self) -> Result<(), WinWifiError> {
+        self.info = self
+            .session
+            .handle
+            .interface_infos()?
+            .into_iter()
+            .find(|info| info.guid == self.info.guid)
+            .ok_or_else(|| {
+                WlanErrorContext::new(WlanCall::EnumInterfaces)
+                    .with_interface(&self.info.guid)
+                    .error(ERROR_NOT_FOUND)
+            })?;
+
+        Ok(())
+    }
+
+    /// Get the saved profiles associated with this interface
+    pub fn profiles(&self) -> Result<Vec<SessionProfile>, WinWifiError> {
+        Ok(self
+            .session
+            .handle
+            .profile_infos(&self.info.guid)?
+            .into_iter()
+            .map(|info| SessionProfile {
+                interface: self.clone(),
+                info,
+            })
+            .collect())
+    }
+
+    /// Requests a scan for wireless networks. The scan completes asynchronously, the results are
+    /// returned by [`SessionInterface::bss_entries`] afterwards
+    pub fn scan(&self) -> Result<(), WinWifiError> {
+        self.session.handle.scan(&self.info.guid)
+    }
+
+    /// Returns the access points found by the scans of the interface
+    pub fn bss_entries(&self) -> Result<Vec<WlanBssEntry>, WinWifiError> {
+        self.session.handle.bss_entries(&self.info.guid)
+    }
+
+    /// Connects the interface to the network of a saved profile. The connection completes
+    /// asynchronously
+    pub fn connect(&self, profile_name: &str) -> Result<(), WinWifiError> {
+        self.session.handle.connect(&self.info.guid, profile_name)
+    }
+
+    /// Disconnects the interface from its network
+    pub fn disconnect(&self) -> Result<(), WinWifiError> {
+        self.session.handle.disconnect(&self.info.guid)
+    }
+
+    /// Saves a profile for the interface. An existing profile with the same name is only
+    /// replaced if `overwrite` is set
+    pub fn set_profile(&self, profile: &WlanProfile, overwrite: bool) -> Result<(), WinWifiError> {
+        self.session
+            .handle
+            .set_profile(&self.info.guid, profile, overwrite)
+    }
+}
+
+/// A saved profile of a [`SessionInterface`]
+#[derive(Debug, Clone)]
+pub struct SessionProfile {
+    /// Interface the profile is saved for
+    interface: SessionInterface,
+
+    /// Snapshot of the profile
+    info: ProfileInfo,
+}
+
+impl SessionProfile {
+    /// Returns the interface the profile is saved for
+    pub fn interface(&self) -> &SessionInterface {
+        &self.interface
+    }
+
+    /// Returns the snapshot of the profile
+    pub fn info(&self) -> &ProfileInfo {
+        &self.info
+    }
+
+    /// Returns the name of the profile
+    pub fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    /// Whether the profile was created by group policy
+    pub fn group_policy_profile(&self) -> bool {
+        self.info.group_policy_profile()
+    }
+
+    /// Whether the profile is a per-user profile
+    pub fn user_profile(&self) -> bool {
+        self.info.user_profile()
+    }
+
+    /// Connects the interface to the network of the profile
+    pub fn connect(&self) -> Result<(), WinWifiError> {
+        self.interface.connect(&self.info.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::{SimulatedBss, SimulatedInterface, WlanSimulator},
+        profiles::WlanProfileBuilder,
+    };
+
+    const TEST_GUID: GUID = GUID::from_values(
+        0x12345678,
+        0x1234,
+        0x1234,
+        [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88],
+    );
+
+    /// Returns every interface together with its profiles, which is not possible with the
+    /// borrowed views
+    fn interfaces_with_profiles(
+        session: &WlanSession,
+    ) -> Result<Vec<(SessionInterface, Vec<SessionProfile>)>, WinWifiError> {
+        session
+            .interfaces()?
+            .into_iter()
+            .map(|interface| {
+                let profiles = interface.profiles()?;
+                Ok((interface, profiles))
+            })
+            .collect()
+    }
+
+    /// Uses owned interfaces and profiles on another thread after the session was dropped
+    #[test]
+    fn owned_session() {
+        let profile = WlanProfileBuilder::new("Home")
+            .wpa2_psk("passphrase")
+            .build()
+            .expect("Failed to build profile");
+        let simulator = Arc::new(
+            WlanSimulator::new()
+                .with_interface(
+                    SimulatedInterface::new(TEST_GUID, "Simulated Adapter").with_profile(profile),
+                )
+                .with_bss(SimulatedBss::new("Home", [0x02, 0, 0, 0, 0, 1], -60)),
+        );
+
+        let session = WlanSession::with_backend(simulator.clone(), WlanApiVersion::ApiVersion2)
+            .expect("Failed to open session");
+        let mut interfaces =
+            interfaces_with_profiles(&session).expect("Failed to enumerate interfaces");
+        drop(session);
+
+        let (mut interface, profiles) = interfaces.pop().expect("Missing interface");
+        assert_eq!(interface.guid(), TEST_GUID);
+        assert_eq!(interface.description(), "Simulated Adapter");
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].name(), "Home");
+
+        std::thread::spawn(move || profiles[0].connect())
+            .join()
+            .unwrap()
+            .expect("Failed to connect");
+        simulator.run_until_idle();
+
+        assert_eq!(interface.if_state(), WlanInterfaceState::Disconnected);
+        interface.refresh().expect("Failed to refresh interface");
+        assert_eq!(interface.if_state(), WlanInterfaceState::Connected);
+
+        let configured: InterfaceGuid = "{12345678-1234-1234-1122-334455667788}"
+            .parse()
+            .expect("Failed to parse GUID");
+        assert_eq!(
+            interface.session().interface(configured).unwrap().guid(),
+            TEST_GUID
+        );
+        assert!(matches!(
+            interface.session().interface(InterfaceGuid::default()),
+            Err(WinWifiError::NotFound(_))
+        ));
+
+        assert_eq!(simulator.open_handles(), 1);
+        drop(interface);
+        assert_eq!(simulator.open_handles(), 0);
+        assert_eq!(simulator.allocations(), 0);
+    }
+}
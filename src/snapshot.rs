@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use windows::{
+    core::GUID,
+    Win32::NetworkManagement::WiFi::{
+        WLAN_INTERFACE_INFO, WLAN_PROFILE_GROUP_POLICY, WLAN_PROFILE_INFO, WLAN_PROFILE_USER,
+    },
+};
+
+use crate::{backend::os_string_from_wide, interfaces::WlanInterfaceState};
+
+/// Owned snapshot of a wireless interface which can be stored, sent across threads and
+/// serialized. Created with [`WlanInterface::to_owned`](crate::WlanInterface::to_owned)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceInfo {
+    /// GUID of the interface
+    #[serde(with = "crate::guid::serde_guid")]
+    pub guid: GUID,
+
+    /// Description of the interface
+    pub description: String,
+
+    /// State of the interface when the snapshot was taken
+    pub state: WlanInterfaceState,
+}
+
+impl From<&WLAN_INTERFACE_INFO> for InterfaceInfo {
+    fn from(value: &WLAN_INTERFACE_INFO) -> Self {
+        InterfaceInfo {
+            guid: value.InterfaceGuid,
+            description: string_from_wide(&value.strInterfaceDescription),
+            state: value.isState.into(),
+        }
+    }
+}
+
+/// Owned snapshot of a saved profile which can be stored, sent across threads and serialized.
+/// Created with [`WlanInterfaceProfile::to_owned`](crate::profiles::WlanInterfaceProfile::to_owned)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileInfo {
+    /// Name of the profile
+    pub name: String,
+
+    /// `WLAN_PROFILE_*` flags of the profile
+    pub flags: u32,
+}
+
+impl From<&WLAN_PROFILE_INFO> for ProfileInfo {
+    fn from(value: &WLAN_PROFILE_INFO) -> Self {
+        ProfileInfo {
+            name: string_from_wide(&value.strProfileName),
+            flags: value.dwFlags,
+        }
+    }
+}
+
+impl ProfileInfo {
+    /// Whether the profile was created by group policy
+    pub fn group_policy_profile(&self) -> bool {
+        self.flags & WLAN_PROFILE_GROUP_POLICY != 0
+    }
+
+    /// Whether the profile is a per-user profile
+    pub fn user_profile(&self) -> bool {
+        self.flags & WLAN_PROFILE_USER != 0
+    }
+}
+
+/// Converts a null terminated UTF-16 string into a string with invalid characters replaced
+fn string_from_wide(wide: &[u16]) -> String {
+    let length = wide.iter().position(|v| v == &0).unwrap_or(wide.len());
+    os_string_from_wide(&wide[..length])
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        backend::{FakeBackend, FakeInterface, FakeProfile},
+        handle::{WlanApiVersion, WlanHandle},
+    };
+
+    /// Takes snapshots through the public api and checks that they outlive the handle and
+    /// survive a JSON round trip
+    #[test]
+    fn snapshots() {
+        let guid = GUID::from_u128(0x12345678_1234_1234_1122_334455667788);
+        let backend = Arc::new(
+            FakeBackend::new().with_interface(
+                FakeInterface::new(guid, "Fake Adapter")
+                    .with_state(WlanInterfaceState::Connected)
+                    .with_profile(FakeProfile::new("Home").with_flags(WLAN_PROFILE_USER)),
+            ),
+        );
+
+        let (interfaces, profiles) = {
+            let interfaces = WlanHandle::with_backend(backend.clone(), WlanApiVersion::ApiVersion2)
+                .expect("Failed to open handle")
+                .into_interfaces()
+                .expect("Failed to enumerate interfaces");
+            let interface = interfaces.iter().next().expect("Missing interface");
+            let profiles = interface.profiles().expect("Failed to get profiles");
+
+            (
+                interfaces
+                    .iter()
+                    .map(|interface| interface.to_owned())
+                    .collect::<Vec<_>>(),
+                profiles
+                    .iter()
+                    .map(|profile| profile.to_owned())
+                    .collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(backend.open_handles(), 0);
+
+        assert_eq!(
+            interfaces,
+            [InterfaceInfo {
+                guid,
+                description: "Fake Adapter".into(),
+                state: WlanInterfaceState::Connected,
+            }]
+        );
+        assert!(profiles[0].user_profile());
+        assert!(!profiles[0].group_policy_profile());
+
+        let json = serde_json::to_string(&interfaces).expect("Failed to serialize interfaces");
+        assert_eq!(
+            json,
+            r#"[{"guid":"12345678-1234-1234-1122-334455667788","description":"Fake Adapter","state":"Connected"}]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Vec<InterfaceInfo>>(&json).unwrap(),
+            interfaces
+        );
+
+        let json = serde_json::to_string(&profiles).expect("Failed to serialize profiles");
+        assert_eq!(json, r#"[{"name":"Home","flags":2}]"#);
+        assert_eq!(
+            serde_json::from_str::<Vec<ProfileInfo>>(&json).unwrap(),
+            profiles
+        );
+    }
+}
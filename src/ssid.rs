@@ -0,0 +1,173 @@
+use std::{borrow::Cow, fmt::Display};
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use windows::Win32::NetworkManagement::WiFi::DOT11_SSID;
+
+use crate::errors::WinWifiError;
+
+/// SSID of a wireless network. SSIDs are up to 32 arbitrary bytes which are not required to be
+/// valid UTF-8, so the raw bytes are stored and compared
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ssid(Vec<u8>);
+
+impl Ssid {
+    /// Maximum length of an SSID in bytes
+    pub const MAX_LENGTH: usize = 32;
+
+    /// Creates an SSID from raw bytes
+    pub fn new(bytes: impl Into<Vec<u8>>) -> Ssid {
+        Ssid(bytes.into())
+    }
+
+    /// Parses an SSID from a hex string like the one in the `hex` element of a profile
+    pub fn from_hex(hex: &str) -> Result<Ssid, WinWifiError> {
+        let invalid = || WinWifiError::InvalidSsid(format!("'{hex}' is not a valid hex string"));
+
+        crate::hex::decode(hex).map(Ssid).ok_or_else(invalid)
+    }
+
+    /// Returns the SSID as an uppercase hex string the way Windows writes it to profiles
+    pub fn to_hex(&self) -> String {
+        crate::hex::encode(&self.0).to_ascii_uppercase()
+    }
+
+    /// Returns the raw bytes of the SSID
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the SSID as a string if it is valid UTF-8
+    pub fn to_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.0).ok()
+    }
+
+    /// Returns the SSID as a string with invalid UTF-8 sequences replaced
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.0)
+    }
+
+    /// Length of the SSID in bytes
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the SSID is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl std::fmt::Debug for Ssid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.to_str() {
+            Some(ssid) => f.debug_tuple("Ssid").field(&ssid).finish(),
+            None => f.debug_tuple("Ssid").field(&self.0).finish(),
+        }
+    }
+}
+
+impl Display for Ssid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_string_lossy())
+    }
+}
+
+impl From<&str> for Ssid {
+    fn from(value: &str) -> Self {
+        Ssid(value.as_bytes().to_vec())
+    }
+}
+
+impl From<String> for Ssid {
+    fn from(value: String) -> Self {
+        Ssid(value.into_bytes())
+    }
+}
+
+impl From<&[u8]> for Ssid {
+    fn from(value: &[u8]) -> Self {
+        Ssid(value.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for Ssid {
+    fn from(value: Vec<u8>) -> Self {
+        Ssid(value)
+    }
+}
+
+impl From<&DOT11_SSID> for Ssid {
+    fn from(value: &DOT11_SSID) -> Self {
+        let length = (value.uSSIDLength as usize).min(Ssid::MAX_LENGTH);
+        Ssid(value.ucSSID[..length].to_vec())
+    }
+}
+
+/// SSIDs longer than [`Ssid::MAX_LENGTH`] are truncated
+impl From<&Ssid> for DOT11_SSID {
+    fn from(value: &Ssid) -> Self {
+        let length = value.len().min(Ssid::MAX_LENGTH);
+        let mut dot11_ssid = DOT11_SSID {
+            uSSIDLength: length as u32,
+            ucSSID: [0; Ssid::MAX_LENGTH],
+        };
+        dot11_ssid.ucSSID[..length].copy_from_slice(&value.0[..length]);
+        dot11_ssid
+    }
+}
+
+/// Serialized as the hex string used in the `hex` element of a profile
+impl Serialize for Ssid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Ssid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Ssid::from_hex(hex.trim()).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that hex SSIDs are decoded in either case and encoded in uppercase
+    #[test]
+    fn hex_round_trip() {
+        let ssid = Ssid::from_hex("52454441435445440aff").expect("Failed to parse hex SSID");
+
+        assert_eq!(ssid.as_bytes(), b"REDACTED\x0a\xff");
+        assert_eq!(ssid.to_hex(), "52454441435445440AFF");
+        assert_eq!(Ssid::from_hex(&ssid.to_hex()).unwrap(), ssid);
+
+        assert!(Ssid::from_hex("524").is_err());
+        assert!(Ssid::from_hex("zz").is_err());
+    }
+
+    /// Checks that SSIDs which are not valid UTF-8 are kept as is
+    #[test]
+    fn non_utf8_ssid() {
+        let ssid = Ssid::new(vec![b'a', 0xff, b'b']);
+
+        assert_eq!(ssid.to_str(), None);
+        assert_eq!(ssid.to_string(), "a\u{fffd}b");
+        assert_ne!(ssid, Ssid::from("a\u{fffd}b"));
+        assert_eq!(ssid.len(), 3);
+    }
+
+    /// Checks the conversion from the Windows SSID structure
+    #[test]
+    fn from_dot11_ssid() {
+        let mut dot11_ssid = DOT11_SSID {
+            uSSIDLength: 4,
+            ucSSID: [0; 32],
+        };
+        dot11_ssid.ucSSID[..4].copy_from_slice(b"test");
+
+        assert_eq!(Ssid::from(&dot11_ssid), Ssid::from("test"));
+        assert_eq!(DOT11_SSID::from(&Ssid::from("test")), dot11_ssid);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::profiles::info::WlanProfile;
+
 #[macro_export]
 macro_rules! create_test_handle {
     () => {
@@ -7,7 +9,30 @@ macro_rules! create_test_handle {
 
 #[macro_export]
 macro_rules! create_test_interfaces {
-    ($handle:ident, $ifaces:expr) => {
-        ManuallyDrop::new(unsafe { $crate::WlanInterfaces::from_raw_parts($handle, $ifaces) })
-    };
+    ($handle:ident, $ifaces:expr) => {{
+        let handle = $handle;
+        let interface_list_ptr = $ifaces;
+        ManuallyDrop::new(unsafe {
+            $crate::WlanInterfaces::from_raw_parts(handle, interface_list_ptr)
+        })
+    }};
+}
+
+/// Example profiles file with several profile documents
+pub(crate) const TEST_PROFILES: &str = include_str!("../res/profile.xml");
+
+/// Returns the XML document of the example profile with the specified index
+pub(crate) fn example_document(index: usize) -> String {
+    let document = TEST_PROFILES
+        .split("<?xml")
+        .filter(|document| !document.trim().is_empty())
+        .nth(index)
+        .expect("Missing example profile");
+
+    format!("<?xml{document}")
+}
+
+/// Returns the example profile with the specified index
+pub(crate) fn example_profile(index: usize) -> WlanProfile {
+    WlanProfile::from_xml(&example_document(index)).expect("Failed to parse example profile")
 }